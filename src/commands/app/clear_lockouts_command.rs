@@ -0,0 +1,66 @@
+use std::collections::HashMap;
+
+use crate::{
+    command_success,
+    core::commands::{
+        console_command::{CommandResult, ConsoleCommand},
+        console_command_utils::ConsoleIO,
+    },
+    middlewares::{
+        cron_log_middleware::CronLogMiddleware, login_throttle_middleware::LoginThrottleMiddleware,
+    },
+};
+
+use anyhow::Result;
+
+/// a command to clear failed logins and lockouts, for a login (`login=...`), a client IP (`ip=...`) or everything (`all`).
+pub struct ClearLockoutsCommand {
+    cron_log_middleware: CronLogMiddleware,
+    login_throttle_middleware: LoginThrottleMiddleware,
+}
+
+impl ClearLockoutsCommand {
+    pub fn new(
+        cron_log_middleware: CronLogMiddleware,
+        login_throttle_middleware: LoginThrottleMiddleware,
+    ) -> Self {
+        Self {
+            cron_log_middleware,
+            login_throttle_middleware,
+        }
+    }
+}
+
+#[async_trait]
+impl ConsoleCommand for ClearLockoutsCommand {
+    fn get_name(&self) -> String {
+        "app:lockout-clear".into()
+    }
+
+    fn get_cron_middleware(&self) -> &CronLogMiddleware {
+        &self.cron_log_middleware
+    }
+
+    async fn do_run(&self, args: &HashMap<String, Option<String>>) -> Result<CommandResult> {
+        let io = ConsoleIO::new();
+
+        io.title("Login lockouts clearing");
+
+        let cleared = if args.contains_key("all") {
+            self.login_throttle_middleware.clear_all()?
+        } else if let Some(Some(client_ip)) = args.get("ip") {
+            self.login_throttle_middleware.clear_ip(client_ip)?
+        } else {
+            let login = match args.get("login") {
+                Some(Some(login)) => login.clone(),
+                _ => io.ask_question("What is the login to unlock:"),
+            };
+
+            self.login_throttle_middleware.clear_login(&login)?
+        };
+
+        io.success(&format!("{cleared} lockout entries cleared !"));
+
+        command_success!();
+    }
+}
@@ -63,7 +63,7 @@ impl ConsoleCommand for CreateAccountCommand {
             "This command will guide you through account, application and first user creation",
         );
         io.new_line();
-        io.note("First user will be created as a \"standard\" user, meaning you have to use app:elevate-user to promote this user as an administrator role.");
+        io.note("First user will be created as a \"standard\" user, meaning you have to use app:user-role-grant to give this user the ROLE_USER_ADMIN role.");
         io.new_line();
 
         let application_ulid = Ulid::new();
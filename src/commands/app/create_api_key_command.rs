@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+
+use crate::{
+    command_error, command_success,
+    core::commands::{
+        console_command::{CommandResult, ConsoleCommand},
+        console_command_utils::ConsoleIO,
+    },
+    middlewares::{
+        api_key_middleware::ApiKeyMiddleware, application_middleware::ApplicationMiddleware,
+        cron_log_middleware::CronLogMiddleware,
+    },
+};
+
+use anyhow::Result;
+use chrono::{Duration, Utc};
+
+/// a command to create an API key for an application.
+///
+/// Arguments may be given as `application-id=...`, `name=...`, `scopes=subject:right,...` and `expires-in-days=...`,
+/// missing ones are asked for.
+pub struct CreateApiKeyCommand {
+    cron_log_middleware: CronLogMiddleware,
+    application_middleware: ApplicationMiddleware,
+    api_key_middleware: ApiKeyMiddleware,
+}
+
+impl CreateApiKeyCommand {
+    pub fn new(
+        cron_log_middleware: CronLogMiddleware,
+        application_middleware: ApplicationMiddleware,
+        api_key_middleware: ApiKeyMiddleware,
+    ) -> Self {
+        Self {
+            cron_log_middleware,
+            application_middleware,
+            api_key_middleware,
+        }
+    }
+}
+
+#[async_trait]
+impl ConsoleCommand for CreateApiKeyCommand {
+    fn get_name(&self) -> String {
+        "app:api-key-create".into()
+    }
+
+    fn get_cron_middleware(&self) -> &CronLogMiddleware {
+        &self.cron_log_middleware
+    }
+
+    async fn do_run(&self, args: &HashMap<String, Option<String>>) -> Result<CommandResult> {
+        let io = ConsoleIO::new();
+
+        io.title("API key creation");
+
+        let get_arg = |name: &str, question: &str| match args.get(name) {
+            Some(Some(value)) => value.clone(),
+            _ => io.ask_question(question),
+        };
+
+        let application_id = get_arg("application-id", "ID of the application:");
+        let name = get_arg("name", "Name of the API key:");
+        let scopes = get_arg(
+            "scopes",
+            "Scopes of the API key, comma separated (e.g. user:list,account:*):",
+        );
+        let expires_in_days = get_arg(
+            "expires-in-days",
+            "Validity of the API key in days (leave empty for no expiration):",
+        );
+
+        io.new_line();
+
+        let application = self
+            .application_middleware
+            .find_one_by_id(application_id.parse()?)?;
+
+        if application.is_none() {
+            command_error!(&format!("Cannot find an application for {application_id}"));
+        }
+
+        let application = application.unwrap();
+
+        let scopes = scopes
+            .split(',')
+            .map(|scope| scope.trim())
+            .filter(|scope| !scope.is_empty())
+            .collect::<Vec<&str>>();
+
+        let expiration_date = match expires_in_days.trim() {
+            "" => None,
+            days => match days.parse::<i64>() {
+                Ok(days) if days > 0 => Some(Utc::now() + Duration::days(days)),
+                _ => {
+                    command_error!(&format!("Invalid number of days : {days}"));
+                }
+            },
+        };
+
+        let (api_key, clear_key) =
+            self.api_key_middleware
+                .create(&application, &name, scopes, expiration_date)?;
+
+        io.success(&format!(
+            "API key created for application {} !",
+            application.name
+        ));
+        io.warning("The key is displayed only once, store it safely.");
+        io.new_line();
+
+        let data = vec![
+            ("API key ID", api_key.id.to_string()),
+            ("API key name", api_key.name),
+            ("API key scopes", api_key.scopes.join(", ")),
+            (
+                "API key expiration date",
+                api_key
+                    .expiration_date
+                    .map(|date| date.to_rfc3339())
+                    .unwrap_or_else(|| "never".into()),
+            ),
+            ("API key", clear_key),
+        ];
+
+        io.key_value_pair(data);
+
+        command_success!();
+    }
+}
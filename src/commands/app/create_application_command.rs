@@ -61,7 +61,7 @@ impl ConsoleCommand for CreateApplicationCommand {
 
         io.comment("This command will guide you through application and first user creation");
         io.new_line();
-        io.note("First user will be created as a \"standard\" user, meaning you have to use app:elevate-user to promote this user as an administrator role.");
+        io.note("First user will be created as a \"standard\" user, meaning you have to use app:user-role-grant to give this user the ROLE_USER_ADMIN role.");
         io.new_line();
 
         let application_ulid = Ulid::new();
@@ -76,7 +76,9 @@ impl ConsoleCommand for CreateApplicationCommand {
 
         io.step(1, 3, &format!("Gathering account {account_id}..."));
 
-        let account = self.account_middleware.find_one_by_id(&account_id)?;
+        let account = self
+            .account_middleware
+            .find_one_by_id(account_id.parse()?)?;
 
         if account.is_none() {
             command_error!(&format!("Cannot find an account for {account_id}"));
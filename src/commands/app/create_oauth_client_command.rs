@@ -0,0 +1,107 @@
+use std::collections::HashMap;
+
+use crate::{
+    command_error, command_success,
+    core::commands::{
+        console_command::{CommandResult, ConsoleCommand},
+        console_command_utils::ConsoleIO,
+    },
+    middlewares::{
+        application_middleware::ApplicationMiddleware, cron_log_middleware::CronLogMiddleware,
+        oauth_middleware::OAuthMiddleware,
+    },
+};
+
+use anyhow::Result;
+
+/// a command to register an OAuth2 client for an application.
+///
+/// Arguments may be given as `application-id=...`, `name=...` and `scopes=subject:right,...`, missing ones are asked for.
+pub struct CreateOAuthClientCommand {
+    cron_log_middleware: CronLogMiddleware,
+    application_middleware: ApplicationMiddleware,
+    oauth_middleware: OAuthMiddleware,
+}
+
+impl CreateOAuthClientCommand {
+    pub fn new(
+        cron_log_middleware: CronLogMiddleware,
+        application_middleware: ApplicationMiddleware,
+        oauth_middleware: OAuthMiddleware,
+    ) -> Self {
+        Self {
+            cron_log_middleware,
+            application_middleware,
+            oauth_middleware,
+        }
+    }
+}
+
+#[async_trait]
+impl ConsoleCommand for CreateOAuthClientCommand {
+    fn get_name(&self) -> String {
+        "app:oauth-client-create".into()
+    }
+
+    fn get_cron_middleware(&self) -> &CronLogMiddleware {
+        &self.cron_log_middleware
+    }
+
+    async fn do_run(&self, args: &HashMap<String, Option<String>>) -> Result<CommandResult> {
+        let io = ConsoleIO::new();
+
+        io.title("OAuth client creation");
+
+        let get_arg = |name: &str, question: &str| match args.get(name) {
+            Some(Some(value)) => value.clone(),
+            _ => io.ask_question(question),
+        };
+
+        let application_id = get_arg("application-id", "ID of the application:");
+        let name = get_arg("name", "Name of the client:");
+        let scopes = get_arg(
+            "scopes",
+            "Scopes of the client, comma separated (e.g. user:list,account:*):",
+        );
+
+        io.new_line();
+
+        let application = self
+            .application_middleware
+            .find_one_by_id(application_id.parse()?)?;
+
+        if application.is_none() {
+            command_error!(&format!("Cannot find an application for {application_id}"));
+        }
+
+        let application = application.unwrap();
+
+        let scopes = scopes
+            .split(',')
+            .map(|scope| scope.trim())
+            .filter(|scope| !scope.is_empty())
+            .collect::<Vec<&str>>();
+
+        let (client, client_secret) =
+            self.oauth_middleware
+                .create_client(&application, &name, scopes)?;
+
+        io.success(&format!(
+            "OAuth client created for application {} !",
+            application.name
+        ));
+        io.warning("The client secret is displayed only once, store it safely.");
+        io.new_line();
+
+        let data = vec![
+            ("Client name", client.name),
+            ("Client scopes", client.scopes.join(", ")),
+            ("Client ID", client.client_id),
+            ("Client secret", client_secret),
+        ];
+
+        io.key_value_pair(data);
+
+        command_success!();
+    }
+}
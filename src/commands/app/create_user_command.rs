@@ -60,7 +60,7 @@ impl ConsoleCommand for CreateUserCommand {
 
         io.comment("This command will guide you through user creation");
         io.new_line();
-        io.note("First user will be created as a \"standard\" user, meaning you have to use app:elevate-user to promote this user as an administrator role.");
+        io.note("First user will be created as a \"standard\" user, meaning you have to use app:user-role-grant to give this user the ROLE_USER_ADMIN role.");
         io.new_line();
 
         let user_password = password::generate_sized(16);
@@ -73,7 +73,9 @@ impl ConsoleCommand for CreateUserCommand {
 
         io.step(1, 3, &format!("Gathering account {account_id}..."));
 
-        let account = self.account_middleware.find_one_by_id(&account_id)?;
+        let account = self
+            .account_middleware
+            .find_one_by_id(account_id.parse()?)?;
 
         if account.is_none() {
             command_error!(&format!("Cannot find an account for {account_id}"));
@@ -84,7 +86,7 @@ impl ConsoleCommand for CreateUserCommand {
         io.step(2, 3, &format!("Gathering application {application_id}..."));
         let application = self
             .application_middleware
-            .find_one_by_id(&application_id)?;
+            .find_one_by_id(application_id.parse()?)?;
 
         if application.is_none() {
             command_error!(&format!("Cannot find an application for {application_id}"));
@@ -0,0 +1,68 @@
+use std::{collections::HashMap, fs};
+
+use crate::{
+    command_success,
+    core::{
+        commands::{
+            console_command::{CommandResult, ConsoleCommand},
+            console_command_utils::ConsoleIO,
+        },
+        openapi::OpenApiDocument,
+    },
+    middlewares::cron_log_middleware::CronLogMiddleware,
+};
+
+use anyhow::Result;
+
+/// Default file the OpenAPI document is written to.
+const DEFAULT_OUTPUT: &str = "openapi.json";
+
+/// a command to write the OpenAPI document of the API to a file (e.g. to diff it in CI).
+///
+/// The file is set with the `output` argument, defaulting to `openapi.json`.
+pub struct ExportOpenApiCommand {
+    cron_log_middleware: CronLogMiddleware,
+    document: OpenApiDocument,
+}
+
+impl ExportOpenApiCommand {
+    pub fn new(cron_log_middleware: CronLogMiddleware, document: OpenApiDocument) -> Self {
+        Self {
+            cron_log_middleware,
+            document,
+        }
+    }
+}
+
+#[async_trait]
+impl ConsoleCommand for ExportOpenApiCommand {
+    fn get_name(&self) -> String {
+        "app:openapi-export".into()
+    }
+
+    fn get_cron_middleware(&self) -> &CronLogMiddleware {
+        &self.cron_log_middleware
+    }
+
+    async fn do_run(&self, args: &HashMap<String, Option<String>>) -> Result<CommandResult> {
+        let io = ConsoleIO::new();
+
+        io.title("OpenAPI export");
+
+        let output = match args.get("output") {
+            Some(Some(output)) => output.clone(),
+            _ => DEFAULT_OUTPUT.into(),
+        };
+
+        // a trailing new line keeps the file friendly to diff tools.
+        let content = format!("{}\n", serde_json::to_string_pretty(&self.document.0)?);
+
+        io.step(1, 1, &format!("Writing OpenAPI document to {output}..."));
+        fs::write(&output, content)?;
+
+        io.new_line();
+        io.success(&format!("OpenAPI document written to {output} !"));
+
+        command_success!();
+    }
+}
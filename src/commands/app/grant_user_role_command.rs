@@ -0,0 +1,102 @@
+use std::collections::HashMap;
+
+use crate::{
+    command_error, command_success,
+    core::commands::{
+        console_command::{CommandResult, ConsoleCommand},
+        console_command_utils::ConsoleIO,
+    },
+    middlewares::{
+        cron_log_middleware::CronLogMiddleware, role_middleware::RoleMiddleware,
+        user_middleware::UserMiddleware,
+    },
+};
+
+use anyhow::Result;
+
+/// a command to grant a role (e.g. `ROLE_USER_ADMIN`) to a user.
+pub struct GrantUserRoleCommand {
+    cron_log_middleware: CronLogMiddleware,
+    user_middleware: UserMiddleware,
+    role_middleware: RoleMiddleware,
+}
+
+impl GrantUserRoleCommand {
+    pub fn new(
+        cron_log_middleware: CronLogMiddleware,
+        user_middleware: UserMiddleware,
+        role_middleware: RoleMiddleware,
+    ) -> Self {
+        Self {
+            cron_log_middleware,
+            user_middleware,
+            role_middleware,
+        }
+    }
+}
+
+#[async_trait]
+impl ConsoleCommand for GrantUserRoleCommand {
+    fn get_name(&self) -> String {
+        "app:user-role-grant".into()
+    }
+
+    fn get_cron_middleware(&self) -> &CronLogMiddleware {
+        &self.cron_log_middleware
+    }
+
+    async fn do_run(&self, args: &HashMap<String, Option<String>>) -> Result<CommandResult> {
+        let io = ConsoleIO::new();
+
+        io.title("User role grant");
+
+        io.comment("This command will guide you through granting a role to a user.");
+        io.new_line();
+
+        let user_id = if args.contains_key("user-id") {
+            args.get("user-id").unwrap().as_ref().unwrap().clone()
+        } else {
+            io.ask_question("What is the user ID:")
+        };
+
+        if user_id.parse::<i32>().is_err() {
+            command_error!(&format!("{user_id} is not a valid user ID."));
+        }
+
+        let role = if args.contains_key("role") {
+            args.get("role").unwrap().as_ref().unwrap().clone()
+        } else {
+            io.ask_question("What is the role to grant (e.g. ROLE_USER_ADMIN):")
+        };
+
+        io.step(1, 2, "Fetching user {user_id}...");
+
+        let user = self.user_middleware.find_one_by_id(&user_id)?;
+
+        if user.is_none() {
+            command_error!(&format!("User ID {user_id} is not found."));
+        }
+
+        let user = user.unwrap();
+
+        if !self
+            .role_middleware
+            .is_defined(user.application_id, &role)?
+        {
+            command_error!(&format!(
+                "Role {role} is not defined for application {}.",
+                user.application_id
+            ));
+        }
+
+        io.step(2, 2, "Granting role {role}...");
+        let user = self.user_middleware.promote(&user, &role)?;
+
+        io.success(&format!(
+            "Role {role} successfully granted to {} !",
+            user.login
+        ));
+
+        command_success!();
+    }
+}
@@ -0,0 +1,112 @@
+use std::collections::HashMap;
+
+use crate::{
+    command_success,
+    core::commands::{
+        console_command::{CommandResult, ConsoleCommand},
+        console_command_utils::ConsoleIO,
+    },
+    middlewares::{api_key_middleware::ApiKeyMiddleware, cron_log_middleware::CronLogMiddleware},
+};
+
+use anyhow::Result;
+use chrono::Utc;
+
+/// a command to list API keys, optionally for a single application (`application-id=...`).
+pub struct ListApiKeysCommand {
+    cron_log_middleware: CronLogMiddleware,
+    api_key_middleware: ApiKeyMiddleware,
+}
+
+impl ListApiKeysCommand {
+    pub fn new(
+        cron_log_middleware: CronLogMiddleware,
+        api_key_middleware: ApiKeyMiddleware,
+    ) -> Self {
+        Self {
+            cron_log_middleware,
+            api_key_middleware,
+        }
+    }
+}
+
+#[async_trait]
+impl ConsoleCommand for ListApiKeysCommand {
+    fn get_name(&self) -> String {
+        "app:api-key-list".into()
+    }
+
+    fn get_cron_middleware(&self) -> &CronLogMiddleware {
+        &self.cron_log_middleware
+    }
+
+    async fn do_run(&self, args: &HashMap<String, Option<String>>) -> Result<CommandResult> {
+        let io = ConsoleIO::new();
+
+        io.title("API keys");
+
+        let application_id = match args.get("application-id") {
+            Some(Some(application_id)) => Some(application_id.parse::<i32>()?),
+            _ => None,
+        };
+
+        let api_keys = self.api_key_middleware.find_all(application_id)?;
+
+        if api_keys.is_empty() {
+            io.success("No API key found.");
+
+            command_success!();
+        }
+
+        let rows = api_keys
+            .iter()
+            .map(|api_key| {
+                let status = if api_key.is_revoked {
+                    "revoked"
+                } else if api_key
+                    .expiration_date
+                    .is_some_and(|date| date < Utc::now())
+                {
+                    "expired"
+                } else {
+                    "active"
+                };
+
+                vec![
+                    api_key.id.to_string(),
+                    api_key.application_id.to_string(),
+                    api_key.name.clone(),
+                    api_key.prefix.clone(),
+                    api_key.scopes.join(", "),
+                    api_key
+                        .expiration_date
+                        .map(|date| date.to_rfc3339())
+                        .unwrap_or_else(|| "-".into()),
+                    api_key
+                        .last_used_date
+                        .map(|date| date.to_rfc3339())
+                        .unwrap_or_else(|| "-".into()),
+                    status.into(),
+                ]
+            })
+            .collect::<Vec<Vec<String>>>();
+
+        io.table(
+            vec![
+                "ID",
+                "Application",
+                "Name",
+                "Prefix",
+                "Scopes",
+                "Expires",
+                "Last used",
+                "Status",
+            ],
+            rows.iter()
+                .map(|row| row.iter().map(|cell| cell.as_str()).collect())
+                .collect(),
+        );
+
+        command_success!();
+    }
+}
@@ -0,0 +1,88 @@
+use std::collections::HashMap;
+
+use crate::{
+    command_success,
+    core::commands::{
+        console_command::{CommandResult, ConsoleCommand},
+        console_command_utils::ConsoleIO,
+    },
+    middlewares::{
+        cron_log_middleware::CronLogMiddleware, login_throttle_middleware::LoginThrottleMiddleware,
+    },
+};
+
+use anyhow::Result;
+
+/// a command to list logins and client IPs currently locked after failed logins.
+pub struct ListLockoutsCommand {
+    cron_log_middleware: CronLogMiddleware,
+    login_throttle_middleware: LoginThrottleMiddleware,
+}
+
+impl ListLockoutsCommand {
+    pub fn new(
+        cron_log_middleware: CronLogMiddleware,
+        login_throttle_middleware: LoginThrottleMiddleware,
+    ) -> Self {
+        Self {
+            cron_log_middleware,
+            login_throttle_middleware,
+        }
+    }
+}
+
+#[async_trait]
+impl ConsoleCommand for ListLockoutsCommand {
+    fn get_name(&self) -> String {
+        "app:lockout-list".into()
+    }
+
+    fn get_cron_middleware(&self) -> &CronLogMiddleware {
+        &self.cron_log_middleware
+    }
+
+    async fn do_run(&self, _args: &HashMap<String, Option<String>>) -> Result<CommandResult> {
+        let io = ConsoleIO::new();
+
+        io.title("Login lockouts");
+
+        let lockouts = self.login_throttle_middleware.find_all_locked()?;
+
+        if lockouts.is_empty() {
+            io.success("No login nor client IP is currently locked.");
+
+            command_success!();
+        }
+
+        let rows = lockouts
+            .iter()
+            .map(|lockout| {
+                vec![
+                    lockout.scope.clone(),
+                    lockout.identifier.clone(),
+                    lockout.failure_count.to_string(),
+                    lockout.last_failure_date.to_rfc3339(),
+                    lockout
+                        .locked_until
+                        .map(|date| date.to_rfc3339())
+                        .unwrap_or_else(|| "-".into()),
+                ]
+            })
+            .collect::<Vec<Vec<String>>>();
+
+        io.table(
+            vec![
+                "Scope",
+                "Identifier",
+                "Failures",
+                "Last failure",
+                "Locked until",
+            ],
+            rows.iter()
+                .map(|row| row.iter().map(|cell| cell.as_str()).collect())
+                .collect(),
+        );
+
+        command_success!();
+    }
+}
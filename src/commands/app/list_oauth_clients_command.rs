@@ -0,0 +1,91 @@
+use std::collections::HashMap;
+
+use crate::{
+    command_success,
+    core::commands::{
+        console_command::{CommandResult, ConsoleCommand},
+        console_command_utils::ConsoleIO,
+    },
+    middlewares::{cron_log_middleware::CronLogMiddleware, oauth_middleware::OAuthMiddleware},
+};
+
+use anyhow::Result;
+
+/// a command to list OAuth2 clients, optionally for a single application (`application-id=...`).
+pub struct ListOAuthClientsCommand {
+    cron_log_middleware: CronLogMiddleware,
+    oauth_middleware: OAuthMiddleware,
+}
+
+impl ListOAuthClientsCommand {
+    pub fn new(cron_log_middleware: CronLogMiddleware, oauth_middleware: OAuthMiddleware) -> Self {
+        Self {
+            cron_log_middleware,
+            oauth_middleware,
+        }
+    }
+}
+
+#[async_trait]
+impl ConsoleCommand for ListOAuthClientsCommand {
+    fn get_name(&self) -> String {
+        "app:oauth-client-list".into()
+    }
+
+    fn get_cron_middleware(&self) -> &CronLogMiddleware {
+        &self.cron_log_middleware
+    }
+
+    async fn do_run(&self, args: &HashMap<String, Option<String>>) -> Result<CommandResult> {
+        let io = ConsoleIO::new();
+
+        io.title("OAuth clients");
+
+        let application_id = match args.get("application-id") {
+            Some(Some(application_id)) => Some(application_id.parse::<i32>()?),
+            _ => None,
+        };
+
+        let clients = self.oauth_middleware.find_all_clients(application_id)?;
+
+        if clients.is_empty() {
+            io.success("No OAuth client found.");
+
+            command_success!();
+        }
+
+        let rows = clients
+            .iter()
+            .map(|client| {
+                vec![
+                    client.client_id.clone(),
+                    client.application_id.to_string(),
+                    client.name.clone(),
+                    client.scopes.join(", "),
+                    client.created_date.to_rfc3339(),
+                    if client.is_revoked {
+                        "revoked".into()
+                    } else {
+                        "active".into()
+                    },
+                ]
+            })
+            .collect::<Vec<Vec<String>>>();
+
+        io.table(
+            vec![
+                "Client ID",
+                "Application",
+                "Name",
+                "Scopes",
+                "Created",
+                "Status",
+            ],
+            rows.iter()
+                .map(|row| row.iter().map(|cell| cell.as_str()).collect())
+                .collect(),
+        );
+
+        command_success!();
+    }
+}
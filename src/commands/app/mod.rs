@@ -1,5 +1,17 @@
+pub mod clear_lockouts_command;
 pub mod create_account_command;
+pub mod create_api_key_command;
 pub mod create_application_command;
+pub mod create_oauth_client_command;
 pub mod create_user_command;
-pub mod demote_user_command;
-pub mod promote_user_command;
+pub mod export_openapi_command;
+pub mod grant_user_role_command;
+pub mod list_api_keys_command;
+pub mod list_lockouts_command;
+pub mod list_oauth_clients_command;
+pub mod permission_matrix_command;
+pub mod purge_jwt_denylist_command;
+pub mod revoke_api_key_command;
+pub mod revoke_oauth_client_command;
+pub mod revoke_user_role_command;
+pub mod rotate_jwt_keys_command;
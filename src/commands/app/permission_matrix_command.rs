@@ -0,0 +1,102 @@
+use std::collections::HashMap;
+
+use crate::{
+    command_success,
+    core::{
+        commands::{
+            console_command::{CommandResult, ConsoleCommand},
+            console_command_utils::ConsoleIO,
+        },
+        role_hierarchy::RoleHierarchy,
+        security::{AccessDecision, AccessError, Security, SecurityVoter},
+    },
+    domain::model::user::User,
+    middlewares::cron_log_middleware::CronLogMiddleware,
+};
+
+use anyhow::Result;
+
+/// a command to print the effective permission matrix, e.g. which built-in role is granted each right on each subject.
+pub struct PermissionMatrixCommand {
+    cron_log_middleware: CronLogMiddleware,
+    security: Security<'static, dyn SecurityVoter<'static>>,
+    role_hierarchy: RoleHierarchy,
+}
+
+impl PermissionMatrixCommand {
+    pub fn new(
+        cron_log_middleware: CronLogMiddleware,
+        security: Security<'static, dyn SecurityVoter<'static>>,
+        role_hierarchy: RoleHierarchy,
+    ) -> Self {
+        Self {
+            cron_log_middleware,
+            security,
+            role_hierarchy,
+        }
+    }
+}
+
+#[async_trait]
+impl ConsoleCommand for PermissionMatrixCommand {
+    fn get_name(&self) -> String {
+        "app:permission-matrix".into()
+    }
+
+    fn get_cron_middleware(&self) -> &CronLogMiddleware {
+        &self.cron_log_middleware
+    }
+
+    async fn do_run(&self, _args: &HashMap<String, Option<String>>) -> Result<CommandResult> {
+        let io = ConsoleIO::new();
+
+        io.title("Permission matrix");
+
+        let role_names = self.role_hierarchy.get_role_names();
+
+        // each role is checked with a user having this role, and every role it implies.
+        let users = role_names
+            .iter()
+            .map(|role_name| User {
+                roles: self.role_hierarchy.resolve(std::slice::from_ref(role_name)),
+                ..Default::default()
+            })
+            .collect::<Vec<User>>();
+
+        let mut rows = Vec::<Vec<String>>::new();
+
+        for (subject, rights) in self.security.get_subjects() {
+            for right in rights {
+                let mut row = vec![subject.to_string(), right.to_string()];
+
+                for user in users.iter() {
+                    let cell = match self.security.has_access(subject, right, user, None) {
+                        Ok(AccessDecision::Granted) => "yes",
+                        Ok(AccessDecision::Denied(_)) => "no",
+                        // the decision needs a context (e.g. the resource owner).
+                        Err(AccessError::MissingContext(_)) => "context",
+                        Err(_) => "error",
+                    };
+
+                    row.push(cell.into());
+                }
+
+                rows.push(row);
+            }
+        }
+
+        let mut headers = vec!["Subject", "Right"];
+        headers.extend(role_names.iter().map(|role_name| role_name.as_str()));
+
+        io.table(
+            headers,
+            rows.iter()
+                .map(|row| row.iter().map(|cell| cell.as_str()).collect())
+                .collect(),
+        );
+
+        io.note("\"context\" means access depends on the checked resource (e.g. its owner or application).");
+
+        command_success!();
+    }
+}
@@ -0,0 +1,56 @@
+use std::collections::HashMap;
+
+use crate::{
+    command_success,
+    core::commands::{
+        console_command::{CommandResult, ConsoleCommand},
+        console_command_utils::ConsoleIO,
+    },
+    middlewares::{
+        cron_log_middleware::CronLogMiddleware, jwt_denylist_middleware::JWTDenylistMiddleware,
+    },
+};
+
+use anyhow::Result;
+
+/// a command to purge expired entries of the JWT denylist, intended to be run as a cron.
+pub struct PurgeJWTDenylistCommand {
+    cron_log_middleware: CronLogMiddleware,
+    jwt_denylist_middleware: JWTDenylistMiddleware,
+}
+
+impl PurgeJWTDenylistCommand {
+    pub fn new(
+        cron_log_middleware: CronLogMiddleware,
+        jwt_denylist_middleware: JWTDenylistMiddleware,
+    ) -> Self {
+        Self {
+            cron_log_middleware,
+            jwt_denylist_middleware,
+        }
+    }
+}
+
+#[async_trait]
+impl ConsoleCommand for PurgeJWTDenylistCommand {
+    fn get_name(&self) -> String {
+        "app:jwt-denylist-purge".into()
+    }
+
+    fn get_cron_middleware(&self) -> &CronLogMiddleware {
+        &self.cron_log_middleware
+    }
+
+    async fn do_run(&self, _args: &HashMap<String, Option<String>>) -> Result<CommandResult> {
+        let io = ConsoleIO::new();
+
+        io.title("JWT denylist purge");
+
+        io.step(1, 1, "Purging expired JWT denylist entries...");
+        let purged = self.jwt_denylist_middleware.purge_expired()?;
+
+        io.success(&format!("{purged} expired entries purged !"));
+
+        command_success!();
+    }
+}
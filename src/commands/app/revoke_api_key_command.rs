@@ -0,0 +1,60 @@
+use std::collections::HashMap;
+
+use crate::{
+    command_error, command_success,
+    core::commands::{
+        console_command::{CommandResult, ConsoleCommand},
+        console_command_utils::ConsoleIO,
+    },
+    middlewares::{api_key_middleware::ApiKeyMiddleware, cron_log_middleware::CronLogMiddleware},
+};
+
+use anyhow::Result;
+
+/// a command to revoke an API key by its ID (`id=...`).
+pub struct RevokeApiKeyCommand {
+    cron_log_middleware: CronLogMiddleware,
+    api_key_middleware: ApiKeyMiddleware,
+}
+
+impl RevokeApiKeyCommand {
+    pub fn new(
+        cron_log_middleware: CronLogMiddleware,
+        api_key_middleware: ApiKeyMiddleware,
+    ) -> Self {
+        Self {
+            cron_log_middleware,
+            api_key_middleware,
+        }
+    }
+}
+
+#[async_trait]
+impl ConsoleCommand for RevokeApiKeyCommand {
+    fn get_name(&self) -> String {
+        "app:api-key-revoke".into()
+    }
+
+    fn get_cron_middleware(&self) -> &CronLogMiddleware {
+        &self.cron_log_middleware
+    }
+
+    async fn do_run(&self, args: &HashMap<String, Option<String>>) -> Result<CommandResult> {
+        let io = ConsoleIO::new();
+
+        io.title("API key revocation");
+
+        let id = match args.get("id") {
+            Some(Some(id)) => id.clone(),
+            _ => io.ask_question("ID of the API key to revoke:"),
+        };
+
+        if !self.api_key_middleware.revoke(&id)? {
+            command_error!(&format!("Cannot find an active API key for {id}"));
+        }
+
+        io.success(&format!("API key {id} revoked !"));
+
+        command_success!();
+    }
+}
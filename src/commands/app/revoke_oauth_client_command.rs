@@ -0,0 +1,60 @@
+use std::collections::HashMap;
+
+use crate::{
+    command_error, command_success,
+    core::commands::{
+        console_command::{CommandResult, ConsoleCommand},
+        console_command_utils::ConsoleIO,
+    },
+    middlewares::{cron_log_middleware::CronLogMiddleware, oauth_middleware::OAuthMiddleware},
+};
+
+use anyhow::Result;
+
+/// a command to revoke an OAuth2 client by its client ID (`client-id=...`).
+pub struct RevokeOAuthClientCommand {
+    cron_log_middleware: CronLogMiddleware,
+    oauth_middleware: OAuthMiddleware,
+}
+
+impl RevokeOAuthClientCommand {
+    pub fn new(cron_log_middleware: CronLogMiddleware, oauth_middleware: OAuthMiddleware) -> Self {
+        Self {
+            cron_log_middleware,
+            oauth_middleware,
+        }
+    }
+}
+
+#[async_trait]
+impl ConsoleCommand for RevokeOAuthClientCommand {
+    fn get_name(&self) -> String {
+        "app:oauth-client-revoke".into()
+    }
+
+    fn get_cron_middleware(&self) -> &CronLogMiddleware {
+        &self.cron_log_middleware
+    }
+
+    async fn do_run(&self, args: &HashMap<String, Option<String>>) -> Result<CommandResult> {
+        let io = ConsoleIO::new();
+
+        io.title("OAuth client revocation");
+
+        let client_id = match args.get("client-id") {
+            Some(Some(client_id)) => client_id.clone(),
+            _ => io.ask_question("ID of the client to revoke:"),
+        };
+
+        if !self.oauth_middleware.revoke_client(&client_id)? {
+            command_error!(&format!(
+                "Cannot find an active OAuth client for {client_id}"
+            ));
+        }
+
+        io.success(&format!("OAuth client {client_id} revoked !"));
+        io.note("Access tokens already issued to the client are now reported as inactive by introspection.");
+
+        command_success!();
+    }
+}
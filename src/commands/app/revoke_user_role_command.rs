@@ -11,13 +11,13 @@ use crate::{
 
 use anyhow::Result;
 
-/// a command to demote (e.g. removing administration role) to a user.
-pub struct DemoteUserCommand {
+/// a command to revoke a role (e.g. `ROLE_USER_ADMIN`) from a user.
+pub struct RevokeUserRoleCommand {
     cron_log_middleware: CronLogMiddleware,
     user_middleware: UserMiddleware,
 }
 
-impl DemoteUserCommand {
+impl RevokeUserRoleCommand {
     pub fn new(cron_log_middleware: CronLogMiddleware, user_middleware: UserMiddleware) -> Self {
         Self {
             cron_log_middleware,
@@ -27,9 +27,9 @@ impl DemoteUserCommand {
 }
 
 #[async_trait]
-impl ConsoleCommand for DemoteUserCommand {
+impl ConsoleCommand for RevokeUserRoleCommand {
     fn get_name(&self) -> String {
-        "app:user-demotion".into()
+        "app:user-role-revoke".into()
     }
 
     fn get_cron_middleware(&self) -> &CronLogMiddleware {
@@ -39,21 +39,27 @@ impl ConsoleCommand for DemoteUserCommand {
     async fn do_run(&self, args: &HashMap<String, Option<String>>) -> Result<CommandResult> {
         let io = ConsoleIO::new();
 
-        io.title("User demotion");
+        io.title("User role revocation");
 
-        io.comment("This command will guide you through user demotion from administrator.");
+        io.comment("This command will guide you through revokeing a role from a user.");
         io.new_line();
 
         let user_id = if args.contains_key("user-id") {
             args.get("user-id").unwrap().as_ref().unwrap().clone()
         } else {
-            io.ask_question("What is the user ID to demote:")
+            io.ask_question("What is the user ID:")
         };
 
         if user_id.parse::<i32>().is_err() {
             command_error!(&format!("{user_id} is not a valid user ID."));
         }
 
+        let role = if args.contains_key("role") {
+            args.get("role").unwrap().as_ref().unwrap().clone()
+        } else {
+            io.ask_question("What is the role to revoke (e.g. ROLE_USER_ADMIN):")
+        };
+
         io.step(1, 2, "Fetching user {user_id}...");
 
         let user = self.user_middleware.find_one_by_id(&user_id)?;
@@ -64,10 +70,13 @@ impl ConsoleCommand for DemoteUserCommand {
 
         let user = user.unwrap();
 
-        io.step(2, 2, "Demoting user {user_id}...");
-        let user = self.user_middleware.demote(&user)?;
+        io.step(2, 2, "Revoking role {role}...");
+        let user = self.user_middleware.demote(&user, &role)?;
 
-        io.success(&format!("User {} successfully demoted !", user.login));
+        io.success(&format!(
+            "Role {role} successfully revoked from {} !",
+            user.login
+        ));
 
         command_success!();
     }
@@ -0,0 +1,98 @@
+use std::collections::HashMap;
+
+use crate::{
+    command_success,
+    core::{
+        commands::{
+            console_command::{CommandResult, ConsoleCommand},
+            console_command_utils::ConsoleIO,
+        },
+        configuration::ConfigState,
+        jwt_keys::{self, JWTAlgorithm, JWTKeyStore},
+    },
+    middlewares::cron_log_middleware::CronLogMiddleware,
+};
+
+use anyhow::Result;
+
+/// a command to rotate the JWT signing key.
+///
+/// Previous signing keys are kept to verify tokens until every token they signed has expired.
+pub struct RotateJWTKeysCommand {
+    cron_log_middleware: CronLogMiddleware,
+    jwt_key_store: JWTKeyStore,
+    configuration: ConfigState,
+}
+
+impl RotateJWTKeysCommand {
+    pub fn new(
+        cron_log_middleware: CronLogMiddleware,
+        jwt_key_store: JWTKeyStore,
+        configuration: ConfigState,
+    ) -> Self {
+        Self {
+            cron_log_middleware,
+            jwt_key_store,
+            configuration,
+        }
+    }
+}
+
+#[async_trait]
+impl ConsoleCommand for RotateJWTKeysCommand {
+    fn get_name(&self) -> String {
+        "app:jwt-keys-rotate".into()
+    }
+
+    fn get_cron_middleware(&self) -> &CronLogMiddleware {
+        &self.cron_log_middleware
+    }
+
+    async fn do_run(&self, _args: &HashMap<String, Option<String>>) -> Result<CommandResult> {
+        let io = ConsoleIO::new();
+
+        io.title("JWT keys rotation");
+
+        let jwt_ttl = self.configuration.get_int_or_default("jwt_ttl", 3600);
+        let algorithm = JWTAlgorithm::from_config(&self.configuration)?;
+
+        io.step(
+            1,
+            1,
+            &format!("Generating a new {algorithm} signing key..."),
+        );
+        let key = jwt_keys::rotate(algorithm, jwt_ttl)?;
+        // other processes pick the new key up on their next use, as the manifest changed.
+        self.jwt_key_store.reload()?;
+
+        io.new_line();
+        io.success(&format!("Key {} is now the signing key !", key.kid));
+        io.new_line();
+
+        let manifest = jwt_keys::JWTKeyManifest::load()?;
+
+        let rows = manifest
+            .keys
+            .iter()
+            .map(|key| {
+                vec![
+                    key.kid.clone(),
+                    key.alg.to_string(),
+                    key.created_at.to_rfc3339(),
+                    key.expires_at
+                        .map(|date| date.to_rfc3339())
+                        .unwrap_or_else(|| "-".into()),
+                ]
+            })
+            .collect::<Vec<Vec<String>>>();
+
+        io.table(
+            vec!["Key ID", "Algorithm", "Created at", "Verifies tokens until"],
+            rows.iter()
+                .map(|row| row.iter().map(|cell| cell.as_str()).collect())
+                .collect(),
+        );
+
+        command_success!();
+    }
+}
@@ -594,6 +594,14 @@ use crate::security::voters::{module_name}_security::{module_name_ucfirst}Securi
             factory_content.replace("                // __CONTROLLERS__", &controllers_insert);
         // --
 
+        // -- openapi --
+        let openapi_insert = format!(
+            "    {module_name}::document(&mut openapi, \"/api\");
+    // __OPENAPI__"
+        );
+        let factory_content = factory_content.replace("    // __OPENAPI__", &openapi_insert);
+        // --
+
         // -- manage --
         let manage_insert = format!(
             ".manage({module_name}_middleware)
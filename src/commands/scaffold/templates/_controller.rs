@@ -4,33 +4,39 @@ use crate::core::validation::Validated;
 use crate::extract_message;
 use crate::{
     core::{
-        guards::{connected_user::ConnectedUser, pagination::Pagination},
-        response::{ApiResponse, NoContentResponse},
-        security::{Security, SecurityVoter},
+        guards::{
+            granted::{CreateRight, DeleteRight, DetailsRight, Granted, ListRight, UpdateRight},
+            list_query::ListQuery,
+            pagination::Pagination,
+        },
+        openapi::{OpenApi, OpenApiOperation},
+        response::{ApiResponse, NoContentResponse, Paginated},
     },
-    deny_access_unless_granted,
     domain::dto::__MODULE_NAME__::{
         New__DATA_CLASS_STRUCT_NAME__InputDTO, Update__DATA_CLASS_STRUCT_NAME__InputDTO,
         __DATA_CLASS_STRUCT_NAME__DetailsDTO, __DATA_CLASS_STRUCT_NAME__ListItemDTO,
     },
     exceptions::dto::http_exception::HttpException,
-    http_exception, http_no_content, http_ok,
+    http_exception, http_no_content, http_ok, http_paginated,
     middlewares::__MODULE_NAME___middleware::__DATA_CLASS_STRUCT_NAME__Middleware,
+    security::voters::__MODULE_NAME___security::__DATA_CLASS_STRUCT_NAME__Subject,
 };
 
 #[get("/__MODULE_NAME__s", format = "json")]
 pub fn __MODULE_NAME___list(
-    connected_user: ConnectedUser,
+    granted: Granted<__DATA_CLASS_STRUCT_NAME__Subject, ListRight>,
     __MODULE_NAME___middleware: &State<__DATA_CLASS_STRUCT_NAME__Middleware>,
-    security: &State<Security<dyn SecurityVoter>>,
     pagination: Pagination,
-) -> Result<ApiResponse<Vec<__DATA_CLASS_STRUCT_NAME__ListItemDTO>>, ApiResponse<HttpException>> {
-    let user = &connected_user.user;
-
-    deny_access_unless_granted!(security, user, "__MODULE_NAME__", "list");
+    list_query: ListQuery<__DATA_CLASS_STRUCT_NAME__ListItemDTO>,
+) -> Result<
+    ApiResponse<Paginated<__DATA_CLASS_STRUCT_NAME__ListItemDTO>>,
+    ApiResponse<HttpException>,
+> {
+    let user = &granted.user;
 
     let list = __MODULE_NAME___middleware.find_for_user(
         user,
+        &list_query,
         pagination.page.into(),
         pagination.per_page.into(),
     );
@@ -39,23 +45,24 @@ pub fn __MODULE_NAME___list(
         http_exception!(Status::InternalServerError, &extract_message!(list));
     }
 
-    let list = list.unwrap();
+    let total = __MODULE_NAME___middleware.count_for_user(user, &list_query);
+
+    if total.is_err() {
+        http_exception!(Status::InternalServerError, &extract_message!(total));
+    }
 
-    let dto_list = __MODULE_NAME___middleware.to_list_dto(list);
+    let page = Paginated::new(list.unwrap(), &pagination, total.unwrap());
 
-    http_ok!(dto_list);
+    http_paginated!(page.map(|list| __MODULE_NAME___middleware.to_list_dto(list)));
 }
 
 #[get("/__MODULE_NAME__s/<id>", format = "json")]
 pub fn __MODULE_NAME___details(
     id: String,
-    connected_user: ConnectedUser,
+    granted: Granted<__DATA_CLASS_STRUCT_NAME__Subject, DetailsRight>,
     __MODULE_NAME___middleware: &State<__DATA_CLASS_STRUCT_NAME__Middleware>,
-    security: &State<Security<dyn SecurityVoter>>,
 ) -> Result<ApiResponse<__DATA_CLASS_STRUCT_NAME__DetailsDTO>, ApiResponse<HttpException>> {
-    let user = &connected_user.user;
-
-    deny_access_unless_granted!(security, user, "__MODULE_NAME___", "details");
+    let user = &granted.user;
 
     let __MODULE_NAME__ = __MODULE_NAME___middleware.find_one_for_user(&id, user);
 
@@ -81,13 +88,10 @@ pub fn __MODULE_NAME___details(
 #[post("/__MODULE_NAME__s", format = "json", data = "<input>")]
 pub fn __MODULE_NAME___create(
     input: Validated<Json<New__DATA_CLASS_STRUCT_NAME__InputDTO>>,
-    connected_user: ConnectedUser,
+    granted: Granted<__DATA_CLASS_STRUCT_NAME__Subject, CreateRight>,
     __MODULE_NAME___middleware: &State<__DATA_CLASS_STRUCT_NAME__Middleware>,
-    security: &State<Security<dyn SecurityVoter>>,
 ) -> Result<ApiResponse<__DATA_CLASS_STRUCT_NAME__DetailsDTO>, ApiResponse<HttpException>> {
-    let creator = &connected_user.user;
-
-    deny_access_unless_granted!(security, creator, "__MODULE_NAME__", "create");
+    let creator = &granted.user;
 
     let dto = input.into_deep_inner();
 
@@ -114,15 +118,12 @@ pub fn __MODULE_NAME___create(
 pub fn __MODULE_NAME___update(
     id: i32,
     input: Validated<Json<Update__DATA_CLASS_STRUCT_NAME__InputDTO>>,
-    connected_user: ConnectedUser,
+    granted: Granted<__DATA_CLASS_STRUCT_NAME__Subject, UpdateRight>,
     __MODULE_NAME___middleware: &State<__DATA_CLASS_STRUCT_NAME__Middleware>,
-    security: &State<Security<dyn SecurityVoter>>,
 ) -> Result<ApiResponse<__DATA_CLASS_STRUCT_NAME__DetailsDTO>, ApiResponse<HttpException>> {
-    let updater = &connected_user.user;
+    let updater = &granted.user;
     let updated_id = format!("{id}");
 
-    deny_access_unless_granted!(security, updater, "__MODULE_NAME___", "update");
-
     let to_update = __MODULE_NAME___middleware.find_one_for_user(&updated_id, updater);
 
     if to_update.is_err() {
@@ -163,13 +164,10 @@ pub fn __MODULE_NAME___update(
 #[delete("/__MODULE_NAME__s/<id>", format = "json")]
 pub fn __MODULE_NAME___delete(
     id: i32,
-    connected_user: ConnectedUser,
+    granted: Granted<__DATA_CLASS_STRUCT_NAME__Subject, DeleteRight>,
     __MODULE_NAME___middleware: &State<__DATA_CLASS_STRUCT_NAME__Middleware>,
-    security: &State<Security<dyn SecurityVoter>>,
 ) -> Result<ApiResponse<NoContentResponse>, ApiResponse<HttpException>> {
-    let deleter = &connected_user.user;
-
-    deny_access_unless_granted!(security, deleter, "__MODULE_NAME__", "delete");
+    let deleter = &granted.user;
 
     let parsed_id = format!("{id}");
 
@@ -205,3 +203,44 @@ pub fn __MODULE_NAME___delete(
 
     http_no_content!()
 }
+
+/// documents the routes of this controller, mounted on `base`.
+pub fn document(openapi: &mut OpenApi, base: &str) {
+    openapi
+        .document(
+            base,
+            routes![__MODULE_NAME___list],
+            OpenApiOperation::new("List __MODULE_NAME__s")
+                .paginated::<__DATA_CLASS_STRUCT_NAME__ListItemDTO>()
+                .list_query::<__DATA_CLASS_STRUCT_NAME__ListItemDTO>()
+                .authenticated(),
+        )
+        .document(
+            base,
+            routes![__MODULE_NAME___details],
+            OpenApiOperation::new("Get a __MODULE_NAME__")
+                .output::<__DATA_CLASS_STRUCT_NAME__DetailsDTO>()
+                .authenticated(),
+        )
+        .document(
+            base,
+            routes![__MODULE_NAME___create],
+            OpenApiOperation::new("Create a __MODULE_NAME__")
+                .input::<New__DATA_CLASS_STRUCT_NAME__InputDTO>()
+                .output::<__DATA_CLASS_STRUCT_NAME__DetailsDTO>()
+                .authenticated(),
+        )
+        .document(
+            base,
+            routes![__MODULE_NAME___update],
+            OpenApiOperation::new("Update a __MODULE_NAME__")
+                .input::<Update__DATA_CLASS_STRUCT_NAME__InputDTO>()
+                .output::<__DATA_CLASS_STRUCT_NAME__DetailsDTO>()
+                .authenticated(),
+        )
+        .document(
+            base,
+            routes![__MODULE_NAME___delete],
+            OpenApiOperation::new("Delete a __MODULE_NAME__").authenticated(),
+        );
+}
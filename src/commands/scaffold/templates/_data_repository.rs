@@ -1,14 +1,21 @@
-use diesel::prelude::*;
+use chrono::{DateTime, Utc};
+use diesel::{pg::Pg, prelude::*};
 
 use crate::{
-    core::database::{DbPoolState, DB},
+    core::{
+        cursor::Cursor,
+        database::{DbPoolState, DB},
+        guards::list_query::ListQuery,
+    },
     domain::{
+        dto::__MODULE_NAME__::__DATA_CLASS_STRUCT_NAME__ListItemDTO,
         model::__MODULE_NAME__::{__DATA_CLASS_STRUCT_NAME__, __NEW_DATA_CLASS_STRUCT_NAME__},
         schema::__MODULE_NAME__s::{self, *},
     },
+    filter_by, sort_by,
 };
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 
 #[derive(Clone)]
 pub struct __DATA_CLASS_STRUCT_NAME__Repository {
@@ -40,22 +47,61 @@ impl __DATA_CLASS_STRUCT_NAME__Repository {
     pub fn find_all_for_application_id(
         &self,
         __MODULE_NAME___application_id: i32,
+        list_query: &ListQuery<__DATA_CLASS_STRUCT_NAME__ListItemDTO>,
         page: i32,
         per_page: i32,
     ) -> Result<Vec<__DATA_CLASS_STRUCT_NAME__>> {
         let offset = (page - 1) * per_page;
 
-        let __MODULE_NAME__s = __MODULE_NAME__s::table
-            .filter(application_id.eq(__MODULE_NAME___application_id))
-            .filter(is_deleted.eq(false))
-            .limit(per_page.into())
-            .offset(offset.into())
-            .order(created_date.asc())
+        let __MODULE_NAME__s = Self::sort_listing(
+            Self::get_listing_query(__MODULE_NAME___application_id, list_query)?,
+            list_query,
+        )?
+        .then_order_by((created_date.asc(), id.asc()))
+        .limit(per_page.into())
+        .offset(offset.into())
+        .get_results::<__DATA_CLASS_STRUCT_NAME__>(&mut self.get_db())?;
+
+        Ok(__MODULE_NAME__s)
+    }
+
+    pub fn find_all_for_application_id_after(
+        &self,
+        __MODULE_NAME___application_id: i32,
+        list_query: &ListQuery<__DATA_CLASS_STRUCT_NAME__ListItemDTO>,
+        cursor: Option<&Cursor>,
+        limit: i32,
+    ) -> Result<Vec<__DATA_CLASS_STRUCT_NAME__>> {
+        let mut query = Self::get_listing_query(__MODULE_NAME___application_id, list_query)?;
+
+        if let Some(cursor) = cursor {
+            query = query.filter(
+                created_date.gt(cursor.created_date).or(created_date
+                    .eq(cursor.created_date)
+                    .and(id.gt(cursor.id))),
+            );
+        }
+
+        let __MODULE_NAME__s = query
+            .order((created_date.asc(), id.asc()))
+            .limit(limit.into())
             .get_results::<__DATA_CLASS_STRUCT_NAME__>(&mut self.get_db())?;
 
         Ok(__MODULE_NAME__s)
     }
 
+    pub fn count_all_for_application_id(
+        &self,
+        __MODULE_NAME___application_id: i32,
+        list_query: &ListQuery<__DATA_CLASS_STRUCT_NAME__ListItemDTO>,
+    ) -> Result<i64> {
+        let count = Self::get_listing_query(__MODULE_NAME___application_id, list_query)?
+            .count()
+            .get_result(&mut self.get_db())?;
+
+        Ok(count)
+    }
+
     pub fn find_one_with_application_id(
         &self,
         __MODULE_NAME__id: i32,
@@ -92,4 +138,43 @@ impl __DATA_CLASS_STRUCT_NAME__Repository {
 
         Ok(__MODULE_NAME__)
     }
+
+    /// PRIVATE - gets the query of the __MODULE_NAME__s of an application, filtered by a list query.
+    fn get_listing_query<'a>(
+        __MODULE_NAME___application_id: i32,
+        list_query: &ListQuery<__DATA_CLASS_STRUCT_NAME__ListItemDTO>,
+    ) -> Result<__MODULE_NAME__s::BoxedQuery<'a, Pg>> {
+        let mut query = __MODULE_NAME__s::table
+            .filter(application_id.eq(__MODULE_NAME___application_id))
+            .filter(is_deleted.eq(false))
+            .into_boxed();
+
+        for filter in &list_query.filters {
+            query = match filter.field.as_str() {
+                "id" => filter_by!(query, id, filter, i32),
+                // ...
+                "created_date" => filter_by!(query, created_date, filter, DateTime<Utc>),
+                _ => bail!("Unknown filter field {}", filter.field),
+            };
+        }
+
+        Ok(query)
+    }
+
+    /// PRIVATE - sorts a listing query by the sorts of a list query.
+    fn sort_listing<'a>(
+        mut query: __MODULE_NAME__s::BoxedQuery<'a, Pg>,
+        list_query: &ListQuery<__DATA_CLASS_STRUCT_NAME__ListItemDTO>,
+    ) -> Result<__MODULE_NAME__s::BoxedQuery<'a, Pg>> {
+        for sort in &list_query.sorts {
+            query = match sort.field.as_str() {
+                "id" => sort_by!(query, id, sort),
+                // ...
+                "created_date" => sort_by!(query, created_date, sort),
+                _ => bail!("Unknown sort field {}", sort.field),
+            };
+        }
+
+        Ok(query)
+    }
 }
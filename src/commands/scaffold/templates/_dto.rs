@@ -1,10 +1,12 @@
 use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use validator::Validate;
 
+use crate::core::guards::list_query::{FieldKind, ListFields};
 use crate::domain::model::__MODULE_NAME__::__DATA_CLASS_STRUCT_NAME__;
 
-#[derive(Serialize, Default)]
+#[derive(Serialize, Default, JsonSchema)]
 #[serde(crate = "rocket::serde")]
 pub struct __DATA_CLASS_STRUCT_NAME__ListItemDTO {
     pub id: i32,
@@ -22,7 +24,16 @@ impl From<&__DATA_CLASS_STRUCT_NAME__> for __DATA_CLASS_STRUCT_NAME__ListItemDTO
     }
 }
 
-#[derive(Serialize, Default)]
+impl ListFields for __DATA_CLASS_STRUCT_NAME__ListItemDTO {
+    const FILTERABLE: &'static [(&'static str, FieldKind)] = &[
+        ("id", FieldKind::Integer),
+        // ...
+        ("created_date", FieldKind::Date),
+    ];
+    const SORTABLE: &'static [&'static str] = &["id", /* ... */ "created_date"];
+}
+
+#[derive(Serialize, Default, JsonSchema)]
 #[serde(crate = "rocket::serde")]
 pub struct __DATA_CLASS_STRUCT_NAME__DetailsDTO {
     pub id: i32,
@@ -40,13 +51,13 @@ impl From<&__DATA_CLASS_STRUCT_NAME__> for __DATA_CLASS_STRUCT_NAME__DetailsDTO
     }
 }
 
-#[derive(Serialize, Deserialize, Validate)]
+#[derive(Serialize, Deserialize, Validate, JsonSchema)]
 #[serde(crate = "rocket::serde")]
 pub struct New__DATA_CLASS_STRUCT_NAME__InputDTO {
     // ...
 }
 
-#[derive(Serialize, Deserialize, Validate)]
+#[derive(Serialize, Deserialize, Validate, JsonSchema)]
 #[serde(crate = "rocket::serde")]
 pub struct Update__DATA_CLASS_STRUCT_NAME__InputDTO {
     // ...
@@ -1,3 +1,4 @@
+use crate::core::guards::list_query::ListQuery;
 use crate::domain::{
     dto::__MODULE_NAME__::{
         New__DATA_CLASS_STRUCT_NAME__InputDTO, Update__DATA_CLASS_STRUCT_NAME__InputDTO,
@@ -34,16 +35,32 @@ impl __DATA_CLASS_STRUCT_NAME__Middleware {
     pub fn find_for_user(
         &self,
         user: &User,
+        list_query: &ListQuery<__DATA_CLASS_STRUCT_NAME__ListItemDTO>,
         page: i32,
         per_page: i32,
     ) -> anyhow::Result<Vec<__DATA_CLASS_STRUCT_NAME__>> {
-        let __MODULE_NAME__s =
-            self.repository
-                .find_all_for_application_id(user.application_id, page, per_page)?;
+        let __MODULE_NAME__s = self.repository.find_all_for_application_id(
+            user.application_id,
+            list_query,
+            page,
+            per_page,
+        )?;
 
         Ok(__MODULE_NAME__s)
     }
 
+    pub fn count_for_user(
+        &self,
+        user: &User,
+        list_query: &ListQuery<__DATA_CLASS_STRUCT_NAME__ListItemDTO>,
+    ) -> anyhow::Result<i64> {
+        let count = self
+            .repository
+            .count_all_for_application_id(user.application_id, list_query)?;
+
+        Ok(count)
+    }
+
     pub fn find_one_for_user(
         &self,
         __MODULE_NAME___id: &str,
@@ -1,18 +1,26 @@
 use std::collections::HashMap;
 
-use anyhow::{bail, Result};
-
 use crate::{
-    core::security::{is_admin, is_user, SecurityVoter},
+    core::{
+        guards::granted::SecuritySubject,
+        security::{is_admin, is_user, AccessDecision, AccessError, SecurityVoter},
+    },
     domain::model::user::User,
+    security_subject,
 };
 
+security_subject!(__DATA_CLASS_STRUCT_NAME__Subject, "__MODULE_NAME__");
+
 #[derive(Default)]
 pub struct __DATA_CLASS_STRUCT_NAME__SecurityVoter {}
 
 impl<'a> SecurityVoter<'a> for __DATA_CLASS_STRUCT_NAME__SecurityVoter {
     fn supports(&self) -> &'a str {
-        "__MODULE_NAME__"
+        __DATA_CLASS_STRUCT_NAME__Subject::NAME
+    }
+
+    fn get_rights(&self) -> Vec<&'a str> {
+        vec!["list", "details", "create", "update", "delete"]
     }
 
     fn has_access(
@@ -20,17 +28,14 @@ impl<'a> SecurityVoter<'a> for __DATA_CLASS_STRUCT_NAME__SecurityVoter {
         right: &str,
         user: &User,
         context: Option<HashMap<&str, String>>,
-    ) -> Result<bool> {
+    ) -> Result<AccessDecision, AccessError> {
         match right {
-            "list" => Ok(is_user(user)),
-            "details" => Ok(is_user(user)),
-            "create" => Ok(is_admin(user)),
-            "update" => Ok(is_admin(user)),
-            "delete" => Ok(is_admin(user)),
-            _ => bail!(
-                "No right \"{right}\" found for subject \"{}\"",
-                self.supports()
-            ),
+            "list" => Ok(is_user(user).into()),
+            "details" => Ok(is_user(user).into()),
+            "create" => Ok(is_admin(user).into()),
+            "update" => Ok(is_admin(user).into()),
+            "delete" => Ok(is_admin(user).into()),
+            _ => Err(self.unknown_right(right)),
         }
     }
 }
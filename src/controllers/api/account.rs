@@ -2,53 +2,63 @@ use rocket::{http::Status, serde::json::Json, State};
 
 use crate::{
     core::{
-        guards::{connected_user::ConnectedUser, pagination::Pagination},
-        response::ApiResponse,
-        security::{Security, SecurityVoter},
+        guards::{
+            granted::{
+                CreateRight, DeleteRight, DetailsRight, Granted, ListRight, RestoreRight,
+                UpdateRight,
+            },
+            list_query::ListQuery,
+            pagination::Pagination,
+        },
+        openapi::{OpenApi, OpenApiOperation},
+        response::{ApiResponse, NoContentResponse, Paginated},
+        validation::Validated,
+    },
+    domain::dto::account::{
+        AccountDetailsDTO, AccountListItemDTO, NewAccountInputDTO, UpdateAccountInputDTO,
     },
-    deny_access_unless_granted,
-    domain::dto::account::{AccountDetailsDTO, AccountListItemDTO},
     exceptions::dto::http_exception::HttpException,
-    http_exception, http_ok,
-    middlewares::account_middleware::AccountMiddleware,
+    http_exception, http_no_content, http_ok, http_paginated,
+    middlewares::account_middleware::{AccountError, AccountMiddleware},
+    security::voters::account_security::AccountSubject,
 };
 
 #[get("/accounts", format = "json")]
 pub fn account_list(
-    connected_user: ConnectedUser,
+    granted: Granted<AccountSubject, ListRight>,
     account_middleware: &State<AccountMiddleware>,
     pagination: Pagination,
-    security: &State<Security<dyn SecurityVoter>>,
-) -> Result<ApiResponse<Vec<AccountListItemDTO>>, ApiResponse<HttpException>> {
-    let user = &connected_user.user;
-
-    deny_access_unless_granted!(security, user, "account", "list");
+    list_query: ListQuery<AccountListItemDTO>,
+) -> Result<ApiResponse<Paginated<AccountListItemDTO>>, ApiResponse<HttpException>> {
+    let user = &granted.user;
 
-    let list = account_middleware.find_for_user(user, pagination.page, pagination.per_page);
+    let list =
+        account_middleware.find_for_user(user, &list_query, pagination.page, pagination.per_page);
 
     if list.is_err() {
         http_exception!(Status::InternalServerError);
     }
 
-    let list = list.unwrap();
+    let total = account_middleware.count_for_user(user, &list_query);
+
+    if total.is_err() {
+        http_exception!(Status::InternalServerError);
+    }
 
-    let dto_list = account_middleware.to_list_dto(list);
+    let page = Paginated::new(list.unwrap(), &pagination, total.unwrap());
 
-    http_ok!(dto_list)
+    http_paginated!(page.map(|list| account_middleware.to_list_dto(list)))
 }
 
 #[get("/accounts/<id>", format = "json")]
 pub fn account_details(
-    id: String,
-    connected_user: ConnectedUser,
+    id: i32,
+    granted: Granted<AccountSubject, DetailsRight>,
     account_middleware: &State<AccountMiddleware>,
-    security: &State<Security<dyn SecurityVoter>>,
 ) -> Result<ApiResponse<AccountDetailsDTO>, ApiResponse<HttpException>> {
-    let user = &connected_user.user;
-
-    deny_access_unless_granted!(security, user, "account", "details");
+    let user = &granted.user;
 
-    let account = account_middleware.find_one_for_user(&id, user);
+    let account = account_middleware.find_one_for_user(id, user);
 
     if account.is_err() {
         http_exception!(Status::InternalServerError);
@@ -65,3 +75,193 @@ pub fn account_details(
 
     http_ok!(account_details_dto)
 }
+
+#[post("/accounts", format = "json", data = "<input>")]
+pub fn account_create(
+    input: Validated<Json<NewAccountInputDTO>>,
+    _granted: Granted<AccountSubject, CreateRight>,
+    account_middleware: &State<AccountMiddleware>,
+) -> Result<ApiResponse<AccountDetailsDTO>, ApiResponse<HttpException>> {
+    let dto = input.into_deep_inner();
+
+    let created_account = account_middleware.create_from_user_input(dto);
+
+    if let Err(account_error) = created_account {
+        match account_error {
+            AccountError::AlreadyExists(_) => {
+                http_exception!(
+                    Status::BadRequest,
+                    "An account with this name already exists."
+                );
+            }
+            _ => {
+                http_exception!(Status::InternalServerError);
+            }
+        }
+    }
+
+    let output = account_middleware.to_details_dto(&created_account.unwrap());
+
+    http_ok!(output)
+}
+
+#[put("/accounts/<id>", format = "json", data = "<input>")]
+pub fn account_update(
+    id: i32,
+    input: Validated<Json<UpdateAccountInputDTO>>,
+    _granted: Granted<AccountSubject, UpdateRight>,
+    account_middleware: &State<AccountMiddleware>,
+) -> Result<ApiResponse<AccountDetailsDTO>, ApiResponse<HttpException>> {
+    let to_update = account_middleware.find_one_by_id(id);
+
+    if to_update.is_err() {
+        http_exception!(Status::InternalServerError);
+    }
+
+    let to_update = to_update.unwrap();
+
+    if to_update.is_none() {
+        http_exception!(Status::NotFound, "Cannot find account to update.");
+    }
+
+    let dto = input.into_deep_inner();
+
+    let updated_account = account_middleware.update_from_user_input(&to_update.unwrap(), dto);
+
+    if let Err(account_error) = updated_account {
+        match account_error {
+            AccountError::AlreadyExists(_) => {
+                http_exception!(
+                    Status::BadRequest,
+                    "An account with this name already exists."
+                );
+            }
+            _ => {
+                http_exception!(Status::InternalServerError);
+            }
+        }
+    }
+
+    let output = account_middleware.to_details_dto(&updated_account.unwrap());
+
+    http_ok!(output)
+}
+
+#[delete("/accounts/<id>", format = "json")]
+pub fn account_delete(
+    id: i32,
+    _granted: Granted<AccountSubject, DeleteRight>,
+    account_middleware: &State<AccountMiddleware>,
+) -> Result<ApiResponse<NoContentResponse>, ApiResponse<HttpException>> {
+    let to_delete = account_middleware.find_one_by_id(id);
+
+    if to_delete.is_err() {
+        http_exception!(Status::InternalServerError);
+    }
+
+    let to_delete = to_delete.unwrap();
+
+    if to_delete.is_none() {
+        http_exception!(Status::NotFound, "Cannot find account to delete.");
+    }
+
+    if let Err(account_error) = account_middleware.delete(&to_delete.unwrap()) {
+        match account_error {
+            AccountError::NotEmpty(_) => {
+                http_exception!(
+                    Status::BadRequest,
+                    &format!("{account_error}, delete them first.")
+                );
+            }
+            _ => {
+                http_exception!(Status::InternalServerError);
+            }
+        }
+    }
+
+    http_no_content!()
+}
+
+#[post("/accounts/<id>/restore", format = "json")]
+pub fn account_restore(
+    id: i32,
+    _granted: Granted<AccountSubject, RestoreRight>,
+    account_middleware: &State<AccountMiddleware>,
+) -> Result<ApiResponse<AccountDetailsDTO>, ApiResponse<HttpException>> {
+    let to_restore = account_middleware.find_one_by_id_including_deleted(id);
+
+    if to_restore.is_err() {
+        http_exception!(Status::InternalServerError);
+    }
+
+    let to_restore = to_restore.unwrap();
+
+    if to_restore.is_none() {
+        http_exception!(Status::NotFound, "Cannot find account to restore.");
+    }
+
+    let restored_account = account_middleware.restore(&to_restore.unwrap());
+
+    if let Err(account_error) = restored_account {
+        match account_error {
+            AccountError::NotDeleted => {
+                http_exception!(Status::BadRequest, "Account is not deleted.");
+            }
+            _ => {
+                http_exception!(Status::InternalServerError);
+            }
+        }
+    }
+
+    let output = account_middleware.to_details_dto(&restored_account.unwrap());
+
+    http_ok!(output)
+}
+
+/// documents the routes of this controller, mounted on `base`.
+pub fn document(openapi: &mut OpenApi, base: &str) {
+    openapi
+        .document(
+            base,
+            routes![account_list],
+            OpenApiOperation::new("List accounts")
+                .paginated::<AccountListItemDTO>()
+                .list_query::<AccountListItemDTO>()
+                .authenticated(),
+        )
+        .document(
+            base,
+            routes![account_details],
+            OpenApiOperation::new("Get an account")
+                .output::<AccountDetailsDTO>()
+                .authenticated(),
+        )
+        .document(
+            base,
+            routes![account_create],
+            OpenApiOperation::new("Create an account")
+                .input::<NewAccountInputDTO>()
+                .output::<AccountDetailsDTO>()
+                .authenticated(),
+        )
+        .document(
+            base,
+            routes![account_update],
+            OpenApiOperation::new("Update an account")
+                .input::<UpdateAccountInputDTO>()
+                .output::<AccountDetailsDTO>()
+                .authenticated(),
+        )
+        .document(
+            base,
+            routes![account_delete],
+            OpenApiOperation::new("Delete an account").authenticated(),
+        )
+        .document(
+            base,
+            routes![account_restore],
+            OpenApiOperation::new("Restore a deleted account")
+                .output::<AccountDetailsDTO>()
+                .authenticated(),
+        );
+}
@@ -2,53 +2,103 @@ use rocket::{http::Status, serde::json::Json, State};
 
 use crate::{
     core::{
-        guards::{connected_user::ConnectedUser, pagination::Pagination},
-        response::ApiResponse,
-        security::{Security, SecurityVoter},
+        guards::{
+            cursor_pagination::CursorPagination,
+            granted::{
+                CreateRight, DeleteRight, DetailsRight, Granted, ListRight, RestoreRight,
+                UpdateRight,
+            },
+            list_query::ListQuery,
+            pagination::Pagination,
+        },
+        openapi::{OpenApi, OpenApiOperation},
+        response::{ApiResponse, CursorPaginated, NoContentResponse, Paginated},
+        validation::Validated,
+    },
+    domain::dto::application::{
+        ApplicationDetailsDTO, ApplicationListItemDTO, NewApplicationInputDTO,
+        UpdateApplicationInputDTO,
     },
-    deny_access_unless_granted,
-    domain::dto::application::{ApplicationDetailsDTO, ApplicationListItemDTO},
     exceptions::dto::http_exception::HttpException,
-    http_exception, http_ok,
-    middlewares::application_middleware::ApplicationMiddleware,
+    http_exception, http_no_content, http_ok, http_paginated,
+    middlewares::application_middleware::{ApplicationError, ApplicationMiddleware},
+    security::voters::application_security::ApplicationSubject,
 };
 
-#[get("/applications", format = "json")]
+#[get("/applications", format = "json", rank = 1)]
+pub fn application_list_by_cursor(
+    cursor_pagination: CursorPagination,
+    granted: Granted<ApplicationSubject, ListRight>,
+    application_middleware: &State<ApplicationMiddleware>,
+    list_query: ListQuery<ApplicationListItemDTO>,
+) -> Result<ApiResponse<CursorPaginated<ApplicationListItemDTO>>, ApiResponse<HttpException>> {
+    let user = &granted.user;
+
+    if !list_query.sorts.is_empty() {
+        http_exception!(
+            Status::BadRequest,
+            "sort is not supported with cursor pagination"
+        );
+    }
+
+    let list = application_middleware.find_for_user_after(
+        user,
+        &list_query,
+        cursor_pagination.cursor.as_ref(),
+        cursor_pagination.limit,
+    );
+
+    if list.is_err() {
+        http_exception!(Status::InternalServerError);
+    }
+
+    let page = CursorPaginated::new(list.unwrap(), &cursor_pagination, |application| {
+        (application.created_date, application.id)
+    });
+
+    http_paginated!(page.map(|list| application_middleware.to_list_dto(list)));
+}
+
+#[get("/applications", format = "json", rank = 2)]
 pub fn application_list(
-    connected_user: ConnectedUser,
+    granted: Granted<ApplicationSubject, ListRight>,
     application_middleware: &State<ApplicationMiddleware>,
-    security: &State<Security<dyn SecurityVoter>>,
     pagination: Pagination,
-) -> Result<ApiResponse<Vec<ApplicationListItemDTO>>, ApiResponse<HttpException>> {
-    let user = &connected_user.user;
+    list_query: ListQuery<ApplicationListItemDTO>,
+) -> Result<ApiResponse<Paginated<ApplicationListItemDTO>>, ApiResponse<HttpException>> {
+    let user = &granted.user;
 
-    deny_access_unless_granted!(security, user, "application", "list");
-
-    let list = application_middleware.find_for_user(user, pagination.page, pagination.per_page);
+    let list = application_middleware.find_for_user(
+        user,
+        &list_query,
+        pagination.page,
+        pagination.per_page,
+    );
 
     if list.is_err() {
         http_exception!(Status::InternalServerError);
     }
 
-    let list = list.unwrap();
+    let total = application_middleware.count_for_user(user, &list_query);
 
-    let dto_list = application_middleware.to_list_dto(list);
+    if total.is_err() {
+        http_exception!(Status::InternalServerError);
+    }
+
+    let page = Paginated::new(list.unwrap(), &pagination, total.unwrap());
 
-    http_ok!(dto_list);
+    http_paginated!(page.map(|list| application_middleware.to_list_dto(list)));
 }
 
 #[get("/applications/<id>", format = "json")]
 pub fn application_details(
-    id: String,
-    connected_user: ConnectedUser,
+    id: i32,
+    granted: Granted<ApplicationSubject, DetailsRight>,
     application_middleware: &State<ApplicationMiddleware>,
-    security: &State<Security<dyn SecurityVoter>>,
 ) -> Result<ApiResponse<ApplicationDetailsDTO>, ApiResponse<HttpException>> {
-    let user = &connected_user.user;
+    let user = &granted.user;
 
-    deny_access_unless_granted!(security, user, "application", "details");
-
-    let application = application_middleware.find_one_for_user(&id, user);
+    let application = application_middleware.find_one_for_user(id, user);
 
     if application.is_err() {
         http_exception!(Status::InternalServerError);
@@ -65,3 +115,193 @@ pub fn application_details(
 
     http_ok!(account_details_dto);
 }
+
+#[post("/applications", format = "json", data = "<input>")]
+pub fn application_create(
+    input: Validated<Json<NewApplicationInputDTO>>,
+    _granted: Granted<ApplicationSubject, CreateRight>,
+    application_middleware: &State<ApplicationMiddleware>,
+) -> Result<ApiResponse<ApplicationDetailsDTO>, ApiResponse<HttpException>> {
+    let dto = input.into_deep_inner();
+
+    let created_application = application_middleware.create_from_user_input(dto);
+
+    if let Err(application_error) = created_application {
+        match application_error {
+            ApplicationError::AccountNotFound(_) => {
+                http_exception!(
+                    Status::BadRequest,
+                    "Cannot find the account of the application."
+                );
+            }
+            _ => {
+                http_exception!(Status::InternalServerError);
+            }
+        }
+    }
+
+    let output = application_middleware.to_details_dto(&created_application.unwrap());
+
+    http_ok!(output)
+}
+
+#[put("/applications/<id>", format = "json", data = "<input>")]
+pub fn application_update(
+    id: i32,
+    input: Validated<Json<UpdateApplicationInputDTO>>,
+    _granted: Granted<ApplicationSubject, UpdateRight>,
+    application_middleware: &State<ApplicationMiddleware>,
+) -> Result<ApiResponse<ApplicationDetailsDTO>, ApiResponse<HttpException>> {
+    let to_update = application_middleware.find_one_by_id(id);
+
+    if to_update.is_err() {
+        http_exception!(Status::InternalServerError);
+    }
+
+    let to_update = to_update.unwrap();
+
+    if to_update.is_none() {
+        http_exception!(Status::NotFound, "Cannot find application to update.");
+    }
+
+    let dto = input.into_deep_inner();
+
+    let updated_application =
+        application_middleware.update_from_user_input(&to_update.unwrap(), dto);
+
+    if updated_application.is_err() {
+        http_exception!(Status::InternalServerError);
+    }
+
+    let output = application_middleware.to_details_dto(&updated_application.unwrap());
+
+    http_ok!(output)
+}
+
+#[delete("/applications/<id>", format = "json")]
+pub fn application_delete(
+    id: i32,
+    granted: Granted<ApplicationSubject, DeleteRight>,
+    application_middleware: &State<ApplicationMiddleware>,
+) -> Result<ApiResponse<NoContentResponse>, ApiResponse<HttpException>> {
+    let deleter = &granted.user;
+
+    let to_delete = application_middleware.find_one_by_id(id);
+
+    if to_delete.is_err() {
+        http_exception!(Status::InternalServerError);
+    }
+
+    let to_delete = to_delete.unwrap();
+
+    if to_delete.is_none() {
+        http_exception!(Status::NotFound, "Cannot find application to delete.");
+    }
+
+    if let Err(application_error) = application_middleware.delete(&to_delete.unwrap(), deleter) {
+        match application_error {
+            ApplicationError::OwnApplication => {
+                http_exception!(
+                    Status::BadRequest,
+                    "You cannot delete your own application."
+                );
+            }
+            _ => {
+                http_exception!(Status::InternalServerError);
+            }
+        }
+    }
+
+    http_no_content!()
+}
+
+#[post("/applications/<id>/restore", format = "json")]
+pub fn application_restore(
+    id: i32,
+    _granted: Granted<ApplicationSubject, RestoreRight>,
+    application_middleware: &State<ApplicationMiddleware>,
+) -> Result<ApiResponse<ApplicationDetailsDTO>, ApiResponse<HttpException>> {
+    let to_restore = application_middleware.find_one_by_id_including_deleted(id);
+
+    if to_restore.is_err() {
+        http_exception!(Status::InternalServerError);
+    }
+
+    let to_restore = to_restore.unwrap();
+
+    if to_restore.is_none() {
+        http_exception!(Status::NotFound, "Cannot find application to restore.");
+    }
+
+    let restored_application = application_middleware.restore(&to_restore.unwrap());
+
+    if let Err(application_error) = restored_application {
+        match application_error {
+            ApplicationError::NotDeleted => {
+                http_exception!(Status::BadRequest, "Application is not deleted.");
+            }
+            ApplicationError::AccountNotFound(_) => {
+                http_exception!(
+                    Status::BadRequest,
+                    "The account of the application is deleted, restore it first."
+                );
+            }
+            _ => {
+                http_exception!(Status::InternalServerError);
+            }
+        }
+    }
+
+    let output = application_middleware.to_details_dto(&restored_application.unwrap());
+
+    http_ok!(output)
+}
+
+/// documents the routes of this controller, mounted on `base`.
+pub fn document(openapi: &mut OpenApi, base: &str) {
+    openapi
+        .document(
+            base,
+            routes![application_list_by_cursor, application_list],
+            OpenApiOperation::new("List applications")
+                .paginated::<ApplicationListItemDTO>()
+                .list_query::<ApplicationListItemDTO>()
+                .cursor_paginated::<ApplicationListItemDTO>()
+                .authenticated(),
+        )
+        .document(
+            base,
+            routes![application_details],
+            OpenApiOperation::new("Get an application")
+                .output::<ApplicationDetailsDTO>()
+                .authenticated(),
+        )
+        .document(
+            base,
+            routes![application_create],
+            OpenApiOperation::new("Create an application")
+                .input::<NewApplicationInputDTO>()
+                .output::<ApplicationDetailsDTO>()
+                .authenticated(),
+        )
+        .document(
+            base,
+            routes![application_update],
+            OpenApiOperation::new("Update an application")
+                .input::<UpdateApplicationInputDTO>()
+                .output::<ApplicationDetailsDTO>()
+                .authenticated(),
+        )
+        .document(
+            base,
+            routes![application_delete],
+            OpenApiOperation::new("Delete an application").authenticated(),
+        )
+        .document(
+            base,
+            routes![application_restore],
+            OpenApiOperation::new("Restore a deleted application")
+                .output::<ApplicationDetailsDTO>()
+                .authenticated(),
+        );
+}
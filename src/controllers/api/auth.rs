@@ -1,12 +1,38 @@
-use crate::{core::validation::Validated, http_exception, http_ok};
-use rocket::{http::Status, serde::json::Json, State};
+use crate::{
+    core::validation::Validated, deny_access_if_impersonated, http_exception, http_no_content,
+    http_ok,
+};
+
+use rocket::{
+    http::{Header, Status},
+    serde::json::Json,
+    State,
+};
 
 use crate::{
-    core::response::ApiResponse,
-    domain::dto::auth::{JWTTokenOutputDTO, LoginInputDTO, RefreshTokenInputDTO},
+    core::{
+        guards::{client_ip::ClientIp, connected_user::ConnectedUser},
+        jwt,
+        openapi::{OpenApi, OpenApiOperation},
+        response::{ApiResponse, NoContentResponse},
+    },
+    domain::{
+        dto::auth::{
+            DelegationInputDTO, DelegationOutputDTO, JWTTokenOutputDTO, LoginInputDTO,
+            LoginOutputDTO, MFAChallengeOutputDTO, MFALoginInputDTO, PasswordResetConfirmInputDTO,
+            PasswordResetRequestInputDTO, RefreshTokenInputDTO,
+        },
+        model::user::User,
+    },
     exceptions::dto::http_exception::HttpException,
     middlewares::{
-        refresh_token_middleware::RefreshTokenMiddleware, user_middleware::UserMiddleware,
+        jwt_denylist_middleware::JWTDenylistMiddleware,
+        login_throttle_middleware::LoginThrottleMiddleware,
+        oauth_middleware::OAuthMiddleware,
+        password_reset_middleware::{PasswordResetError, PasswordResetMiddleware},
+        refresh_token_middleware::RefreshTokenMiddleware,
+        totp_middleware::{MFAError, TOTPMiddleware},
+        user_middleware::{AuthenticationError, UserMiddleware},
     },
 };
 
@@ -17,36 +43,127 @@ pub fn token(
     input: Validated<Json<LoginInputDTO>>,
     user_middleware: &State<UserMiddleware>,
     refresh_token_middleware: &State<RefreshTokenMiddleware>,
-) -> Result<ApiResponse<JWTTokenOutputDTO>, ApiResponse<HttpException>> {
+    totp_middleware: &State<TOTPMiddleware>,
+    login_throttle_middleware: &State<LoginThrottleMiddleware>,
+    client_ip: Option<ClientIp>,
+) -> Result<ApiResponse<LoginOutputDTO>, ApiResponse<HttpException>> {
     let real_input = input.into_inner().into_inner();
+    let client_ip = client_ip.map(|ClientIp(ip)| ip.to_string());
+
+    let retry_after =
+        login_throttle_middleware.get_retry_after(&real_input.login, client_ip.as_deref());
+
+    if retry_after.is_err() {
+        http_exception!(Status::InternalServerError);
+    }
+
+    if let Some(retry_after) = retry_after.unwrap() {
+        return Err(ApiResponse::from_status_with_reason(
+            Status::TooManyRequests,
+            "Too many failed login attempts, please retry later.",
+        )
+        .with_header(Header::new("Retry-After", retry_after.to_string())));
+    }
 
     let auth_result = user_middleware.authenticate_user_from_input(&real_input);
 
-    if auth_result.is_err() {
+    if let Err(error) = &auth_result {
+        if !matches!(
+            error.downcast_ref::<AuthenticationError>(),
+            Some(AuthenticationError::UserNotFound(_) | AuthenticationError::WrongPassword(_))
+        ) {
+            log::error!("Could not authenticate user : {error}");
+            http_exception!(Status::InternalServerError);
+        }
+
+        // unknown logins are tracked as well, so attempts cannot be used to find existing logins.
+        if login_throttle_middleware
+            .register_failure(&real_input.login, client_ip.as_deref())
+            .is_err()
+        {
+            http_exception!(Status::InternalServerError);
+        }
+
         http_exception!(Status::NotFound);
     }
 
     let current_user = auth_result.unwrap();
 
-    let jwt_token_result = user_middleware.create_jwt_for_user(&current_user);
+    if login_throttle_middleware
+        .register_success(&real_input.login)
+        .is_err()
+    {
+        http_exception!(Status::InternalServerError);
+    }
 
-    if jwt_token_result.is_err() {
-        http_exception!(Status::InternalServerError, "Could not create JWT token");
+    let mfa_enabled = totp_middleware.is_enabled_for_user(&current_user);
+
+    if mfa_enabled.is_err() {
+        http_exception!(Status::InternalServerError);
     }
 
-    let refresh_token_result = refresh_token_middleware.generate_for_user(&current_user);
+    if mfa_enabled.unwrap() {
+        let challenge = totp_middleware.create_challenge(&current_user);
 
-    if refresh_token_result.is_err() {
-        http_exception!(
-            Status::InternalServerError,
-            "Could not create refresh token"
-        );
+        if challenge.is_err() {
+            http_exception!(
+                Status::InternalServerError,
+                "Could not create MFA challenge"
+            );
+        }
+
+        let (challenge, expiration_date) = challenge.unwrap();
+
+        http_ok!(LoginOutputDTO::MFAChallenge(MFAChallengeOutputDTO {
+            mfa_required: true,
+            challenge,
+            expiration_date,
+        }));
     }
 
-    http_ok!(JWTTokenOutputDTO {
-        token: jwt_token_result.unwrap(),
-        refresh_token: refresh_token_result.unwrap().token,
-    });
+    let tokens = issue_tokens(&current_user, user_middleware, refresh_token_middleware)?;
+
+    http_ok!(LoginOutputDTO::Token(tokens));
+}
+
+#[post("/token/mfa", format = "json", data = "<input>")]
+pub fn token_mfa(
+    input: Validated<Json<MFALoginInputDTO>>,
+    user_middleware: &State<UserMiddleware>,
+    refresh_token_middleware: &State<RefreshTokenMiddleware>,
+    totp_middleware: &State<TOTPMiddleware>,
+) -> Result<ApiResponse<JWTTokenOutputDTO>, ApiResponse<HttpException>> {
+    let input = input.into_deep_inner();
+
+    let auth_result = totp_middleware.complete_challenge(&input.challenge, &input.code);
+
+    if let Err(mfa_error) = auth_result {
+        match mfa_error {
+            MFAError::ChallengeNotFound => {
+                http_exception!(Status::NotFound);
+            }
+            MFAError::ChallengeExpired(_) => {
+                http_exception!(Status::BadRequest, "Challenge expired");
+            }
+            MFAError::TooManyAttempts => {
+                http_exception!(Status::TooManyRequests, "Too many attempts");
+            }
+            MFAError::InvalidCode | MFAError::NotEnrolled | MFAError::AlreadyEnabled => {
+                http_exception!(Status::Unauthorized, "Invalid code");
+            }
+            MFAError::Internal(_) => {
+                http_exception!(Status::InternalServerError);
+            }
+        }
+    }
+
+    let tokens = issue_tokens(
+        &auth_result.unwrap(),
+        user_middleware,
+        refresh_token_middleware,
+    )?;
+
+    http_ok!(tokens);
 }
 
 #[post("/refresh-token", format = "json", data = "<input>")]
@@ -57,7 +174,7 @@ pub fn refresh_token(
 ) -> Result<ApiResponse<JWTTokenOutputDTO>, ApiResponse<HttpException>> {
     let input = input.into_inner().into_inner();
 
-    let token_valid = refresh_token_middleware.is_valid(&input.refresh_token);
+    let token_valid = refresh_token_middleware.is_valid(&input.refresh_token, None);
 
     if let Err(token_error) = token_valid {
         match token_error {
@@ -67,6 +184,15 @@ pub fn refresh_token(
             JWTRefreshTokenValidationError::Expired(_) => {
                 http_exception!(Status::BadRequest, "Token expired");
             }
+            JWTRefreshTokenValidationError::Revoked(_) => {
+                http_exception!(Status::Unauthorized, "Token revoked");
+            }
+            JWTRefreshTokenValidationError::Reused(_) => {
+                http_exception!(
+                    Status::Unauthorized,
+                    "Token already used, all related sessions have been revoked"
+                );
+            }
         }
     }
 
@@ -92,7 +218,109 @@ pub fn refresh_token(
         http_exception!(Status::InternalServerError, "Could not create JWT token");
     }
 
-    let refresh_token_result = refresh_token_middleware.generate_for_user(&current_user);
+    let refresh_token_result = refresh_token_middleware.rotate(&token);
+
+    if let Err(token_error) = refresh_token_result {
+        match token_error {
+            JWTRefreshTokenValidationError::Reused(_) => {
+                http_exception!(
+                    Status::Unauthorized,
+                    "Token already used, all related sessions have been revoked"
+                );
+            }
+            _ => {
+                http_exception!(
+                    Status::InternalServerError,
+                    "Could not create refresh token"
+                );
+            }
+        }
+    }
+
+    http_ok!(JWTTokenOutputDTO {
+        token: jwt_token_result.unwrap(),
+        refresh_token: refresh_token_result.unwrap().token,
+    });
+}
+
+#[post("/logout", format = "json", data = "<input>")]
+pub fn logout(
+    input: Validated<Json<RefreshTokenInputDTO>>,
+    connected_user: ConnectedUser,
+    refresh_token_middleware: &State<RefreshTokenMiddleware>,
+    jwt_denylist_middleware: &State<JWTDenylistMiddleware>,
+) -> Result<ApiResponse<NoContentResponse>, ApiResponse<HttpException>> {
+    let input = input.into_deep_inner();
+    let user = &connected_user.user;
+
+    if refresh_token_middleware
+        .revoke_for_user(&input.refresh_token, user)
+        .is_err()
+    {
+        http_exception!(Status::NotFound);
+    }
+
+    if deny_connected_jwt(&connected_user, jwt_denylist_middleware).is_err() {
+        http_exception!(Status::InternalServerError, "Could not revoke JWT token");
+    }
+
+    http_no_content!()
+}
+
+#[post("/logout-all", format = "json")]
+pub fn logout_all(
+    connected_user: ConnectedUser,
+    user_middleware: &State<UserMiddleware>,
+    refresh_token_middleware: &State<RefreshTokenMiddleware>,
+) -> Result<ApiResponse<NoContentResponse>, ApiResponse<HttpException>> {
+    // it would end every session of the impersonated user.
+    deny_access_if_impersonated!(connected_user);
+
+    let user = &connected_user.user;
+
+    if refresh_token_middleware.revoke_all_for_user(user).is_err() {
+        http_exception!(
+            Status::InternalServerError,
+            "Could not revoke refresh tokens"
+        );
+    }
+
+    // JWTs cannot be listed, every one issued so far (including the current one) is refused instead.
+    if user_middleware.revoke_all_jwt(user).is_err() {
+        http_exception!(Status::InternalServerError, "Could not revoke JWT tokens");
+    }
+
+    http_no_content!()
+}
+
+#[post("/delegate", format = "json", data = "<input>")]
+pub fn delegate(
+    input: Validated<Json<DelegationInputDTO>>,
+    connected_user: ConnectedUser,
+    oauth_middleware: &State<OAuthMiddleware>,
+    refresh_token_middleware: &State<RefreshTokenMiddleware>,
+) -> Result<ApiResponse<DelegationOutputDTO>, ApiResponse<HttpException>> {
+    // the client would keep acting on behalf of the impersonated user.
+    deny_access_if_impersonated!(connected_user);
+
+    let input = input.into_deep_inner();
+    let user = &connected_user.user;
+
+    let client = oauth_middleware.find_client(&input.client_id, user.application_id);
+
+    if client.is_err() {
+        http_exception!(Status::InternalServerError);
+    }
+
+    let client = client.unwrap();
+
+    if client.is_none() {
+        http_exception!(Status::NotFound);
+    }
+
+    let client = client.unwrap();
+
+    let refresh_token_result = refresh_token_middleware.generate_for_client(user, &client);
 
     if refresh_token_result.is_err() {
         http_exception!(
@@ -101,8 +329,415 @@ pub fn refresh_token(
         );
     }
 
-    http_ok!(JWTTokenOutputDTO {
-        token: jwt_token_result.unwrap(),
+    http_ok!(DelegationOutputDTO {
+        client_id: client.client_id,
         refresh_token: refresh_token_result.unwrap().token,
     });
 }
+
+#[post("/password-reset/request", format = "json", data = "<input>")]
+pub fn password_reset_request(
+    input: Validated<Json<PasswordResetRequestInputDTO>>,
+    password_reset_middleware: &State<PasswordResetMiddleware>,
+) -> Result<ApiResponse<NoContentResponse>, ApiResponse<HttpException>> {
+    let input = input.into_deep_inner();
+
+    // the response never tells whether a user matched, to avoid disclosing logins or emails :
+    // failures (e.g. of the notifier) only happen for matching users, so they are logged but not answered.
+    if let Err(error) = password_reset_middleware.request(&input.login) {
+        log::error!("Could not issue password reset token : {error}");
+    }
+
+    http_no_content!()
+}
+
+#[post("/password-reset/confirm", format = "json", data = "<input>")]
+pub fn password_reset_confirm(
+    input: Validated<Json<PasswordResetConfirmInputDTO>>,
+    user_middleware: &State<UserMiddleware>,
+    password_reset_middleware: &State<PasswordResetMiddleware>,
+    refresh_token_middleware: &State<RefreshTokenMiddleware>,
+) -> Result<ApiResponse<NoContentResponse>, ApiResponse<HttpException>> {
+    let input = input.into_deep_inner();
+
+    let consume_result = password_reset_middleware.consume(&input.token);
+
+    if let Err(reset_error) = consume_result {
+        match reset_error {
+            PasswordResetError::NotFound => {
+                http_exception!(Status::NotFound);
+            }
+            PasswordResetError::Expired(_) => {
+                http_exception!(Status::BadRequest, "Token expired");
+            }
+            PasswordResetError::AlreadyUsed => {
+                http_exception!(Status::BadRequest, "Token already used");
+            }
+        }
+    }
+
+    let user = consume_result.unwrap();
+
+    if user_middleware
+        .set_password(&user, &input.password)
+        .is_err()
+    {
+        http_exception!(Status::InternalServerError, "Could not update password");
+    }
+
+    if refresh_token_middleware.revoke_all_for_user(&user).is_err() {
+        http_exception!(
+            Status::InternalServerError,
+            "Could not revoke refresh tokens"
+        );
+    }
+
+    http_no_content!()
+}
+
+/// PRIVATE - creates a JWT and a refresh token for an authenticated user.
+fn issue_tokens(
+    user: &User,
+    user_middleware: &UserMiddleware,
+    refresh_token_middleware: &RefreshTokenMiddleware,
+) -> Result<JWTTokenOutputDTO, ApiResponse<HttpException>> {
+    let jwt_token_result = user_middleware.create_jwt_for_user(user);
+
+    if jwt_token_result.is_err() {
+        http_exception!(Status::InternalServerError, "Could not create JWT token");
+    }
+
+    let refresh_token_result = refresh_token_middleware.generate_for_user(user);
+
+    if refresh_token_result.is_err() {
+        http_exception!(
+            Status::InternalServerError,
+            "Could not create refresh token"
+        );
+    }
+
+    Ok(JWTTokenOutputDTO {
+        token: jwt_token_result.unwrap(),
+        refresh_token: refresh_token_result.unwrap().token,
+    })
+}
+
+/// PRIVATE - denies the JWT used by the connected user until it expires.
+fn deny_connected_jwt(
+    connected_user: &ConnectedUser,
+    jwt_denylist_middleware: &JWTDenylistMiddleware,
+) -> anyhow::Result<()> {
+    let claims = &connected_user.claims;
+
+    if let (Some(jti), Some(expiration_date)) = (&claims.jwt_id, jwt::get_expiration_date(claims)) {
+        jwt_denylist_middleware.deny(jti, Some(connected_user.user.id), expiration_date)?;
+    }
+
+    Ok(())
+}
+
+/// documents the routes of this controller, mounted on `base`.
+pub fn document(openapi: &mut OpenApi, base: &str) {
+    openapi
+        .document(
+            base,
+            routes![token],
+            OpenApiOperation::new("Log in, getting tokens or a two-factor challenge")
+                .input::<LoginInputDTO>()
+                .output::<LoginOutputDTO>(),
+        )
+        .document(
+            base,
+            routes![token_mfa],
+            OpenApiOperation::new("Answer a two-factor challenge")
+                .input::<MFALoginInputDTO>()
+                .output::<JWTTokenOutputDTO>(),
+        )
+        .document(
+            base,
+            routes![refresh_token],
+            OpenApiOperation::new("Exchange a refresh token for new tokens")
+                .input::<RefreshTokenInputDTO>()
+                .output::<JWTTokenOutputDTO>(),
+        )
+        .document(
+            base,
+            routes![logout],
+            OpenApiOperation::new("Log out, revoking the JWT and a refresh token")
+                .input::<RefreshTokenInputDTO>()
+                .authenticated(),
+        )
+        .document(
+            base,
+            routes![logout_all],
+            OpenApiOperation::new("Log out of every session").authenticated(),
+        )
+        .document(
+            base,
+            routes![delegate],
+            OpenApiOperation::new(
+                "Issue a refresh token to an OAuth client, to act on behalf of the user",
+            )
+            .input::<DelegationInputDTO>()
+            .output::<DelegationOutputDTO>()
+            .authenticated(),
+        )
+        .document(
+            base,
+            routes![password_reset_request],
+            OpenApiOperation::new("Request a password reset")
+                .input::<PasswordResetRequestInputDTO>(),
+        )
+        .document(
+            base,
+            routes![password_reset_confirm],
+            OpenApiOperation::new("Reset a password with a reset token")
+                .input::<PasswordResetConfirmInputDTO>(),
+        );
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use anyhow::bail;
+    use chrono::{DateTime, Utc};
+    use rocket::{
+        http::{ContentType, Header},
+        local::blocking::Client,
+    };
+
+    use super::*;
+    use crate::{
+        core::{
+            configuration::ConfigState,
+            notifier::{CapturingNotifier, Notifier},
+            role_hierarchy::RoleHierarchy,
+            testing::{
+                create_test_application, create_test_user, get_test_configuration,
+                get_test_db_state, get_test_key_store,
+            },
+        },
+        domain::repository::{
+            jwt_denylist_repository::JWTDenylistRepository,
+            password_reset_token_repository::PasswordResetTokenRepository,
+            refresh_token_repository::RefreshTokenRepository, role_repository::RoleRepository,
+            user_repository::UserRepository,
+        },
+        middlewares::{role_middleware::RoleMiddleware, user_middleware::JWTAuthenticationError},
+    };
+
+    /// A notifier failing to deliver anything.
+    struct FailingNotifier;
+
+    impl Notifier for FailingNotifier {
+        fn notify_password_reset(&self, _: &User, _: &str, _: DateTime<Utc>) -> anyhow::Result<()> {
+            bail!("Mail server unavailable")
+        }
+    }
+
+    fn get_client(notifier: Arc<dyn Notifier>) -> Client {
+        let db_state = get_test_db_state();
+        let configuration = get_test_configuration();
+        let jwt_denylist_middleware =
+            JWTDenylistMiddleware::new(JWTDenylistRepository::new(db_state.clone()));
+
+        let rocket = rocket::build()
+            .mount(
+                "/api/auth",
+                routes![
+                    logout,
+                    logout_all,
+                    password_reset_request,
+                    password_reset_confirm
+                ],
+            )
+            .manage(UserMiddleware::new(
+                UserRepository::new(db_state.clone()),
+                jwt_denylist_middleware.clone(),
+                get_test_key_store(),
+                configuration.clone(),
+            ))
+            .manage(RoleMiddleware::new(
+                RoleRepository::new(db_state.clone()),
+                UserRepository::new(db_state.clone()),
+                RoleHierarchy::from_config(&configuration).unwrap(),
+            ))
+            .manage(jwt_denylist_middleware)
+            .manage(PasswordResetMiddleware::new(
+                PasswordResetTokenRepository::new(db_state.clone()),
+                UserRepository::new(db_state.clone()),
+                notifier,
+                configuration.clone(),
+            ))
+            .manage(RefreshTokenMiddleware::new(
+                RefreshTokenRepository::new(db_state),
+                configuration,
+            ));
+
+        Client::untracked(rocket).unwrap()
+    }
+
+    /// opens a session for a user, giving its JWT and refresh token.
+    fn log_in(client: &Client, user: &User) -> (String, String) {
+        let user_middleware = client.rocket().state::<UserMiddleware>().unwrap();
+        let refresh_token_middleware = client.rocket().state::<RefreshTokenMiddleware>().unwrap();
+
+        (
+            user_middleware.create_jwt_for_user(user).unwrap(),
+            refresh_token_middleware
+                .generate_for_user(user)
+                .unwrap()
+                .token,
+        )
+    }
+
+    fn log_out(client: &Client, jwt: &str, uri: &str, refresh_token: &str) -> Status {
+        client
+            .post(uri.to_string())
+            .header(ContentType::JSON)
+            .header(Header::new("Authorization", format!("Bearer {jwt}")))
+            .body(format!(r#"{{"refresh_token": "{refresh_token}"}}"#))
+            .dispatch()
+            .status()
+    }
+
+    /// checks that the JWT and the refresh token of a session are still accepted.
+    fn is_session_alive(client: &Client, (jwt, refresh_token): &(String, String)) -> bool {
+        let user_middleware = client.rocket().state::<UserMiddleware>().unwrap();
+        let refresh_token_middleware = client.rocket().state::<RefreshTokenMiddleware>().unwrap();
+
+        let jwt_result = user_middleware.authenticate_user_from_jwt(jwt);
+        let refresh_token_result = refresh_token_middleware.is_valid(refresh_token, None);
+
+        match (jwt_result, refresh_token_result) {
+            (Ok(_), Ok(_)) => true,
+            (Err(jwt_error), Err(JWTRefreshTokenValidationError::Revoked(_))) => {
+                assert!(matches!(
+                    jwt_error.downcast_ref::<JWTAuthenticationError>(),
+                    Some(JWTAuthenticationError::RevokedToken)
+                ));
+                false
+            }
+            _ => panic!("A session is either alive or revoked as a whole"),
+        }
+    }
+
+    #[test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    fn test_logout_only_closes_the_current_session() {
+        let db_state = get_test_db_state();
+        let user = create_test_user(&db_state, &create_test_application(&db_state), &[]);
+        let client = get_client(Arc::new(CapturingNotifier::default()));
+
+        let session = log_in(&client, &user);
+        let other_session = log_in(&client, &user);
+
+        assert_eq!(
+            log_out(&client, &session.0, "/api/auth/logout", &session.1),
+            Status::NoContent
+        );
+        assert!(!is_session_alive(&client, &session));
+        assert!(is_session_alive(&client, &other_session));
+
+        // refresh tokens of other users cannot be revoked.
+        let other_user = create_test_user(&db_state, &create_test_application(&db_state), &[]);
+        let other_user_session = log_in(&client, &other_user);
+
+        assert_eq!(
+            log_out(
+                &client,
+                &other_session.0,
+                "/api/auth/logout",
+                &other_user_session.1
+            ),
+            Status::NotFound
+        );
+        assert!(is_session_alive(&client, &other_user_session));
+    }
+
+    #[test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    fn test_logout_all_closes_every_session() {
+        let db_state = get_test_db_state();
+        let user = create_test_user(&db_state, &create_test_application(&db_state), &[]);
+        let other_user = create_test_user(&db_state, &create_test_application(&db_state), &[]);
+        let client = get_client(Arc::new(CapturingNotifier::default()));
+
+        let session = log_in(&client, &user);
+        let other_session = log_in(&client, &user);
+        let other_user_session = log_in(&client, &other_user);
+
+        assert_eq!(
+            log_out(&client, &session.0, "/api/auth/logout-all", ""),
+            Status::NoContent
+        );
+        assert!(!is_session_alive(&client, &session));
+        assert!(!is_session_alive(&client, &other_session));
+        assert!(is_session_alive(&client, &other_user_session));
+        assert_eq!(
+            log_out(&client, &session.0, "/api/auth/logout-all", ""),
+            Status::Unauthorized
+        );
+    }
+
+    fn request_reset(client: &Client, login: &str) -> Status {
+        client
+            .post("/api/auth/password-reset/request")
+            .header(ContentType::JSON)
+            .body(format!(r#"{{"login": "{login}"}}"#))
+            .dispatch()
+            .status()
+    }
+
+    fn confirm_reset(client: &Client, token: &str) -> Status {
+        client
+            .post("/api/auth/password-reset/confirm")
+            .header(ContentType::JSON)
+            .body(format!(
+                r#"{{"token": "{token}", "password": "N3w-Passw0rd!"}}"#
+            ))
+            .dispatch()
+            .status()
+    }
+
+    #[test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    fn test_password_reset_revokes_refresh_tokens() {
+        let db_state = get_test_db_state();
+        let user = create_test_user(&db_state, &create_test_application(&db_state), &[]);
+        let refresh_token_middleware = RefreshTokenMiddleware::new(
+            RefreshTokenRepository::new(db_state),
+            ConfigState::default(),
+        );
+        let refresh_token = refresh_token_middleware.generate_for_user(&user).unwrap();
+
+        let notifier = Arc::new(CapturingNotifier::default());
+        let client = get_client(notifier.clone());
+
+        assert_eq!(request_reset(&client, &user.login), Status::NoContent);
+
+        let token = notifier.get_password_reset_token(&user).unwrap();
+
+        assert_eq!(confirm_reset(&client, &token), Status::NoContent);
+        assert!(matches!(
+            refresh_token_middleware.is_valid(&refresh_token.token, None),
+            Err(JWTRefreshTokenValidationError::Revoked(_))
+        ));
+        // tokens are single-use.
+        assert_eq!(confirm_reset(&client, &token), Status::BadRequest);
+    }
+
+    #[test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    fn test_password_reset_request_does_not_disclose_users() {
+        let db_state = get_test_db_state();
+        let user = create_test_user(&db_state, &create_test_application(&db_state), &[]);
+
+        let client = get_client(Arc::new(FailingNotifier));
+
+        // a failing delivery answers as an unknown user does.
+        assert_eq!(request_reset(&client, &user.login), Status::NoContent);
+        assert_eq!(request_reset(&client, "unknown-login"), Status::NoContent);
+    }
+}
@@ -0,0 +1,226 @@
+use rocket::{http::Status, serde::json::Json, State};
+
+use crate::{
+    core::{
+        guards::connected_user::ConnectedUser,
+        openapi::{OpenApi, OpenApiOperation},
+        response::ApiResponse,
+        security::{Security, SecurityVoter},
+        validation::Validated,
+    },
+    domain::dto::user::{CurrentUserDTO, UpdateProfileInputDTO, UserDetailsDTO},
+    exceptions::dto::http_exception::HttpException,
+    http_exception, http_ok,
+    middlewares::{
+        account_middleware::AccountMiddleware, application_middleware::ApplicationMiddleware,
+        user_middleware::UserMiddleware,
+    },
+};
+
+#[get("/me", format = "json")]
+pub fn me_details(
+    connected_user: ConnectedUser,
+    user_middleware: &State<UserMiddleware>,
+    application_middleware: &State<ApplicationMiddleware>,
+    account_middleware: &State<AccountMiddleware>,
+    security: &State<Security<dyn SecurityVoter>>,
+) -> Result<ApiResponse<CurrentUserDTO>, ApiResponse<HttpException>> {
+    let user = &connected_user.user;
+
+    let application = application_middleware.find_one_by_id(user.application_id);
+
+    if application.is_err() {
+        http_exception!(Status::InternalServerError);
+    }
+
+    let application = application.unwrap();
+
+    if application.is_none() {
+        http_exception!(Status::NotFound, "Cannot find your application.");
+    }
+
+    let application = application.unwrap();
+
+    let account = account_middleware.find_one_by_id(application.account_id);
+
+    if account.is_err() {
+        http_exception!(Status::InternalServerError);
+    }
+
+    let account = account.unwrap();
+
+    if account.is_none() {
+        http_exception!(Status::NotFound, "Cannot find your account.");
+    }
+
+    http_ok!(CurrentUserDTO {
+        user: user_middleware.to_details_dto(user),
+        application: application_middleware.to_details_dto(&application),
+        account: account_middleware.to_details_dto(&account.unwrap()),
+        permissions: security.get_granted_rights(user),
+        impersonator_id: connected_user
+            .impersonator
+            .as_ref()
+            .map(|impersonator| impersonator.id),
+    });
+}
+
+#[patch("/me", format = "json", data = "<input>")]
+pub fn me_update(
+    input: Validated<Json<UpdateProfileInputDTO>>,
+    connected_user: ConnectedUser,
+    user_middleware: &State<UserMiddleware>,
+) -> Result<ApiResponse<UserDetailsDTO>, ApiResponse<HttpException>> {
+    let user = &connected_user.user;
+
+    let dto = input.into_deep_inner();
+
+    let updated_user = user_middleware.update_profile(user, dto);
+
+    if updated_user.is_err() {
+        http_exception!(Status::InternalServerError);
+    }
+
+    let mut updated_user = updated_user.unwrap();
+    // the stored roles are returned by the database, the connected user has the effective ones.
+    updated_user.roles = user.roles.clone();
+
+    http_ok!(user_middleware.to_details_dto(&updated_user));
+}
+
+/// documents the routes of this controller, mounted on `base`.
+pub fn document(openapi: &mut OpenApi, base: &str) {
+    openapi
+        .document(
+            base,
+            routes![me_details],
+            OpenApiOperation::new("Get the connected user")
+                .output::<CurrentUserDTO>()
+                .authenticated(),
+        )
+        .document(
+            base,
+            routes![me_update],
+            OpenApiOperation::new("Update the profile of the connected user")
+                .input::<UpdateProfileInputDTO>()
+                .output::<UserDetailsDTO>()
+                .authenticated(),
+        );
+}
+
+#[cfg(test)]
+mod tests {
+    use rocket::{
+        http::{ContentType, Header},
+        local::blocking::Client,
+    };
+    use serde_json::Value;
+
+    use super::*;
+    use crate::{
+        core::{
+            rocket_factory::create_security,
+            role_hierarchy::RoleHierarchy,
+            security_policy::SecurityPolicy,
+            testing::{
+                create_test_application, create_test_user, get_test_configuration,
+                get_test_db_state, get_test_key_store,
+            },
+        },
+        domain::{
+            dto::role::NewRoleInputDTO,
+            repository::{
+                account_repository::AccountRepository,
+                application_repository::ApplicationRepository,
+                jwt_denylist_repository::JWTDenylistRepository, role_repository::RoleRepository,
+                user_repository::UserRepository,
+            },
+        },
+        middlewares::{
+            jwt_denylist_middleware::JWTDenylistMiddleware, role_middleware::RoleMiddleware,
+        },
+    };
+
+    fn get_client() -> Client {
+        let db_state = get_test_db_state();
+        let configuration = get_test_configuration();
+
+        let rocket = rocket::build()
+            .mount("/api", routes![me_details])
+            .manage(create_security(SecurityPolicy::default()))
+            .manage(UserMiddleware::new(
+                UserRepository::new(db_state.clone()),
+                JWTDenylistMiddleware::new(JWTDenylistRepository::new(db_state.clone())),
+                get_test_key_store(),
+                configuration.clone(),
+            ))
+            .manage(RoleMiddleware::new(
+                RoleRepository::new(db_state.clone()),
+                UserRepository::new(db_state.clone()),
+                RoleHierarchy::from_config(&configuration).unwrap(),
+            ))
+            .manage(ApplicationMiddleware::new(
+                ApplicationRepository::new(db_state.clone()),
+                AccountRepository::new(db_state.clone()),
+            ))
+            .manage(AccountMiddleware::new(
+                AccountRepository::new(db_state.clone()),
+                ApplicationRepository::new(db_state),
+            ));
+
+        Client::untracked(rocket).unwrap()
+    }
+
+    #[test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    fn test_me_returns_the_effective_roles() {
+        let db_state = get_test_db_state();
+        let client = get_client();
+        let user_middleware = client.rocket().state::<UserMiddleware>().unwrap();
+        let role_middleware = client.rocket().state::<RoleMiddleware>().unwrap();
+        let application = create_test_application(&db_state);
+
+        let mut creator = create_test_user(&db_state, &application, &["ROLE_USER_ADMIN"]);
+        creator.roles = role_middleware.resolve_roles(&creator).unwrap();
+        role_middleware
+            .create(
+                &creator,
+                NewRoleInputDTO {
+                    name: "ROLE_SUPPORT".into(),
+                    implied_roles: vec!["ROLE_USER_ADMIN".into()],
+                    description: None,
+                },
+            )
+            .unwrap();
+        let user = create_test_user(&db_state, &application, &["ROLE_SUPPORT"]);
+
+        let response = client
+            .get("/api/me")
+            .header(ContentType::JSON)
+            .header(Header::new(
+                "Authorization",
+                format!(
+                    "Bearer {}",
+                    user_middleware.create_jwt_for_user(&user).unwrap()
+                ),
+            ))
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+
+        let output = response.into_json::<Value>().unwrap();
+        let mut roles = output["roles"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|role| role.as_str().unwrap())
+            .collect::<Vec<&str>>();
+        roles.sort();
+
+        // the custom role implies the admin role, which implies the user one.
+        assert_eq!(roles, vec!["ROLE_SUPPORT", "ROLE_USER", "ROLE_USER_ADMIN"]);
+        assert!(output["permissions"]
+            .as_array()
+            .unwrap()
+            .contains(&Value::from("user:create")));
+    }
+}
@@ -1,4 +1,7 @@
 pub mod account;
 pub mod application;
 pub mod auth;
+pub mod me;
+pub mod role;
+pub mod totp;
 pub mod user;
@@ -0,0 +1,105 @@
+use rocket::{http::Status, serde::json::Json, State};
+
+use crate::{
+    core::{
+        guards::granted::{CreateRight, DeleteRight, Granted, ListRight},
+        openapi::{OpenApi, OpenApiOperation},
+        response::{ApiResponse, NoContentResponse},
+        validation::Validated,
+    },
+    domain::dto::role::{NewRoleInputDTO, RoleDTO},
+    exceptions::dto::http_exception::HttpException,
+    http_exception, http_no_content, http_ok,
+    middlewares::role_middleware::{RoleError, RoleMiddleware},
+    security::voters::role_security::RoleSubject,
+};
+
+#[get("/roles", format = "json")]
+pub fn role_list(
+    granted: Granted<RoleSubject, ListRight>,
+    role_middleware: &State<RoleMiddleware>,
+) -> Result<ApiResponse<Vec<RoleDTO>>, ApiResponse<HttpException>> {
+    let user = &granted.user;
+
+    let custom_roles = role_middleware.find_all_for_application(user.application_id);
+
+    if let Err(error) = custom_roles {
+        http_exception!(Status::InternalServerError, &error.to_string());
+    }
+
+    http_ok!(role_middleware.to_list_dto(custom_roles.unwrap()));
+}
+
+#[post("/roles", format = "json", data = "<input>")]
+pub fn role_create(
+    input: Validated<Json<NewRoleInputDTO>>,
+    granted: Granted<RoleSubject, CreateRight>,
+    role_middleware: &State<RoleMiddleware>,
+) -> Result<ApiResponse<RoleDTO>, ApiResponse<HttpException>> {
+    let user = &granted.user;
+
+    let created_role = role_middleware.create(user, input.into_deep_inner());
+
+    match created_role {
+        Ok(role) => http_ok!(RoleDTO::from(role)),
+        Err(RoleError::NotHeld(role)) => {
+            http_exception!(
+                Status::Forbidden,
+                &format!("You cannot imply role {role} as you do not have it.")
+            );
+        }
+        Err(RoleError::Internal(message)) => {
+            http_exception!(Status::InternalServerError, &message);
+        }
+        Err(error) => {
+            http_exception!(Status::BadRequest, &error.to_string());
+        }
+    }
+}
+
+#[delete("/roles/<name>", format = "json")]
+pub fn role_delete(
+    name: String,
+    granted: Granted<RoleSubject, DeleteRight>,
+    role_middleware: &State<RoleMiddleware>,
+) -> Result<ApiResponse<NoContentResponse>, ApiResponse<HttpException>> {
+    let user = &granted.user;
+
+    match role_middleware.delete(user.application_id, &name) {
+        Ok(()) => http_no_content!(),
+        Err(RoleError::UnknownRole(_)) => {
+            http_exception!(Status::NotFound, "Cannot find role to delete.");
+        }
+        Err(RoleError::Internal(message)) => {
+            http_exception!(Status::InternalServerError, &message);
+        }
+        Err(error) => {
+            http_exception!(Status::BadRequest, &error.to_string());
+        }
+    }
+}
+
+/// documents the routes of this controller, mounted on `base`.
+pub fn document(openapi: &mut OpenApi, base: &str) {
+    openapi
+        .document(
+            base,
+            routes![role_list],
+            OpenApiOperation::new("List roles")
+                .output::<Vec<RoleDTO>>()
+                .authenticated(),
+        )
+        .document(
+            base,
+            routes![role_create],
+            OpenApiOperation::new("Create a custom role")
+                .input::<NewRoleInputDTO>()
+                .output::<RoleDTO>()
+                .authenticated(),
+        )
+        .document(
+            base,
+            routes![role_delete],
+            OpenApiOperation::new("Delete a custom role").authenticated(),
+        );
+}
@@ -0,0 +1,106 @@
+use rocket::{http::Status, serde::json::Json, State};
+
+use crate::{
+    core::{
+        guards::connected_user::ConnectedUser,
+        openapi::{OpenApi, OpenApiOperation},
+        response::ApiResponse,
+        validation::Validated,
+    },
+    deny_access_if_impersonated,
+    domain::dto::totp::{RecoveryCodesOutputDTO, TOTPCodeInputDTO, TOTPEnrolmentOutputDTO},
+    exceptions::dto::http_exception::HttpException,
+    http_exception, http_ok,
+    middlewares::totp_middleware::{MFAError, TOTPMiddleware},
+};
+
+#[post("/users/me/totp", format = "json")]
+pub fn totp_enroll(
+    connected_user: ConnectedUser,
+    totp_middleware: &State<TOTPMiddleware>,
+) -> Result<ApiResponse<TOTPEnrolmentOutputDTO>, ApiResponse<HttpException>> {
+    deny_access_if_impersonated!(connected_user);
+
+    let user = &connected_user.user;
+
+    let enrolment = totp_middleware.enroll(user);
+
+    if let Err(mfa_error) = enrolment {
+        match mfa_error {
+            MFAError::AlreadyEnabled => {
+                http_exception!(
+                    Status::BadRequest,
+                    "Two-factor authentication is already enabled."
+                );
+            }
+            _ => {
+                http_exception!(Status::InternalServerError);
+            }
+        }
+    }
+
+    let (secret, otpauth_uri) = enrolment.unwrap();
+
+    http_ok!(TOTPEnrolmentOutputDTO {
+        secret,
+        otpauth_uri,
+    });
+}
+
+#[post("/users/me/totp/verify", format = "json", data = "<input>")]
+pub fn totp_verify(
+    input: Validated<Json<TOTPCodeInputDTO>>,
+    connected_user: ConnectedUser,
+    totp_middleware: &State<TOTPMiddleware>,
+) -> Result<ApiResponse<RecoveryCodesOutputDTO>, ApiResponse<HttpException>> {
+    deny_access_if_impersonated!(connected_user);
+
+    let user = &connected_user.user;
+    let input = input.into_deep_inner();
+
+    let recovery_codes = totp_middleware.confirm_enrolment(user, &input.code);
+
+    if let Err(mfa_error) = recovery_codes {
+        match mfa_error {
+            MFAError::NotEnrolled => {
+                http_exception!(Status::NotFound, "No pending two-factor enrolment.");
+            }
+            MFAError::AlreadyEnabled => {
+                http_exception!(
+                    Status::BadRequest,
+                    "Two-factor authentication is already enabled."
+                );
+            }
+            MFAError::InvalidCode => {
+                http_exception!(Status::BadRequest, "Invalid code.");
+            }
+            _ => {
+                http_exception!(Status::InternalServerError);
+            }
+        }
+    }
+
+    http_ok!(RecoveryCodesOutputDTO {
+        recovery_codes: recovery_codes.unwrap(),
+    });
+}
+
+/// documents the routes of this controller, mounted on `base`.
+pub fn document(openapi: &mut OpenApi, base: &str) {
+    openapi
+        .document(
+            base,
+            routes![totp_enroll],
+            OpenApiOperation::new("Start two-factor authentication enrolment")
+                .output::<TOTPEnrolmentOutputDTO>()
+                .authenticated(),
+        )
+        .document(
+            base,
+            routes![totp_verify],
+            OpenApiOperation::new("Confirm two-factor authentication enrolment")
+                .input::<TOTPCodeInputDTO>()
+                .output::<RecoveryCodesOutputDTO>()
+                .authenticated(),
+        );
+}
@@ -1,57 +1,146 @@
 use map_macro::map;
 use rocket::{http::Status, serde::json::Json, State};
 
-use crate::core::security::is_admin;
+use crate::core::security::{is_a, is_admin};
 use crate::core::validation::Validated;
 use crate::extract_message;
 use crate::{
     core::{
-        guards::{connected_user::ConnectedUser, pagination::Pagination},
-        response::{ApiResponse, NoContentResponse},
+        guards::{
+            connected_user::ConnectedUser,
+            cursor_pagination::CursorPagination,
+            granted::{CreateRight, DeleteRight, DetailsRight, Granted, ListRight},
+            granted_application::GrantedApplication,
+            list_query::ListQuery,
+            pagination::Pagination,
+        },
+        openapi::{OpenApi, OpenApiOperation},
+        response::{ApiResponse, CursorPaginated, NoContentResponse, Paginated},
         security::{Security, SecurityVoter},
     },
-    deny_access_unless_granted,
-    domain::dto::user::{NewUserInputDTO, UpdateUserInputDTO, UserDetailsDTO, UserListItemDTO},
+    deny_access_if_impersonated, deny_access_unless_granted,
+    domain::dto::{
+        auth::{ImpersonationInputDTO, ImpersonationOutputDTO},
+        role::RoleAssignmentInputDTO,
+        user::{
+            NewUserInputDTO, UpdatePasswordInputDTO, UpdateUserInputDTO, UserDetailsDTO,
+            UserListItemDTO,
+        },
+    },
     exceptions::dto::http_exception::HttpException,
-    http_exception, http_no_content, http_ok,
-    middlewares::user_middleware::UserMiddleware,
+    http_exception, http_no_content, http_ok, http_paginated,
+    middlewares::{
+        impersonation_middleware::{ImpersonationError, ImpersonationMiddleware},
+        refresh_token_middleware::RefreshTokenMiddleware,
+        role_middleware::{RoleError, RoleMiddleware},
+        user_middleware::{AuthenticationError, UserMiddleware},
+    },
+    security::voters::user_security::{ImpersonateRight, RolesRight, UserSubject},
 };
 
-#[get("/users", format = "json")]
-pub fn user_list(
-    connected_user: ConnectedUser,
+#[get("/users", format = "json", rank = 0)]
+pub fn user_list_for_application(
+    granted: GrantedApplication<UserSubject, ListRight>,
     user_middleware: &State<UserMiddleware>,
-    security: &State<Security<dyn SecurityVoter>>,
     pagination: Pagination,
-) -> Result<ApiResponse<Vec<UserListItemDTO>>, ApiResponse<HttpException>> {
-    let user = &connected_user.user;
+    list_query: ListQuery<UserListItemDTO>,
+) -> Result<ApiResponse<Paginated<UserListItemDTO>>, ApiResponse<HttpException>> {
+    let application = &granted.application;
+
+    let list = user_middleware.find_for_application(
+        application,
+        &list_query,
+        pagination.page.into(),
+        pagination.per_page.into(),
+    );
+
+    if list.is_err() {
+        http_exception!(Status::InternalServerError, &extract_message!(list));
+    }
+
+    let total = user_middleware.count_for_application(application, &list_query);
+
+    if total.is_err() {
+        http_exception!(Status::InternalServerError, &extract_message!(total));
+    }
+
+    let page = Paginated::new(list.unwrap(), &pagination, total.unwrap());
+
+    http_paginated!(page.map(|list| user_middleware.to_list_dto(list)));
+}
+
+#[get("/users", format = "json", rank = 1)]
+pub fn user_list_by_cursor(
+    cursor_pagination: CursorPagination,
+    granted: Granted<UserSubject, ListRight>,
+    user_middleware: &State<UserMiddleware>,
+    list_query: ListQuery<UserListItemDTO>,
+) -> Result<ApiResponse<CursorPaginated<UserListItemDTO>>, ApiResponse<HttpException>> {
+    let user = &granted.user;
+
+    if !list_query.sorts.is_empty() {
+        http_exception!(
+            Status::BadRequest,
+            "sort is not supported with cursor pagination"
+        );
+    }
+
+    let list = user_middleware.find_for_user_after(
+        user,
+        &list_query,
+        cursor_pagination.cursor.as_ref(),
+        cursor_pagination.limit.into(),
+    );
+
+    if list.is_err() {
+        http_exception!(Status::InternalServerError, &extract_message!(list));
+    }
 
-    deny_access_unless_granted!(security, user, "user", "list");
+    let page = CursorPaginated::new(list.unwrap(), &cursor_pagination, |user| {
+        (user.created_date, user.id)
+    });
 
-    let list =
-        user_middleware.find_for_user(user, pagination.page.into(), pagination.per_page.into());
+    http_paginated!(page.map(|list| user_middleware.to_list_dto(list)));
+}
+
+#[get("/users", format = "json", rank = 2)]
+pub fn user_list(
+    granted: Granted<UserSubject, ListRight>,
+    user_middleware: &State<UserMiddleware>,
+    pagination: Pagination,
+    list_query: ListQuery<UserListItemDTO>,
+) -> Result<ApiResponse<Paginated<UserListItemDTO>>, ApiResponse<HttpException>> {
+    let user = &granted.user;
+
+    let list = user_middleware.find_for_user(
+        user,
+        &list_query,
+        pagination.page.into(),
+        pagination.per_page.into(),
+    );
 
     if list.is_err() {
         http_exception!(Status::InternalServerError, &extract_message!(list));
     }
 
-    let list = list.unwrap();
+    let total = user_middleware.count_for_user(user, &list_query);
+
+    if total.is_err() {
+        http_exception!(Status::InternalServerError, &extract_message!(total));
+    }
 
-    let dto_list = user_middleware.to_list_dto(list);
+    let page = Paginated::new(list.unwrap(), &pagination, total.unwrap());
 
-    http_ok!(dto_list);
+    http_paginated!(page.map(|list| user_middleware.to_list_dto(list)));
 }
 
 #[get("/users/<id>", format = "json")]
 pub fn user_details(
     id: String,
-    connected_user: ConnectedUser,
+    granted: Granted<UserSubject, DetailsRight>,
     user_middleware: &State<UserMiddleware>,
-    security: &State<Security<dyn SecurityVoter>>,
 ) -> Result<ApiResponse<UserDetailsDTO>, ApiResponse<HttpException>> {
-    let user = &connected_user.user;
-
-    deny_access_unless_granted!(security, user, "user", "details");
+    let user = &granted.user;
 
     let user = user_middleware.find_one_for_user(&id, user);
 
@@ -74,13 +163,10 @@ pub fn user_details(
 #[post("/users", format = "json", data = "<input>")]
 pub fn user_create(
     input: Validated<Json<NewUserInputDTO>>,
-    connected_user: ConnectedUser,
+    granted: Granted<UserSubject, CreateRight>,
     user_middleware: &State<UserMiddleware>,
-    security: &State<Security<dyn SecurityVoter>>,
 ) -> Result<ApiResponse<UserDetailsDTO>, ApiResponse<HttpException>> {
-    let creator = &connected_user.user;
-
-    deny_access_unless_granted!(security, creator, "user", "create");
+    let creator = &granted.user;
 
     let dto = input.into_deep_inner();
 
@@ -167,16 +253,55 @@ pub fn user_update(
     http_ok!(output);
 }
 
+#[put("/users/me/password", format = "json", data = "<input>")]
+pub fn user_update_password(
+    input: Validated<Json<UpdatePasswordInputDTO>>,
+    connected_user: ConnectedUser,
+    user_middleware: &State<UserMiddleware>,
+    refresh_token_middleware: &State<RefreshTokenMiddleware>,
+) -> Result<ApiResponse<NoContentResponse>, ApiResponse<HttpException>> {
+    deny_access_if_impersonated!(connected_user);
+
+    let user = &connected_user.user;
+
+    let dto = input.into_deep_inner();
+
+    let updated_user = user_middleware.change_password(user, &dto.current_password, &dto.password);
+
+    if let Err(error) = updated_user {
+        if let Some(AuthenticationError::WrongPassword(_)) =
+            error.downcast_ref::<AuthenticationError>()
+        {
+            http_exception!(Status::BadRequest, "Current password is invalid.");
+        }
+
+        http_exception!(Status::InternalServerError);
+    }
+
+    // every session (including the current one) is closed, sessions opened with the old password must not survive.
+    if refresh_token_middleware.revoke_all_for_user(user).is_err() {
+        http_exception!(
+            Status::InternalServerError,
+            "Could not revoke refresh tokens"
+        );
+    }
+
+    if user_middleware.revoke_all_jwt(user).is_err() {
+        http_exception!(Status::InternalServerError, "Could not revoke JWT tokens");
+    }
+
+    http_no_content!()
+}
+
 #[delete("/users/<id>", format = "json")]
 pub fn user_delete(
     id: i32,
-    connected_user: ConnectedUser,
+    granted: Granted<UserSubject, DeleteRight>,
     user_middleware: &State<UserMiddleware>,
-    security: &State<Security<dyn SecurityVoter>>,
 ) -> Result<ApiResponse<NoContentResponse>, ApiResponse<HttpException>> {
-    let deleter = &connected_user.user;
+    deny_access_if_impersonated!(granted);
 
-    deny_access_unless_granted!(security, deleter, "user", "delete");
+    let deleter = &granted.user;
 
     let parsed_id = format!("{id}");
 
@@ -211,3 +336,593 @@ pub fn user_delete(
 
     http_no_content!()
 }
+
+#[put("/users/<id>/roles", format = "json", data = "<input>")]
+pub fn user_role_grant(
+    id: String,
+    input: Validated<Json<RoleAssignmentInputDTO>>,
+    granted: Granted<UserSubject, RolesRight>,
+    user_middleware: &State<UserMiddleware>,
+    role_middleware: &State<RoleMiddleware>,
+    refresh_token_middleware: &State<RefreshTokenMiddleware>,
+) -> Result<ApiResponse<UserDetailsDTO>, ApiResponse<HttpException>> {
+    deny_access_if_impersonated!(granted);
+
+    let granter = &granted.user;
+
+    let role = input.into_deep_inner().role;
+
+    match role_middleware.check_grantable(granter, &role) {
+        Ok(()) => {}
+        Err(RoleError::NotHeld(_)) => {
+            http_exception!(
+                Status::Forbidden,
+                &format!("You cannot grant role {role} as you do not have it.")
+            );
+        }
+        Err(RoleError::Internal(message)) => {
+            http_exception!(Status::InternalServerError, &message);
+        }
+        Err(_) => {
+            http_exception!(Status::BadRequest, &format!("Role {role} is not defined."));
+        }
+    }
+
+    // only users of the granter's application are found.
+    let user = user_middleware.find_one_for_user(&id, granter);
+
+    if user.is_err() {
+        http_exception!(Status::InternalServerError, &extract_message!(user));
+    }
+
+    let user = user.unwrap();
+
+    if user.is_none() {
+        http_exception!(Status::NotFound, "Cannot find user to grant role to.");
+    }
+
+    let updated_user = user_middleware.promote(&user.unwrap(), &role);
+
+    if updated_user.is_err() {
+        let message = extract_message!(updated_user);
+
+        if message.contains("already has role") {
+            http_exception!(Status::BadRequest, &message);
+        }
+
+        http_exception!(Status::InternalServerError, &message);
+    }
+
+    // sessions are closed, so the user logs in again with its new roles.
+    if refresh_token_middleware
+        .revoke_all_for_user(updated_user.as_ref().unwrap())
+        .is_err()
+    {
+        http_exception!(
+            Status::InternalServerError,
+            "Could not revoke refresh tokens"
+        );
+    }
+
+    let output = user_middleware.to_details_dto(&updated_user.unwrap());
+
+    http_ok!(output);
+}
+
+#[delete("/users/<id>/roles/<role>", format = "json")]
+pub fn user_role_revoke(
+    id: String,
+    role: String,
+    granted: Granted<UserSubject, RolesRight>,
+    user_middleware: &State<UserMiddleware>,
+    role_middleware: &State<RoleMiddleware>,
+    refresh_token_middleware: &State<RefreshTokenMiddleware>,
+) -> Result<ApiResponse<UserDetailsDTO>, ApiResponse<HttpException>> {
+    deny_access_if_impersonated!(granted);
+
+    let revoker = &granted.user;
+
+    if !is_a(&role, revoker) {
+        http_exception!(
+            Status::Forbidden,
+            &format!("You cannot revoke role {role} as you do not have it.")
+        );
+    }
+
+    // only users of the revoker's application are found.
+    let user = user_middleware.find_one_for_user(&id, revoker);
+
+    if user.is_err() {
+        http_exception!(Status::InternalServerError, &extract_message!(user));
+    }
+
+    let user = user.unwrap();
+
+    if user.is_none() {
+        http_exception!(Status::NotFound, "Cannot find user to revoke role from.");
+    }
+
+    let user = user.unwrap();
+
+    if user.id == revoker.id {
+        let mut remaining = user.clone();
+        remaining.roles.retain(|user_role| *user_role != role);
+
+        let remaining_roles = role_middleware.resolve_roles(&remaining);
+
+        if remaining_roles.is_err() {
+            http_exception!(
+                Status::InternalServerError,
+                &extract_message!(remaining_roles)
+            );
+        }
+
+        remaining.roles = remaining_roles.unwrap();
+
+        if !is_admin(&remaining) {
+            http_exception!(
+                Status::BadRequest,
+                "You cannot remove your own last admin role."
+            );
+        }
+    }
+
+    let updated_user = user_middleware.demote(&user, &role);
+
+    if updated_user.is_err() {
+        let message = extract_message!(updated_user);
+
+        if message.contains("does not have role") {
+            http_exception!(Status::BadRequest, &message);
+        }
+
+        http_exception!(Status::InternalServerError, &message);
+    }
+
+    if refresh_token_middleware
+        .revoke_all_for_user(updated_user.as_ref().unwrap())
+        .is_err()
+    {
+        http_exception!(
+            Status::InternalServerError,
+            "Could not revoke refresh tokens"
+        );
+    }
+
+    let output = user_middleware.to_details_dto(&updated_user.unwrap());
+
+    http_ok!(output);
+}
+
+#[post("/users/<id>/impersonate", format = "json", data = "<input>")]
+pub fn user_impersonate(
+    id: i32,
+    input: Validated<Json<ImpersonationInputDTO>>,
+    granted: Granted<UserSubject, ImpersonateRight>,
+    impersonation_middleware: &State<ImpersonationMiddleware>,
+) -> Result<ApiResponse<ImpersonationOutputDTO>, ApiResponse<HttpException>> {
+    // an impersonated session cannot chain impersonations.
+    deny_access_if_impersonated!(granted);
+
+    let impersonator = &granted.user;
+
+    let dto = input.into_deep_inner();
+
+    let impersonation =
+        impersonation_middleware.impersonate(impersonator, id, dto.reason.as_deref());
+
+    if let Err(impersonation_error) = impersonation {
+        match impersonation_error {
+            ImpersonationError::UserNotFound(_) => {
+                http_exception!(Status::NotFound, "Cannot find user to impersonate.");
+            }
+            ImpersonationError::SelfImpersonation => {
+                http_exception!(Status::BadRequest, "You cannot impersonate yourself.");
+            }
+            ImpersonationError::NotAllowed(_) => {
+                http_exception!(Status::Forbidden, &impersonation_error.to_string());
+            }
+            ImpersonationError::Internal(_) => {
+                http_exception!(Status::InternalServerError);
+            }
+        }
+    }
+
+    let (token, expiration_date) = impersonation.unwrap();
+
+    http_ok!(ImpersonationOutputDTO {
+        token,
+        expiration_date,
+        impersonator_id: impersonator.id,
+    });
+}
+
+/// documents the routes of this controller, mounted on `base`.
+pub fn document(openapi: &mut OpenApi, base: &str) {
+    openapi
+        .document(
+            base,
+            routes![user_list_for_application, user_list_by_cursor, user_list],
+            OpenApiOperation::new("List users")
+                .paginated::<UserListItemDTO>()
+                .list_query::<UserListItemDTO>()
+                .cursor_paginated::<UserListItemDTO>()
+                .authenticated()
+                .api_key(),
+        )
+        .document(
+            base,
+            routes![user_details],
+            OpenApiOperation::new("Get a user")
+                .output::<UserDetailsDTO>()
+                .authenticated(),
+        )
+        .document(
+            base,
+            routes![user_create],
+            OpenApiOperation::new("Create a user")
+                .input::<NewUserInputDTO>()
+                .output::<UserDetailsDTO>()
+                .authenticated(),
+        )
+        .document(
+            base,
+            routes![user_update],
+            OpenApiOperation::new("Update a user")
+                .input::<UpdateUserInputDTO>()
+                .output::<UserDetailsDTO>()
+                .authenticated(),
+        )
+        .document(
+            base,
+            routes![user_update_password],
+            OpenApiOperation::new(
+                "Change the password of the connected user, closing every session",
+            )
+            .input::<UpdatePasswordInputDTO>()
+            .authenticated(),
+        )
+        .document(
+            base,
+            routes![user_delete],
+            OpenApiOperation::new("Delete a user").authenticated(),
+        )
+        .document(
+            base,
+            routes![user_role_grant],
+            OpenApiOperation::new("Grant a role to a user")
+                .input::<RoleAssignmentInputDTO>()
+                .output::<UserDetailsDTO>()
+                .authenticated(),
+        )
+        .document(
+            base,
+            routes![user_role_revoke],
+            OpenApiOperation::new("Revoke a role from a user")
+                .output::<UserDetailsDTO>()
+                .authenticated(),
+        )
+        .document(
+            base,
+            routes![user_impersonate],
+            OpenApiOperation::new("Get a short-lived token to act as a user")
+                .input::<ImpersonationInputDTO>()
+                .output::<ImpersonationOutputDTO>()
+                .authenticated(),
+        );
+}
+
+#[cfg(test)]
+mod tests {
+    use rocket::{
+        http::{ContentType, Header},
+        local::blocking::Client,
+    };
+    use serde_json::Value;
+
+    use super::*;
+    use crate::{
+        controllers::api::auth,
+        core::{
+            rocket_factory::create_security,
+            role_hierarchy::RoleHierarchy,
+            security_policy::SecurityPolicy,
+            testing::{
+                create_test_application, create_test_user, get_test_configuration,
+                get_test_db_state, get_test_key_store,
+            },
+        },
+        domain::{
+            dto::role::NewRoleInputDTO,
+            model::user::User,
+            repository::{
+                impersonation_log_repository::ImpersonationLogRepository,
+                jwt_denylist_repository::JWTDenylistRepository,
+                refresh_token_repository::RefreshTokenRepository, role_repository::RoleRepository,
+                user_repository::UserRepository,
+            },
+        },
+        middlewares::{
+            jwt_denylist_middleware::JWTDenylistMiddleware,
+            refresh_token_middleware::JWTRefreshTokenValidationError,
+        },
+    };
+
+    const PASSWORD: &str = "Passw0rd!";
+
+    fn get_client() -> Client {
+        let db_state = get_test_db_state();
+        let configuration = get_test_configuration();
+        let jwt_key_store = get_test_key_store();
+
+        let rocket = rocket::build()
+            .mount(
+                "/api",
+                routes![user_update_password, user_impersonate, user_role_revoke],
+            )
+            .mount("/api/auth", routes![auth::logout_all])
+            .manage(create_security(SecurityPolicy::default()))
+            .manage(UserMiddleware::new(
+                UserRepository::new(db_state.clone()),
+                JWTDenylistMiddleware::new(JWTDenylistRepository::new(db_state.clone())),
+                jwt_key_store.clone(),
+                configuration.clone(),
+            ))
+            .manage(ImpersonationMiddleware::new(
+                ImpersonationLogRepository::new(db_state.clone()),
+                UserRepository::new(db_state.clone()),
+                jwt_key_store,
+                configuration.clone(),
+            ))
+            .manage(RoleMiddleware::new(
+                RoleRepository::new(db_state.clone()),
+                UserRepository::new(db_state.clone()),
+                RoleHierarchy::from_config(&configuration).unwrap(),
+            ))
+            .manage(RefreshTokenMiddleware::new(
+                RefreshTokenRepository::new(db_state),
+                configuration,
+            ));
+
+        Client::untracked(rocket).unwrap()
+    }
+
+    fn change_password(client: &Client, jwt: &str, current_password: &str) -> Status {
+        client
+            .put("/api/users/me/password")
+            .header(ContentType::JSON)
+            .header(Header::new("Authorization", format!("Bearer {jwt}")))
+            .body(format!(
+                r#"{{"current_password": "{current_password}", "password": "N3w-Passw0rd!"}}"#
+            ))
+            .dispatch()
+            .status()
+    }
+
+    /// impersonates a user with a JWT, giving the status and the output.
+    fn impersonate(client: &Client, jwt: &str, impersonated: &User) -> (Status, Value) {
+        let response = client
+            .post(format!("/api/users/{}/impersonate", impersonated.id))
+            .header(ContentType::JSON)
+            .header(Header::new("Authorization", format!("Bearer {jwt}")))
+            .body(r#"{"reason": "support ticket"}"#)
+            .dispatch();
+
+        (
+            response.status(),
+            response.into_json::<Value>().unwrap_or_default(),
+        )
+    }
+
+    fn get_jwt(client: &Client, user: &User) -> String {
+        let user_middleware = client.rocket().state::<UserMiddleware>().unwrap();
+
+        user_middleware.create_jwt_for_user(user).unwrap()
+    }
+
+    #[test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    fn test_impersonation_requires_an_admin_role() {
+        let db_state = get_test_db_state();
+        let client = get_client();
+        let application = create_test_application(&db_state);
+        let user = create_test_user(&db_state, &application, &["ROLE_USER"]);
+        let other_user = create_test_user(&db_state, &application, &["ROLE_USER"]);
+
+        let (status, _) = impersonate(&client, &get_jwt(&client, &user), &other_user);
+
+        assert_eq!(status, Status::Forbidden);
+    }
+
+    #[test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    fn test_impersonation_token_carries_the_impersonator() {
+        let db_state = get_test_db_state();
+        let client = get_client();
+        let user_middleware = client.rocket().state::<UserMiddleware>().unwrap();
+        let application = create_test_application(&db_state);
+        let admin = create_test_user(&db_state, &application, &["ROLE_USER_ADMIN"]);
+        let user = create_test_user(&db_state, &application, &["ROLE_USER"]);
+
+        let (status, output) = impersonate(&client, &get_jwt(&client, &admin), &user);
+        assert_eq!(status, Status::Ok);
+        assert_eq!(output["impersonator_id"], admin.id);
+
+        let (impersonated, claims) = user_middleware
+            .authenticate_user_from_jwt(output["token"].as_str().unwrap())
+            .unwrap();
+        assert_eq!(impersonated.id, user.id);
+        assert_eq!(claims.custom.impersonator_id, Some(admin.id));
+
+        // users of other applications cannot be impersonated.
+        let other_user = create_test_user(&db_state, &create_test_application(&db_state), &[]);
+        let (status, _) = impersonate(&client, &get_jwt(&client, &admin), &other_user);
+        assert_eq!(status, Status::NotFound);
+    }
+
+    #[test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    fn test_impersonated_sessions_cannot_impersonate_or_log_out_everywhere() {
+        let db_state = get_test_db_state();
+        let client = get_client();
+        let refresh_token_middleware = client.rocket().state::<RefreshTokenMiddleware>().unwrap();
+        let application = create_test_application(&db_state);
+        let admin = create_test_user(&db_state, &application, &["ROLE_USER_ADMIN"]);
+        let other_admin = create_test_user(&db_state, &application, &["ROLE_USER_ADMIN"]);
+        let user = create_test_user(&db_state, &application, &["ROLE_USER"]);
+        let other_admin_session = refresh_token_middleware
+            .generate_for_user(&other_admin)
+            .unwrap();
+
+        let (status, output) = impersonate(&client, &get_jwt(&client, &admin), &other_admin);
+        assert_eq!(status, Status::Ok);
+        let impersonation_jwt = output["token"].as_str().unwrap();
+
+        let (status, _) = impersonate(&client, impersonation_jwt, &user);
+        assert_eq!(status, Status::Forbidden);
+
+        let status = client
+            .post("/api/auth/logout-all")
+            .header(ContentType::JSON)
+            .header(Header::new(
+                "Authorization",
+                format!("Bearer {impersonation_jwt}"),
+            ))
+            .dispatch()
+            .status();
+        assert_eq!(status, Status::Forbidden);
+        assert!(refresh_token_middleware
+            .is_valid(&other_admin_session.token, None)
+            .is_ok());
+    }
+
+    #[test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    fn test_admins_cannot_impersonate_super_admins() {
+        let db_state = get_test_db_state();
+        let client = get_client();
+        let application = create_test_application(&db_state);
+        let admin = create_test_user(&db_state, &application, &["ROLE_USER_ADMIN"]);
+        let super_admin = create_test_user(&db_state, &application, &["ROLE_SUPER_ADMIN"]);
+
+        let (status, _) = impersonate(&client, &get_jwt(&client, &admin), &super_admin);
+        assert_eq!(status, Status::Forbidden);
+
+        let (status, _) = impersonate(&client, &get_jwt(&client, &super_admin), &admin);
+        assert_eq!(status, Status::Ok);
+    }
+
+    fn revoke_role(client: &Client, jwt: &str, user: &User, role: &str) -> Status {
+        client
+            .delete(format!("/api/users/{}/roles/{role}", user.id))
+            .header(ContentType::JSON)
+            .header(Header::new("Authorization", format!("Bearer {jwt}")))
+            .dispatch()
+            .status()
+    }
+
+    fn get_stored_roles(client: &Client, user: &User) -> Vec<String> {
+        let user_middleware = client.rocket().state::<UserMiddleware>().unwrap();
+
+        user_middleware
+            .find_one_by_id(&user.id.to_string())
+            .unwrap()
+            .unwrap()
+            .roles
+    }
+
+    #[test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    fn test_admins_cannot_revoke_their_own_last_admin_role() {
+        let db_state = get_test_db_state();
+        let client = get_client();
+        let role_middleware = client.rocket().state::<RoleMiddleware>().unwrap();
+        let application = create_test_application(&db_state);
+        let admin = create_test_user(&db_state, &application, &["ROLE_USER_ADMIN"]);
+        let other_admin = create_test_user(&db_state, &application, &["ROLE_USER_ADMIN"]);
+
+        assert_eq!(
+            revoke_role(
+                &client,
+                &get_jwt(&client, &admin),
+                &admin,
+                "ROLE_USER_ADMIN"
+            ),
+            Status::BadRequest
+        );
+        assert_eq!(get_stored_roles(&client, &admin), vec!["ROLE_USER_ADMIN"]);
+
+        // the rule only protects the revoker's own access.
+        assert_eq!(
+            revoke_role(
+                &client,
+                &get_jwt(&client, &admin),
+                &other_admin,
+                "ROLE_USER_ADMIN"
+            ),
+            Status::Ok
+        );
+        assert!(get_stored_roles(&client, &other_admin).is_empty());
+
+        // an admin role still implied by another role can be revoked.
+        let mut creator = admin.clone();
+        creator.roles = role_middleware.resolve_roles(&admin).unwrap();
+        role_middleware
+            .create(
+                &creator,
+                NewRoleInputDTO {
+                    name: "ROLE_MANAGER".into(),
+                    implied_roles: vec!["ROLE_USER_ADMIN".into()],
+                    description: None,
+                },
+            )
+            .unwrap();
+        let manager = create_test_user(
+            &db_state,
+            &application,
+            &["ROLE_USER_ADMIN", "ROLE_MANAGER"],
+        );
+
+        assert_eq!(
+            revoke_role(
+                &client,
+                &get_jwt(&client, &manager),
+                &manager,
+                "ROLE_USER_ADMIN"
+            ),
+            Status::Ok
+        );
+        assert_eq!(get_stored_roles(&client, &manager), vec!["ROLE_MANAGER"]);
+    }
+
+    #[test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    fn test_password_change_closes_every_session() {
+        let db_state = get_test_db_state();
+        let client = get_client();
+        let user_middleware = client.rocket().state::<UserMiddleware>().unwrap();
+        let refresh_token_middleware = client.rocket().state::<RefreshTokenMiddleware>().unwrap();
+
+        let user = create_test_user(&db_state, &create_test_application(&db_state), &[]);
+        let user: User = user_middleware.set_password(&user, PASSWORD).unwrap();
+        let jwt = user_middleware.create_jwt_for_user(&user).unwrap();
+        let refresh_token = refresh_token_middleware.generate_for_user(&user).unwrap();
+
+        // a wrong current password changes nothing.
+        assert_eq!(
+            change_password(&client, &jwt, "wrong-password"),
+            Status::BadRequest
+        );
+        assert!(user_middleware.authenticate_user_from_jwt(&jwt).is_ok());
+        assert!(refresh_token_middleware
+            .is_valid(&refresh_token.token, None)
+            .is_ok());
+
+        assert_eq!(change_password(&client, &jwt, PASSWORD), Status::NoContent);
+        assert!(user_middleware.authenticate_user_from_jwt(&jwt).is_err());
+        assert!(matches!(
+            refresh_token_middleware.is_valid(&refresh_token.token, None),
+            Err(JWTRefreshTokenValidationError::Revoked(_))
+        ));
+    }
+}
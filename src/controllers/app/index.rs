@@ -1,5 +1,6 @@
 use crate::core::{
     configuration::{ConfigState, PublicConfiguration},
+    openapi::{OpenApi, OpenApiOperation},
     response::ApiResponse,
 };
 use rocket::{http::Status, serde::json::Json, State};
@@ -8,8 +9,15 @@ use rocket::{http::Status, serde::json::Json, State};
 pub fn index(config: &State<ConfigState>) -> ApiResponse<PublicConfiguration> {
     let content = Json(PublicConfiguration::from_config_state(config));
 
-    ApiResponse {
-        json: content,
-        status: Status::Ok,
-    }
+    ApiResponse::custom(content, Status::Ok)
+}
+
+/// documents the routes of this controller, mounted on `base`.
+pub fn document(openapi: &mut OpenApi, base: &str) {
+    openapi.document(
+        base,
+        routes![index],
+        OpenApiOperation::new("Get the name and version of the API")
+            .output::<PublicConfiguration>(),
+    );
 }
@@ -1 +1,4 @@
 pub mod index;
+pub mod oauth;
+pub mod openapi;
+pub mod well_known;
@@ -0,0 +1,410 @@
+use rocket::{
+    form::Form,
+    http::{Header, Status},
+    serde::json::Json,
+    State,
+};
+
+use crate::{
+    core::{
+        guards::client_credentials::ClientCredentials,
+        openapi::{OpenApi, OpenApiOperation},
+        response::{ApiResponse, NoContentResponse, WWW_AUTHENTICATE_HEADER},
+    },
+    domain::{
+        dto::oauth::{
+            OAuthErrorOutputDTO, OAuthIntrospectionOutputDTO, OAuthTokenActionInputDTO,
+            OAuthTokenInputDTO, OAuthTokenOutputDTO,
+        },
+        model::oauth_client::OAuthClient,
+    },
+    middlewares::{
+        oauth_middleware::{OAuthError, OAuthMiddleware},
+        refresh_token_middleware::{JWTRefreshTokenValidationError, RefreshTokenMiddleware},
+        user_middleware::UserMiddleware,
+    },
+};
+
+#[post("/token", data = "<input>")]
+pub fn token(
+    input: Form<OAuthTokenInputDTO>,
+    credentials: Option<ClientCredentials>,
+    oauth_middleware: &State<OAuthMiddleware>,
+    user_middleware: &State<UserMiddleware>,
+    refresh_token_middleware: &State<RefreshTokenMiddleware>,
+) -> Result<ApiResponse<OAuthTokenOutputDTO>, ApiResponse<OAuthErrorOutputDTO>> {
+    let input = input.into_inner();
+
+    let client = authenticate_client(
+        oauth_middleware,
+        credentials,
+        input.client_id.as_deref(),
+        input.client_secret.as_deref(),
+    )
+    .map_err(to_error_response)?;
+
+    let scopes = oauth_middleware
+        .grant_scopes(&client, input.scope.as_deref())
+        .map_err(to_error_response)?;
+
+    let (user, refresh_token) = match input.grant_type.as_deref() {
+        // the client acts on its own : no user, and no refresh token (RFC 6749, section 4.4.3).
+        Some("client_credentials") => (None, None),
+        Some("refresh_token") => {
+            let presented_token = input.refresh_token.as_deref().ok_or_else(|| {
+                to_error_response(OAuthError::InvalidRequest(
+                    "Missing refresh_token parameter".into(),
+                ))
+            })?;
+
+            let used_token = refresh_token_middleware
+                // only tokens issued to this client are accepted, so it cannot consume users' sessions.
+                .is_valid(presented_token, Some(&client.client_id))
+                .map_err(|error| to_error_response(to_grant_error(error)))?;
+
+            let user = user_middleware
+                .find_one_by_id(&used_token.user_id.to_string())
+                .map_err(|error| to_error_response(error.into()))?
+                // refresh tokens of other applications' users are unknown to the client.
+                .filter(|user| user.application_id == client.application_id)
+                .ok_or_else(|| {
+                    to_error_response(OAuthError::InvalidGrant("Invalid refresh token".into()))
+                })?;
+
+            let successor = refresh_token_middleware
+                .rotate(&used_token)
+                .map_err(|error| to_error_response(to_grant_error(error)))?;
+
+            (Some(user), Some(successor.token))
+        }
+        Some(grant_type) => {
+            return Err(to_error_response(OAuthError::UnsupportedGrantType(
+                grant_type.into(),
+            )));
+        }
+        None => {
+            return Err(to_error_response(OAuthError::InvalidRequest(
+                "Missing grant_type parameter".into(),
+            )));
+        }
+    };
+
+    let access_token = oauth_middleware
+        .create_jwt(&client, user.as_ref(), &scopes)
+        .map_err(|error| to_error_response(error.into()))?;
+
+    Ok(no_store(ApiResponse::ok(Json(OAuthTokenOutputDTO {
+        access_token,
+        token_type: "Bearer".into(),
+        expires_in: oauth_middleware.get_token_ttl(),
+        refresh_token,
+        scope: scopes.join(" "),
+    }))))
+}
+
+#[post("/introspect", data = "<input>")]
+pub fn introspect(
+    input: Form<OAuthTokenActionInputDTO>,
+    credentials: Option<ClientCredentials>,
+    oauth_middleware: &State<OAuthMiddleware>,
+) -> Result<ApiResponse<OAuthIntrospectionOutputDTO>, ApiResponse<OAuthErrorOutputDTO>> {
+    let input = input.into_inner();
+
+    let client = authenticate_client(
+        oauth_middleware,
+        credentials,
+        input.client_id.as_deref(),
+        input.client_secret.as_deref(),
+    )
+    .map_err(to_error_response)?;
+
+    let token = input.token.as_deref().ok_or_else(|| {
+        to_error_response(OAuthError::InvalidRequest("Missing token parameter".into()))
+    })?;
+
+    // only access tokens can be introspected, any other token is reported as inactive.
+    let claims = oauth_middleware
+        .introspect(&client, token)
+        .map_err(|error| to_error_response(error.into()))?;
+
+    let output = match claims {
+        None => OAuthIntrospectionOutputDTO::default(),
+        Some(claims) => OAuthIntrospectionOutputDTO {
+            active: true,
+            scope: Some(claims.custom.scopes.join(" ")),
+            sub: claims
+                .custom
+                .user_id
+                .map(|user_id| user_id.to_string())
+                .or_else(|| claims.custom.client_id.clone()),
+            client_id: claims.custom.client_id,
+            username: Some(claims.custom.username),
+            token_type: Some("Bearer".into()),
+            exp: claims.expires_at.map(|date| date.as_secs()),
+            iat: claims.issued_at.map(|date| date.as_secs()),
+            iss: claims.issuer,
+            jti: claims.jwt_id,
+        },
+    };
+
+    Ok(no_store(ApiResponse::ok(Json(output))))
+}
+
+#[post("/revoke", data = "<input>")]
+pub fn revoke(
+    input: Form<OAuthTokenActionInputDTO>,
+    credentials: Option<ClientCredentials>,
+    oauth_middleware: &State<OAuthMiddleware>,
+) -> Result<ApiResponse<NoContentResponse>, ApiResponse<OAuthErrorOutputDTO>> {
+    let input = input.into_inner();
+
+    let client = authenticate_client(
+        oauth_middleware,
+        credentials,
+        input.client_id.as_deref(),
+        input.client_secret.as_deref(),
+    )
+    .map_err(to_error_response)?;
+
+    let token = input.token.as_deref().ok_or_else(|| {
+        to_error_response(OAuthError::InvalidRequest("Missing token parameter".into()))
+    })?;
+
+    oauth_middleware
+        .revoke_token(&client, token)
+        .map_err(|error| to_error_response(error.into()))?;
+
+    // RFC 7009 : the response is the same whether the token was found or not.
+    Ok(ApiResponse::custom(Json(NoContentResponse), Status::Ok))
+}
+
+/// PRIVATE - authenticates the client with HTTP Basic credentials, or the ones given in the request body.
+fn authenticate_client(
+    oauth_middleware: &OAuthMiddleware,
+    credentials: Option<ClientCredentials>,
+    client_id: Option<&str>,
+    client_secret: Option<&str>,
+) -> Result<OAuthClient, OAuthError> {
+    match (credentials, client_id, client_secret) {
+        (Some(credentials), _, _) => {
+            oauth_middleware.authenticate_client(&credentials.client_id, &credentials.client_secret)
+        }
+        (None, Some(client_id), Some(client_secret)) => {
+            oauth_middleware.authenticate_client(client_id, client_secret)
+        }
+        _ => Err(OAuthError::InvalidClient),
+    }
+}
+
+/// PRIVATE - maps a refresh token validation error to an `invalid_grant` error.
+fn to_grant_error(error: JWTRefreshTokenValidationError) -> OAuthError {
+    match error {
+        JWTRefreshTokenValidationError::NotFound(_) => {
+            OAuthError::InvalidGrant("Invalid refresh token".into())
+        }
+        _ => OAuthError::InvalidGrant(error.to_string()),
+    }
+}
+
+/// PRIVATE - builds the RFC 6749 error response of an OAuth error.
+fn to_error_response(error: OAuthError) -> ApiResponse<OAuthErrorOutputDTO> {
+    let status = match error {
+        OAuthError::InvalidClient => Status::Unauthorized,
+        OAuthError::Internal(_) => Status::InternalServerError,
+        _ => Status::BadRequest,
+    };
+
+    let response = ApiResponse::custom(
+        Json(OAuthErrorOutputDTO {
+            error: error.get_code().into(),
+            error_description: Some(error.to_string()),
+        }),
+        status,
+    );
+
+    if status == Status::Unauthorized {
+        return no_store(response).with_header(Header::new(WWW_AUTHENTICATE_HEADER, "Basic"));
+    }
+
+    no_store(response)
+}
+
+/// PRIVATE - forbids caching of a response carrying tokens (RFC 6749, section 5.1).
+fn no_store<T>(response: ApiResponse<T>) -> ApiResponse<T> {
+    response
+        .with_header(Header::new("Cache-Control", "no-store"))
+        .with_header(Header::new("Pragma", "no-cache"))
+}
+
+/// documents the routes of this controller, mounted on `base`.
+pub fn document(openapi: &mut OpenApi, base: &str) {
+    openapi
+        .document(
+            base,
+            routes![token],
+            OpenApiOperation::new("Get an access token for an OAuth2 client")
+                .form_input::<OAuthTokenInputDTO>()
+                .output::<OAuthTokenOutputDTO>()
+                .error::<OAuthErrorOutputDTO>(),
+        )
+        .document(
+            base,
+            routes![introspect],
+            OpenApiOperation::new("Introspect an access token")
+                .form_input::<OAuthTokenActionInputDTO>()
+                .output::<OAuthIntrospectionOutputDTO>()
+                .error::<OAuthErrorOutputDTO>(),
+        )
+        .document(
+            base,
+            routes![revoke],
+            OpenApiOperation::new("Revoke an access or refresh token")
+                .form_input::<OAuthTokenActionInputDTO>()
+                .output::<()>()
+                .error::<OAuthErrorOutputDTO>(),
+        );
+}
+
+#[cfg(test)]
+mod tests {
+    use rocket::{http::ContentType, local::blocking::Client};
+    use serde_json::Value;
+
+    use super::*;
+    use crate::{
+        controllers::api::auth,
+        core::testing::{
+            create_test_application, create_test_user, get_test_configuration, get_test_db_state,
+            get_test_key_store,
+        },
+        domain::repository::{
+            application_repository::ApplicationRepository,
+            jwt_denylist_repository::JWTDenylistRepository,
+            oauth_client_repository::OAuthClientRepository,
+            refresh_token_repository::RefreshTokenRepository, user_repository::UserRepository,
+        },
+        middlewares::jwt_denylist_middleware::JWTDenylistMiddleware,
+    };
+
+    fn get_client() -> Client {
+        let db_state = get_test_db_state();
+        let configuration = get_test_configuration();
+        let jwt_key_store = get_test_key_store();
+        let jwt_denylist_middleware =
+            JWTDenylistMiddleware::new(JWTDenylistRepository::new(db_state.clone()));
+
+        let rocket = rocket::build()
+            .mount("/oauth", routes![token])
+            .mount("/api/auth", routes![auth::refresh_token])
+            .manage(OAuthMiddleware::new(
+                OAuthClientRepository::new(db_state.clone()),
+                UserRepository::new(db_state.clone()),
+                ApplicationRepository::new(db_state.clone()),
+                RefreshTokenRepository::new(db_state.clone()),
+                jwt_denylist_middleware.clone(),
+                jwt_key_store.clone(),
+                configuration.clone(),
+            ))
+            .manage(UserMiddleware::new(
+                UserRepository::new(db_state.clone()),
+                jwt_denylist_middleware,
+                jwt_key_store,
+                configuration.clone(),
+            ))
+            .manage(RefreshTokenMiddleware::new(
+                RefreshTokenRepository::new(db_state),
+                configuration,
+            ));
+
+        Client::untracked(rocket).unwrap()
+    }
+
+    /// refreshes through the `refresh_token` grant, giving the status and the successor.
+    fn refresh_as_client(
+        client: &Client,
+        oauth_client: &OAuthClient,
+        client_secret: &str,
+        refresh_token: &str,
+    ) -> (Status, Option<String>) {
+        let response = client
+            .post("/oauth/token")
+            .header(ContentType::Form)
+            .body(format!(
+                "grant_type=refresh_token&refresh_token={refresh_token}&client_id={}&client_secret={client_secret}",
+                oauth_client.client_id
+            ))
+            .dispatch();
+        let status = response.status();
+        let output = response.into_json::<Value>().unwrap();
+
+        (status, output["refresh_token"].as_str().map(String::from))
+    }
+
+    /// refreshes a user session, giving the status and the successor.
+    fn refresh_as_user(client: &Client, refresh_token: &str) -> (Status, Option<String>) {
+        let response = client
+            .post("/api/auth/refresh-token")
+            .header(ContentType::JSON)
+            .body(format!(r#"{{"refresh_token": "{refresh_token}"}}"#))
+            .dispatch();
+        let status = response.status();
+        let output = response.into_json::<Value>().unwrap_or_default();
+
+        (status, output["refresh_token"].as_str().map(String::from))
+    }
+
+    #[test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    fn test_client_and_session_refresh_tokens_do_not_revoke_each_other() {
+        let db_state = get_test_db_state();
+        let application = create_test_application(&db_state);
+        let user = create_test_user(&db_state, &application, &[]);
+        let client = get_client();
+        let oauth_middleware = client.rocket().state::<OAuthMiddleware>().unwrap();
+        let refresh_token_middleware = client.rocket().state::<RefreshTokenMiddleware>().unwrap();
+
+        let (oauth_client, client_secret) = oauth_middleware
+            .create_client(&application, "test", vec![])
+            .unwrap();
+        let session_token = refresh_token_middleware.generate_for_user(&user).unwrap();
+        let client_token = refresh_token_middleware
+            .generate_for_client(&user, &oauth_client)
+            .unwrap();
+        let other_client_token = refresh_token_middleware
+            .generate_for_client(&user, &oauth_client)
+            .unwrap();
+
+        let refresh_as_client = |refresh_token: &str| {
+            refresh_as_client(&client, &oauth_client, &client_secret, refresh_token)
+        };
+
+        // each flow refuses the other's tokens, without consuming them.
+        assert_eq!(
+            refresh_as_client(&session_token.token).0,
+            Status::BadRequest
+        );
+        assert_eq!(
+            refresh_as_user(&client, &client_token.token).0,
+            Status::NotFound
+        );
+
+        // each flow rotates its own tokens.
+        assert_eq!(refresh_as_client(&client_token.token).0, Status::Ok);
+        let (status, session_successor) = refresh_as_user(&client, &session_token.token);
+        assert_eq!(status, Status::Ok);
+
+        // reusing a rotated client token leaves the session alive.
+        assert_eq!(refresh_as_client(&client_token.token).0, Status::BadRequest);
+        assert_eq!(
+            refresh_as_user(&client, &session_successor.unwrap()).0,
+            Status::Ok
+        );
+
+        // reusing a rotated session token leaves the client's other tokens alive.
+        assert_eq!(
+            refresh_as_user(&client, &session_token.token).0,
+            Status::Unauthorized
+        );
+        assert_eq!(refresh_as_client(&other_client_token.token).0, Status::Ok);
+    }
+}
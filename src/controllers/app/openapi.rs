@@ -0,0 +1,62 @@
+use rocket::{http::Status, response::content::RawHtml, serde::json::Json, State};
+use serde_json::Value;
+
+use crate::core::{
+    configuration::ConfigState,
+    openapi::{OpenApi, OpenApiDocument, OpenApiOperation},
+    response::ApiResponse,
+};
+
+/// Redoc bundle rendering the OpenAPI document, pinned to a version whose integrity is checked by browsers.
+pub const REDOC_SCRIPT_URL: &str =
+    "https://cdn.jsdelivr.net/npm/redoc@2.1.5/bundles/redoc.standalone.js";
+
+/// A page rendering the OpenAPI document with Redoc, `__INTEGRITY__` being the SRI hash of its script.
+const OPENAPI_UI: &str = r#"<!DOCTYPE html>
+<html>
+  <head>
+    <title>API documentation</title>
+    <meta charset="utf-8"/>
+    <meta name="viewport" content="width=device-width, initial-scale=1">
+  </head>
+  <body>
+    <redoc spec-url="openapi.json"></redoc>
+    <script src="__SCRIPT_URL__" integrity="__INTEGRITY__" crossorigin="anonymous"></script>
+  </body>
+</html>
+"#;
+
+#[get("/openapi.json")]
+pub fn openapi_json(document: &State<OpenApiDocument>) -> ApiResponse<Value> {
+    ApiResponse::custom(Json(document.0.clone()), Status::Ok)
+}
+
+/// only mounted when the `openapi_ui_integrity` configuration is set.
+#[get("/docs")]
+pub fn openapi_ui(configuration: &State<ConfigState>) -> RawHtml<String> {
+    let integrity = configuration.get_string_or_default("openapi_ui_integrity", "");
+
+    RawHtml(
+        OPENAPI_UI
+            .replace("__SCRIPT_URL__", REDOC_SCRIPT_URL)
+            .replace("__INTEGRITY__", &html_escape(&integrity)),
+    )
+}
+
+/// PRIVATE - escapes a value to be written in an HTML attribute.
+fn html_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// documents the routes of this controller, mounted on `base`.
+pub fn document(openapi: &mut OpenApi, base: &str) {
+    openapi.document(
+        base,
+        routes![openapi_json],
+        OpenApiOperation::new("Get this OpenAPI document").output::<Value>(),
+    );
+}
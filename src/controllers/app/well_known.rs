@@ -0,0 +1,116 @@
+use rocket::{http::Status, serde::json::Json, State};
+
+use crate::{
+    core::{
+        configuration::ConfigState,
+        jwt,
+        jwt_keys::JWTKeyStore,
+        openapi::{OpenApi, OpenApiOperation},
+        response::ApiResponse,
+    },
+    domain::dto::well_known::{JWKSetOutputDTO, OpenIDConfigurationOutputDTO},
+    exceptions::dto::http_exception::HttpException,
+    http_exception, http_ok,
+};
+
+#[get("/jwks.json")]
+pub fn jwks(
+    jwt_key_store: &State<JWTKeyStore>,
+) -> Result<ApiResponse<JWKSetOutputDTO>, ApiResponse<HttpException>> {
+    let keys = jwt::get_jwks(jwt_key_store);
+
+    if keys.is_err() {
+        http_exception!(Status::InternalServerError, "Could not load JWT keys");
+    }
+
+    http_ok!(JWKSetOutputDTO {
+        keys: keys.unwrap(),
+    });
+}
+
+#[get("/openid-configuration")]
+pub fn openid_configuration(
+    config: &State<ConfigState>,
+    jwt_key_store: &State<JWTKeyStore>,
+) -> Result<ApiResponse<OpenIDConfigurationOutputDTO>, ApiResponse<HttpException>> {
+    let issuer = config.get_string("package.name");
+
+    if issuer.is_err() {
+        http_exception!(Status::InternalServerError, "Could not find issuer");
+    }
+
+    let algorithms = jwt::get_signing_algorithms(jwt_key_store);
+
+    if algorithms.is_err() {
+        http_exception!(Status::InternalServerError, "Could not load JWT keys");
+    }
+
+    let public_url = config.get_string_or_default("public_url", "");
+
+    http_ok!(OpenIDConfigurationOutputDTO {
+        issuer: issuer.unwrap(),
+        jwks_uri: format!("{public_url}/.well-known/jwks.json"),
+        token_endpoint: format!("{public_url}/oauth/token"),
+        introspection_endpoint: format!("{public_url}/oauth/introspect"),
+        revocation_endpoint: format!("{public_url}/oauth/revoke"),
+        grant_types_supported: vec!["client_credentials".into(), "refresh_token".into()],
+        token_endpoint_auth_methods_supported: vec![
+            "client_secret_basic".into(),
+            "client_secret_post".into(),
+        ],
+        response_types_supported: vec!["token".into()],
+        subject_types_supported: vec!["public".into()],
+        id_token_signing_alg_values_supported: algorithms.unwrap(),
+    });
+}
+
+/// documents the routes of this controller, mounted on `base`.
+pub fn document(openapi: &mut OpenApi, base: &str) {
+    openapi
+        .document(
+            base,
+            routes![jwks],
+            OpenApiOperation::new("Get the public keys verifying JWTs").output::<JWKSetOutputDTO>(),
+        )
+        .document(
+            base,
+            routes![openid_configuration],
+            OpenApiOperation::new("Get the OpenID Connect discovery document")
+                .output::<OpenIDConfigurationOutputDTO>(),
+        );
+}
+
+#[cfg(test)]
+mod tests {
+    use rocket::local::blocking::Client;
+    use serde_json::Value;
+
+    use super::*;
+    use crate::core::testing::{get_test_configuration, get_test_key_store, TEST_ISSUER};
+
+    #[test]
+    fn test_well_known_endpoints_publish_the_signing_key() {
+        let rocket = rocket::build()
+            .mount("/.well-known", routes![jwks, openid_configuration])
+            .manage(get_test_configuration())
+            .manage(get_test_key_store());
+        let client = Client::untracked(rocket).unwrap();
+
+        let response = client.get("/.well-known/jwks.json").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        let keys = response.into_json::<Value>().unwrap()["keys"].clone();
+        assert_eq!(keys.as_array().unwrap().len(), 1);
+        assert_eq!(keys[0]["alg"], "ES256");
+        assert!(keys[0].get("d").is_none());
+
+        let response = client.get("/.well-known/openid-configuration").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        let configuration = response.into_json::<Value>().unwrap();
+        assert_eq!(configuration["issuer"], TEST_ISSUER);
+        assert_eq!(
+            configuration["id_token_signing_alg_values_supported"][0],
+            "ES256"
+        );
+        assert_eq!(configuration["jwks_uri"], "/.well-known/jwks.json");
+    }
+}
@@ -44,8 +44,8 @@ pub fn default_catcher(status: Status, req: &Request) -> ApiResponse<HttpExcepti
         }
     }
 
-    ApiResponse {
-        json: Json(HttpException::from_code_with_reason(status.code, reason)),
+    ApiResponse::custom(
+        Json(HttpException::from_code_with_reason(status.code, reason)),
         status,
-    }
+    )
 }
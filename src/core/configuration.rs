@@ -1,6 +1,7 @@
 use anyhow::Result;
 use config::Config;
 use rocket::serde::Serialize;
+use schemars::JsonSchema;
 
 /// A struct representing a shared configuration state.
 /// Note that this state is immutable after launching the rocket.
@@ -27,6 +28,19 @@ impl ConfigState {
         Self { configuration }
     }
 
+    /// creates a configuration from the given values instead of the environment (e.g. for tests).
+    pub fn from_values(values: &[(&str, &str)]) -> Self {
+        let configuration = values
+            .iter()
+            .fold(Config::builder(), |builder, (key, value)| {
+                builder.set_override(key.to_lowercase(), *value).unwrap()
+            })
+            .build()
+            .unwrap();
+
+        Self { configuration }
+    }
+
     /// gets a string from configuration as a Result<>
     pub fn get_string(&self, key: &str) -> Result<String> {
         let value = self.configuration.get_string(&key.to_lowercase())?;
@@ -57,7 +71,7 @@ impl ConfigState {
 }
 
 /// A struct representing public configuration, to display on the root (e.g. `GET /`) of the API.
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 #[serde(crate = "rocket::serde")]
 pub struct PublicConfiguration {
     pub name: String,
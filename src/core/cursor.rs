@@ -0,0 +1,144 @@
+use std::sync::Arc;
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, TimeZone, Utc};
+use data_encoding::BASE64URL_NOPAD;
+use hmac::{Hmac, Mac};
+use rand_core::{OsRng, RngCore};
+use sha2::Sha256;
+
+use super::configuration::ConfigState;
+
+/// Size in bytes of the key generated when no `cursor_secret` is configured.
+const GENERATED_SECRET_SIZE: usize = 32;
+
+/// A position in a listing ordered by `(created_date, id)` : the listing continues after this item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cursor {
+    pub created_date: DateTime<Utc>,
+    pub id: i32,
+}
+
+/// Signs and verifies cursors, so clients cannot forge positions nor reuse a cursor on another listing.
+///
+/// Cursors are opaque to clients : `<payload>.<signature>`, both base64url encoded.
+#[derive(Clone)]
+pub struct CursorSigner {
+    secret: Arc<Vec<u8>>,
+}
+
+impl CursorSigner {
+    /// constructor.
+    pub fn new(secret: &[u8]) -> Self {
+        Self {
+            secret: Arc::new(secret.to_vec()),
+        }
+    }
+
+    /// creates the signer from the `cursor_secret` configuration.
+    ///
+    /// Without it, a random secret is generated : cursors are then invalidated on restart, and not shared between instances.
+    pub fn from_config(configuration: &ConfigState) -> Self {
+        match configuration.get_string("cursor_secret") {
+            Ok(secret) if !secret.is_empty() => Self::new(secret.as_bytes()),
+            _ => {
+                log::warn!("No cursor secret configured, cursors will not survive a restart.");
+
+                let mut secret = [0u8; GENERATED_SECRET_SIZE];
+                OsRng.fill_bytes(&mut secret);
+
+                Self::new(&secret)
+            }
+        }
+    }
+
+    /// encodes a cursor for a listing (e.g. its path).
+    pub fn encode(&self, listing: &str, cursor: &Cursor) -> String {
+        let payload = format!("{}.{}", cursor.created_date.timestamp_micros(), cursor.id);
+        let signature = self.sign(listing, &payload).finalize().into_bytes();
+
+        format!(
+            "{}.{}",
+            BASE64URL_NOPAD.encode(payload.as_bytes()),
+            BASE64URL_NOPAD.encode(&signature)
+        )
+    }
+
+    /// decodes a cursor of a listing, checking its signature.
+    pub fn decode(&self, listing: &str, token: &str) -> Result<Cursor> {
+        let (payload, signature) = token.split_once('.').context("Malformed cursor")?;
+
+        let payload = String::from_utf8(
+            BASE64URL_NOPAD
+                .decode(payload.as_bytes())
+                .context("Malformed cursor")?,
+        )?;
+        let signature = BASE64URL_NOPAD
+            .decode(signature.as_bytes())
+            .context("Malformed cursor")?;
+
+        if self
+            .sign(listing, &payload)
+            .verify_slice(&signature)
+            .is_err()
+        {
+            bail!("Invalid cursor signature");
+        }
+
+        let (created_date, id) = payload.split_once('.').context("Malformed cursor")?;
+
+        let created_date = Utc
+            .timestamp_micros(created_date.parse()?)
+            .single()
+            .context("Malformed cursor date")?;
+
+        Ok(Cursor {
+            created_date,
+            id: id.parse()?,
+        })
+    }
+
+    /// PRIVATE - prepares the HMAC of a cursor payload, bound to its listing.
+    fn sign(&self, listing: &str, payload: &str) -> Hmac<Sha256> {
+        let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(&self.secret)
+            .expect("HMAC accepts keys of any size");
+        mac.update(listing.as_bytes());
+        mac.update(b"\n");
+        mac.update(payload.as_bytes());
+
+        mac
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_and_decode() {
+        let signer = CursorSigner::new(b"secret");
+        let cursor = Cursor {
+            created_date: Utc.timestamp_micros(1_700_000_000_123_456).unwrap(),
+            id: 42,
+        };
+
+        let token = signer.encode("/api/users", &cursor);
+
+        assert_eq!(signer.decode("/api/users", &token).unwrap(), cursor);
+        // cursors are bound to their listing and secret.
+        assert!(signer.decode("/api/applications", &token).is_err());
+        assert!(CursorSigner::new(b"other")
+            .decode("/api/users", &token)
+            .is_err());
+
+        // a forged position is refused.
+        let (_, signature) = token.split_once('.').unwrap();
+        let forged = format!(
+            "{}.{signature}",
+            BASE64URL_NOPAD.encode(b"1700000000123456.43")
+        );
+
+        assert!(signer.decode("/api/users", &forged).is_err());
+        assert!(signer.decode("/api/users", "garbage").is_err());
+    }
+}
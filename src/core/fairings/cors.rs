@@ -0,0 +1,262 @@
+use std::io::Cursor;
+
+use rocket::{
+    fairing::{Fairing, Info, Kind},
+    http::{Header, Method, Status},
+    Request, Response,
+};
+
+use crate::core::configuration::ConfigState;
+
+/// Default methods allowed on cross-origin requests.
+const DEFAULT_ALLOWED_METHODS: &str = "GET,POST,PUT,PATCH,DELETE";
+
+/// Default headers allowed on cross-origin requests.
+const DEFAULT_ALLOWED_HEADERS: &str = "Authorization,Content-Type,X-Api-Key";
+
+/// Default response headers browsers may read on cross-origin requests (e.g. pagination headers).
+const DEFAULT_EXPOSED_HEADERS: &str = "Link,X-Total-Count";
+
+/// Default duration (in seconds) browsers may cache a preflight response.
+const DEFAULT_MAX_AGE: i64 = 3600;
+
+/// this struct handles CORS using fairing mechanism : it adds the `Access-Control-*` headers to responses
+/// for allowed origins, and answers preflight (e.g. `OPTIONS`) requests for every route.
+///
+/// It is configured with :
+/// - `cors_allowed_origins` : comma-separated origins, such as `https://app.example.com`, `https://*.example.com`
+///   for any subdomain, or `*` for any origin. None by default, e.g. cross-origin requests are refused.
+/// - `cors_allowed_methods` and `cors_allowed_headers` : comma-separated methods and request headers.
+/// - `cors_exposed_headers` : comma-separated response headers browsers may read (e.g. pagination headers).
+/// - `cors_allow_credentials` : whether browsers may send cookies and `Authorization` headers (`false` by default).
+/// - `cors_max_age` : how long (in seconds) browsers may cache a preflight response.
+#[derive(Debug, Clone, Default)]
+pub struct Cors {
+    allowed_origins: Vec<String>,
+    allowed_methods: Vec<String>,
+    allowed_headers: Vec<String>,
+    exposed_headers: Vec<String>,
+    allow_credentials: bool,
+    max_age: i64,
+}
+
+impl Cors {
+    /// creates the fairing from the `cors_*` configuration.
+    pub fn from_config(configuration: &ConfigState) -> Self {
+        Self {
+            allowed_origins: split_list(
+                &configuration.get_string_or_default("cors_allowed_origins", ""),
+            ),
+            allowed_methods: split_list(
+                &configuration
+                    .get_string_or_default("cors_allowed_methods", DEFAULT_ALLOWED_METHODS)
+                    .to_uppercase(),
+            ),
+            allowed_headers: split_list(
+                &configuration
+                    .get_string_or_default("cors_allowed_headers", DEFAULT_ALLOWED_HEADERS),
+            ),
+            exposed_headers: split_list(
+                &configuration
+                    .get_string_or_default("cors_exposed_headers", DEFAULT_EXPOSED_HEADERS),
+            ),
+            allow_credentials: configuration.get_bool_or_default("cors_allow_credentials", false),
+            max_age: configuration.get_int_or_default("cors_max_age", DEFAULT_MAX_AGE),
+        }
+    }
+
+    /// checks if an origin (e.g. `https://app.example.com`) is allowed.
+    pub fn is_allowed_origin(&self, origin: &str) -> bool {
+        self.allowed_origins
+            .iter()
+            .any(|allowed_origin| match allowed_origin.split_once('*') {
+                // any origin.
+                Some(("", "")) => true,
+                // any subdomain, e.g. `https://*.example.com`.
+                Some((scheme, domain)) => origin
+                    .strip_prefix(scheme)
+                    .and_then(|origin| origin.strip_suffix(domain))
+                    .map(|subdomain| !subdomain.is_empty() && !subdomain.contains('/'))
+                    .unwrap_or(false),
+                None => allowed_origin.eq_ignore_ascii_case(origin),
+            })
+    }
+
+    /// PRIVATE - gets the `Access-Control-Allow-Origin` value for an allowed origin.
+    ///
+    /// Browsers refuse `*` on credentialed requests, so the origin is sent back instead.
+    fn get_allow_origin(&self, origin: &str) -> String {
+        if !self.allow_credentials && self.allowed_origins.iter().any(|allowed| allowed == "*") {
+            "*".into()
+        } else {
+            origin.into()
+        }
+    }
+
+    /// PRIVATE - checks if a request is a preflight request.
+    fn is_preflight(request: &Request<'_>) -> bool {
+        request.method() == Method::Options
+            && request.headers().contains("Access-Control-Request-Method")
+    }
+}
+
+#[rocket::async_trait]
+impl Fairing for Cors {
+    fn info(&self) -> Info {
+        Info {
+            name: "CORS",
+            kind: Kind::Response,
+        }
+    }
+
+    /// adds the CORS headers to responses to allowed origins, turning preflight responses into `204 No Content`.
+    async fn on_response<'r>(&self, request: &'r Request<'_>, response: &mut Response<'r>) {
+        let origin = match request.headers().get_one("Origin") {
+            Some(origin) if self.is_allowed_origin(origin) => origin,
+            _ => return,
+        };
+
+        // no route answers OPTIONS, so preflight responses are the catcher's 404.
+        if Self::is_preflight(request) {
+            response.set_status(Status::NoContent);
+            response.set_sized_body(0, Cursor::new(""));
+            response.remove_header("Content-Type");
+
+            response.set_header(Header::new(
+                "Access-Control-Allow-Methods",
+                self.allowed_methods.join(", "),
+            ));
+            response.set_header(Header::new(
+                "Access-Control-Allow-Headers",
+                self.allowed_headers.join(", "),
+            ));
+            response.set_header(Header::new(
+                "Access-Control-Max-Age",
+                self.max_age.to_string(),
+            ));
+        } else if !self.exposed_headers.is_empty() {
+            response.set_header(Header::new(
+                "Access-Control-Expose-Headers",
+                self.exposed_headers.join(", "),
+            ));
+        }
+
+        response.set_header(Header::new(
+            "Access-Control-Allow-Origin",
+            self.get_allow_origin(origin),
+        ));
+        response.adjoin_header(Header::new("Vary", "Origin"));
+
+        if self.allow_credentials {
+            response.set_header(Header::new("Access-Control-Allow-Credentials", "true"));
+        }
+    }
+}
+
+/// PRIVATE - splits a comma-separated configuration value, ignoring empty items.
+fn split_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(|item| item.trim())
+        .filter(|item| !item.is_empty())
+        .map(|item| item.to_string())
+        .collect()
+}
+
+#[cfg(test)]
+// route macros re-export a URI macro, unused by tests.
+#[allow(unused_imports)]
+mod tests {
+    use rocket::local::blocking::Client;
+
+    use super::*;
+
+    #[get("/users")]
+    fn handler() -> &'static str {
+        "users"
+    }
+
+    fn get_cors(allowed_origins: &str, allow_credentials: bool) -> Cors {
+        Cors {
+            allowed_origins: split_list(allowed_origins),
+            allowed_methods: split_list(DEFAULT_ALLOWED_METHODS),
+            allowed_headers: split_list(DEFAULT_ALLOWED_HEADERS),
+            exposed_headers: split_list(DEFAULT_EXPOSED_HEADERS),
+            allow_credentials,
+            max_age: DEFAULT_MAX_AGE,
+        }
+    }
+
+    #[test]
+    fn test_allowed_origins() {
+        let cors = get_cors("https://app.example.com, https://*.example.org", false);
+
+        assert!(cors.is_allowed_origin("https://app.example.com"));
+        assert!(!cors.is_allowed_origin("http://app.example.com"));
+        assert!(!cors.is_allowed_origin("https://evil.com"));
+        assert!(cors.is_allowed_origin("https://api.example.org"));
+        assert!(cors.is_allowed_origin("https://a.b.example.org"));
+        assert!(!cors.is_allowed_origin("https://example.org"));
+        assert!(!cors.is_allowed_origin("https://evil.com/.example.org"));
+        assert!(!cors.is_allowed_origin("https://evilexample.org"));
+        assert!(get_cors("*", false).is_allowed_origin("https://any.com"));
+        assert!(!get_cors("", false).is_allowed_origin("https://any.com"));
+    }
+
+    #[test]
+    fn test_preflight_and_response_headers() {
+        let rocket = rocket::build()
+            .mount("/api", routes![handler])
+            .attach(get_cors("https://*.example.com", true));
+        let client = Client::untracked(rocket).unwrap();
+
+        let response = client
+            .options("/api/users")
+            .header(Header::new("Origin", "https://app.example.com"))
+            .header(Header::new("Access-Control-Request-Method", "GET"))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::NoContent);
+        assert_eq!(
+            response.headers().get_one("Access-Control-Allow-Origin"),
+            Some("https://app.example.com")
+        );
+        assert_eq!(
+            response
+                .headers()
+                .get_one("Access-Control-Allow-Credentials"),
+            Some("true")
+        );
+        assert_eq!(
+            response.headers().get_one("Access-Control-Max-Age"),
+            Some("3600")
+        );
+
+        let response = client
+            .get("/api/users")
+            .header(Header::new("Origin", "https://app.example.com"))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(
+            response.headers().get_one("Access-Control-Allow-Origin"),
+            Some("https://app.example.com")
+        );
+        assert!(response
+            .headers()
+            .get_one("Access-Control-Allow-Methods")
+            .is_none());
+
+        let response = client
+            .options("/api/users")
+            .header(Header::new("Origin", "https://evil.com"))
+            .header(Header::new("Access-Control-Request-Method", "GET"))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::NotFound);
+        assert!(response
+            .headers()
+            .get_one("Access-Control-Allow-Origin")
+            .is_none());
+    }
+}
@@ -7,6 +7,9 @@ use rocket::{
     Orbit, Rocket,
 };
 
+/// migrations of the `src/migrations` directory, embedded in the binary.
+pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!();
+
 /// this struct handle database migration at launch-time using fairing mechanism.
 #[derive(Default)]
 pub struct DatabaseMigrations {}
@@ -27,8 +30,6 @@ impl Fairing for DatabaseMigrations {
 
         let mut conn = PgConnection::establish(&conn_url).unwrap();
 
-        pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!();
-
         conn.run_pending_migrations(MIGRATIONS).unwrap();
     }
 }
@@ -1,26 +1,100 @@
-use std::{fs, path::PathBuf, process::Command};
+use std::fs;
 
 use rocket::{
     fairing::{Fairing, Info, Kind, Result},
     Build, Rocket,
 };
 
-use crate::core::{commands::console_command_utils::ConsoleIO, jwt};
+use crate::core::{
+    commands::console_command_utils::ConsoleIO,
+    configuration::ConfigState,
+    jwt,
+    jwt_keys::{self, JWTAlgorithm, JWTKeyManifest, JWTKeyStore},
+};
+
+/// a fairing struct to handle JWT key pair generation upon ignite, then loading keys into the shared key store.
+pub struct JWTCertificatesFairing {
+    jwt_key_store: JWTKeyStore,
+}
+
+impl JWTCertificatesFairing {
+    /// constructor.
+    pub fn new(jwt_key_store: JWTKeyStore) -> Self {
+        Self { jwt_key_store }
+    }
+
+    /// PRIVATE - ensures the key manifest exists with a usable signing key for the configured algorithm, generating one if needed.
+    fn ensure_signing_key(
+        &self,
+        io: &ConsoleIO,
+        algorithm: JWTAlgorithm,
+        jwt_ttl: i64,
+    ) -> anyhow::Result<()> {
+        if !JWTKeyManifest::exists() {
+            let private_key_path = jwt_keys::get_legacy_private_key_path();
+            let public_key_path = jwt_keys::get_legacy_public_key_path();
+
+            if public_key_path.exists() && private_key_path.exists() {
+                let key = jwt_keys::adopt_legacy_key_pair()?;
+                io.info(&format!(
+                    "Existing certificates registered as key {}.",
+                    key.kid
+                ));
+
+                return self.ensure_signing_key(io, algorithm, jwt_ttl);
+            }
+
+            if public_key_path.exists() || private_key_path.exists() {
+                io.warning("Something is wrong with certificates, they will be generated.");
+                fs::remove_file(&public_key_path).ok();
+                fs::remove_file(&private_key_path).ok();
+            }
+
+            // rotating without any manifest creates it with a single signing key.
+            jwt_keys::rotate(algorithm, 0)?;
+
+            return Ok(());
+        }
 
-/// a fairing struct to handle SSL certificate generation upon ignite
-#[derive(Default)]
-pub struct JWTCertificatesFairing {}
+        let manifest = JWTKeyManifest::load()?;
+
+        let signing_key = manifest.get_signing_key();
+
+        let signing_key_usable = match signing_key {
+            Some(key) => jwt_keys::check_key_pair(key).is_ok(),
+            None => false,
+        };
+
+        if !signing_key_usable {
+            io.warning("Something is wrong with the JWT signing key, a new one will be generated.");
+            jwt_keys::rotate(algorithm, 0)?;
+
+            return Ok(());
+        }
+
+        if let Some(key) = signing_key.filter(|key| key.alg != algorithm) {
+            // previous keys keep verifying tokens they signed until those expire.
+            io.info(&format!(
+                "JWT algorithm changed from {} to {algorithm}, rotating the signing key.",
+                key.alg
+            ));
+            jwt_keys::rotate(algorithm, jwt_ttl)?;
+        }
+
+        Ok(())
+    }
+}
 
 #[rocket::async_trait]
 impl Fairing for JWTCertificatesFairing {
     fn info(&self) -> Info {
         Info {
-            name: "Generates PEM certificates for JWT",
+            name: "Generates key pairs for JWT",
             kind: Kind::Ignite,
         }
     }
 
-    /// this fairing will create private and public certificates for JWT authentication upon ignite.
+    /// this fairing will create private and public keys for JWT authentication upon ignite.
     async fn on_ignite(&self, rocket: Rocket<Build>) -> Result {
         let io = ConsoleIO::new();
 
@@ -37,40 +111,33 @@ impl Fairing for JWTCertificatesFairing {
             }
         }
 
-        let private_key_path: PathBuf = jwt::get_private_certificate_path();
-        let public_key_path: PathBuf = jwt::get_public_certificate_path();
+        let configuration = rocket
+            .state::<ConfigState>()
+            .cloned()
+            .unwrap_or_else(ConfigState::load);
 
-        let mut needs_generation: bool = false;
-
-        if !public_key_path.exists() && !private_key_path.exists() {
-            needs_generation = true;
-        }
+        let algorithm = match JWTAlgorithm::from_config(&configuration) {
+            Ok(algorithm) => algorithm,
+            Err(error) => {
+                io.error(&format!("{error}, aborting launch..."));
+                return Err(rocket);
+            }
+        };
 
-        if public_key_path.exists() && !private_key_path.exists() {
-            io.warning("Something is wrong with certificates, they will be generated.");
-            fs::remove_file(&public_key_path).unwrap();
-            needs_generation = true;
-        }
+        let jwt_ttl = configuration.get_int_or_default("jwt_ttl", 3600);
 
-        if !public_key_path.exists() && private_key_path.exists() {
-            io.warning("Something is wrong with certificates, they will be generated.");
-            fs::remove_file(&private_key_path).unwrap();
-            needs_generation = true;
+        if let Err(error) = self.ensure_signing_key(&io, algorithm, jwt_ttl) {
+            io.error(&format!(
+                "Cannot create secured JWT token certificates ({error}), aborting launch..."
+            ));
+            return Err(rocket);
         }
 
-        if needs_generation {
-            // TODO: refactor to avoid calling shell sub process
-            let mut child = Command::new("sh");
-
-            let certs_creation = child
-                .arg("-c")
-                .arg(format!("cd {} && openssl genrsa -out private.pem 2048 && openssl rsa -in private.pem -outform PEM -pubout -out public.pem", jwt_sec_dir.as_os_str().to_string_lossy()))
-                .output();
-
-            if certs_creation.is_err() {
-                io.error("Cannot create secured JWT token certificates, aborting launch...");
-                return Err(rocket);
-            }
+        if let Err(error) = self.jwt_key_store.reload() {
+            io.error(&format!(
+                "Cannot load JWT keys ({error}), aborting launch..."
+            ));
+            return Err(rocket);
         }
 
         Ok(rocket)
@@ -1,3 +1,4 @@
+pub mod cors;
 pub mod database_migrations;
 pub mod fixture;
 pub mod jwt_certificates;
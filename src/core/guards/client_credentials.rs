@@ -0,0 +1,57 @@
+use data_encoding::BASE64;
+use rocket::{
+    http::RawStr,
+    request::{FromRequest, Outcome},
+    Request,
+};
+
+/// OAuth2 client credentials guard, extracted from an HTTP Basic `Authorization` header (RFC 6749, section 2.3.1).
+///
+/// It forwards when the header is missing or not a valid Basic one, so it is meant to be used as an `Option`,
+/// clients being allowed to send their credentials in the request body instead.
+#[derive(Debug, Clone)]
+pub struct ClientCredentials {
+    /// the client ID.
+    pub client_id: String,
+    /// the clear client secret.
+    pub client_secret: String,
+}
+
+impl ClientCredentials {
+    /// PRIVATE - decodes the value of a Basic `Authorization` header.
+    fn from_header(header: &str) -> Option<Self> {
+        let (scheme, encoded) = header.trim().split_once(' ')?;
+
+        if !scheme.eq_ignore_ascii_case("basic") {
+            return None;
+        }
+
+        let decoded = BASE64.decode(encoded.trim().as_bytes()).ok()?;
+        let decoded = String::from_utf8(decoded).ok()?;
+        let (client_id, client_secret) = decoded.split_once(':')?;
+
+        // both parts are form-urlencoded before being joined.
+        Some(Self {
+            client_id: RawStr::new(client_id).url_decode().ok()?.into_owned(),
+            client_secret: RawStr::new(client_secret).url_decode().ok()?.into_owned(),
+        })
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for ClientCredentials {
+    /// error type returned in case of extraction error.
+    type Error = ();
+
+    /// Guard interceptor extracting client credentials from the request.
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        match req
+            .headers()
+            .get_one("Authorization")
+            .and_then(ClientCredentials::from_header)
+        {
+            Some(credentials) => Outcome::Success(credentials),
+            None => Outcome::Forward(()),
+        }
+    }
+}
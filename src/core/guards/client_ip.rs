@@ -0,0 +1,101 @@
+use std::net::IpAddr;
+
+use rocket::{
+    request::{FromRequest, Outcome},
+    Request,
+};
+
+use crate::core::configuration::ConfigState;
+
+/// IP address of the client, e.g. the remote address of the connection.
+///
+/// Headers can be forged by any client, so the `ip_header` configuration (e.g. `X-Forwarded-For`) is only read when set,
+/// for an API running behind a trusted proxy. When the header holds a list, the last address (the one added by the proxy) is used.
+/// It forwards when no address can be found, so it is meant to be used as an `Option`.
+#[derive(Debug, Clone, Copy)]
+pub struct ClientIp(pub IpAddr);
+
+impl ClientIp {
+    /// PRIVATE - parses the address set by the trusted proxy in a header value.
+    fn from_header(header: &str) -> Option<Self> {
+        header
+            .rsplit(',')
+            .next()
+            .and_then(|ip| ip.trim().parse::<IpAddr>().ok())
+            .map(ClientIp)
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for ClientIp {
+    /// error type returned in case of extraction error.
+    type Error = ();
+
+    /// Guard interceptor extracting the client IP from the request.
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let ip_header = req
+            .rocket()
+            .state::<ConfigState>()
+            .map(|configuration| configuration.get_string_or_default("ip_header", ""))
+            .unwrap_or_default();
+
+        let client_ip = if ip_header.is_empty() {
+            req.remote().map(|remote| ClientIp(remote.ip()))
+        } else {
+            req.headers()
+                .get_one(&ip_header)
+                .and_then(ClientIp::from_header)
+        };
+
+        match client_ip {
+            Some(client_ip) => Outcome::Success(client_ip),
+            None => Outcome::Forward(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::SocketAddr;
+
+    use rocket::{http::Header, local::asynchronous::Client};
+
+    use super::*;
+
+    async fn get_client_ip(configuration: ConfigState) -> Option<String> {
+        let client = Client::untracked(rocket::build().manage(configuration))
+            .await
+            .unwrap();
+        let request = client
+            .get("/")
+            .remote("192.0.2.1:4000".parse::<SocketAddr>().unwrap())
+            .header(Header::new("X-Real-IP", "198.51.100.1"))
+            .header(Header::new("X-Forwarded-For", "198.51.100.2, 203.0.113.1"));
+
+        ClientIp::from_request(request.inner())
+            .await
+            .succeeded()
+            .map(|ClientIp(ip)| ip.to_string())
+    }
+
+    #[rocket::async_test]
+    async fn test_client_ip_only_trusts_the_configured_header() {
+        assert_eq!(
+            get_client_ip(ConfigState::default()).await.as_deref(),
+            Some("192.0.2.1")
+        );
+        assert_eq!(
+            get_client_ip(ConfigState::from_values(&[(
+                "ip_header",
+                "X-Forwarded-For"
+            )]))
+            .await
+            .as_deref(),
+            Some("203.0.113.1")
+        );
+        assert_eq!(
+            get_client_ip(ConfigState::from_values(&[("ip_header", "X-Client-IP")])).await,
+            None
+        );
+    }
+}
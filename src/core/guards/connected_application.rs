@@ -0,0 +1,148 @@
+use rocket::{
+    http::Status,
+    request::{FromRequest, Outcome},
+    Request,
+};
+use thiserror::Error;
+
+use crate::{
+    core::{response::ErrorMessage, security},
+    domain::model::{api_key::ApiKey, application::Application},
+    middlewares::{
+        api_key_middleware::{ApiKeyAuthenticationError, ApiKeyMiddleware},
+        oauth_middleware::{AuthenticatedClient, OAuthMiddleware},
+    },
+};
+
+/// header carrying the API key.
+pub const API_KEY_HEADER: &str = "X-Api-Key";
+
+/// Connected application guard to get the application authenticated over controllers.
+///
+/// Applications authenticate with an API key, in the `X-Api-Key` header, or with a token issued to one of their OAuth clients on its own (e.g. `client_credentials` grant).
+#[derive(Debug, Clone)]
+pub struct ConnectedApplication {
+    /// the application owning the API key or the OAuth client.
+    pub application: Application,
+    /// the API key used to authenticate, if any.
+    pub api_key: Option<ApiKey>,
+    /// the ID of the OAuth client the token used to authenticate was issued to, if any.
+    pub client_id: Option<String>,
+    /// the scopes granted to the API key, or to the token.
+    pub scopes: Vec<String>,
+}
+
+impl ConnectedApplication {
+    /// checks if the application is granted a right on a subject, with either `subject:right`, `subject:*` or `*` scopes.
+    pub fn has_scope(&self, subject: &str, right: &str) -> bool {
+        security::is_scope_granted(&self.scopes, &format!("{subject}:{right}"))
+    }
+
+    /// checks if a request carries credentials of an application (e.g. an API key, or a token issued to an OAuth client on its own).
+    ///
+    /// Guards can forward other requests, such as those of users.
+    pub fn has_credentials(req: &Request<'_>) -> bool {
+        req.headers().get_one(API_KEY_HEADER).is_some()
+            || matches!(authenticate_client_token(req), Some(Ok(Some(_))))
+    }
+}
+
+/// Every error state that could happen during API key authentication & validation.
+#[derive(Debug, Error)]
+pub enum ApiKeyGuardError {
+    /// No API key header found in the request.
+    #[error("API key header not found")]
+    HeaderNotFound,
+    /// The API key is unknown, revoked or expired.
+    #[error("Invalid API key, perhaps revoked or expired")]
+    InvalidKey,
+    /// The bearer token was not issued to an OAuth client on its own, or is revoked or expired.
+    #[error("Invalid client token, perhaps issued for a user, revoked or expired")]
+    InvalidClientToken,
+    /// The authentication could not be processed.
+    #[error("Authentication could not be processed")]
+    Internal,
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for ConnectedApplication {
+    /// error type returned in case of authentication error.
+    type Error = ApiKeyGuardError;
+
+    /// Guard interceptor extracting the connected application from the request.
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let api_key_middleware = req.rocket().state::<ApiKeyMiddleware>().unwrap();
+
+        let api_key_header = req.headers().get_one(API_KEY_HEADER);
+
+        if api_key_header.is_none() {
+            return match authenticate_client_token(req) {
+                None => {
+                    req.local_cache(|| ErrorMessage {
+                        message: "API key header not found".into(),
+                    });
+                    Outcome::Failure((Status::Unauthorized, ApiKeyGuardError::HeaderNotFound))
+                }
+                Some(Ok(Some(authenticated_client))) => Outcome::Success(ConnectedApplication {
+                    application: authenticated_client.application.clone(),
+                    api_key: None,
+                    client_id: Some(authenticated_client.client.client_id.clone()),
+                    scopes: authenticated_client.claims.custom.scopes.clone(),
+                }),
+                Some(Ok(None)) => {
+                    req.local_cache(|| ErrorMessage {
+                        message:
+                            "Invalid client token, perhaps issued for a user, revoked or expired"
+                                .into(),
+                    });
+                    Outcome::Failure((Status::Unauthorized, ApiKeyGuardError::InvalidClientToken))
+                }
+                Some(Err(_)) => {
+                    Outcome::Failure((Status::InternalServerError, ApiKeyGuardError::Internal))
+                }
+            };
+        }
+
+        let authenticated_application_result =
+            req.local_cache(|| api_key_middleware.authenticate(api_key_header.unwrap().trim()));
+
+        match authenticated_application_result {
+            Ok((application, api_key)) => Outcome::Success(ConnectedApplication {
+                application: application.clone(),
+                api_key: Some(api_key.clone()),
+                client_id: None,
+                scopes: api_key.scopes.clone(),
+            }),
+            Err(error) => match error.downcast_ref::<ApiKeyAuthenticationError>() {
+                None => Outcome::Failure((Status::InternalServerError, ApiKeyGuardError::Internal)),
+                Some(authentication_error) => {
+                    req.local_cache(|| ErrorMessage {
+                        message: authentication_error.to_string(),
+                    });
+                    Outcome::Failure((Status::Unauthorized, ApiKeyGuardError::InvalidKey))
+                }
+            },
+        }
+    }
+}
+
+/// PRIVATE - authenticates the bearer token of a request as a token issued to an OAuth client on its own, once per request.
+///
+/// Gives `None` when no bearer token is sent.
+fn authenticate_client_token<'r>(
+    req: &'r Request<'_>,
+) -> Option<&'r anyhow::Result<Option<AuthenticatedClient>>> {
+    let (scheme, token) = req
+        .headers()
+        .get_one("Authorization")?
+        .trim()
+        .split_once(' ')?;
+
+    if !scheme.eq_ignore_ascii_case("bearer") {
+        return None;
+    }
+
+    let oauth_middleware = req.rocket().state::<OAuthMiddleware>().unwrap();
+
+    Some(req.local_cache(|| oauth_middleware.authenticate_client_token(token.trim())))
+}
@@ -1,3 +1,4 @@
+use jwt_simple::prelude::JWTClaims;
 use rocket::{
     http::Status,
     request::{FromRequest, Outcome},
@@ -6,46 +7,40 @@ use rocket::{
 use thiserror::Error;
 
 use crate::{
-    core::response::ErrorMessage,
+    core::{jwt::APIClaim, response::ErrorMessage},
     domain::model::user::User,
-    middlewares::user_middleware::{JWTAuthenticationError, UserMiddleware},
+    middlewares::{
+        role_middleware::RoleMiddleware,
+        user_middleware::{JWTAuthenticationError, UserMiddleware},
+    },
 };
 
 /// Connected user guard to get the connected user over controllers.
 #[derive(Debug, Clone)]
 pub struct ConnectedUser {
-    /// the currently connected user.
+    /// the currently connected user, with its effective roles (e.g. including implied roles).
     pub user: User,
+    /// the decoded claims of the JWT used to authenticate.
+    pub claims: JWTClaims<APIClaim>,
+    /// the administrator acting as the user, when the JWT was issued to impersonate it.
+    pub impersonator: Option<User>,
 }
 
-/// Every error state that could happen during user authentication & validation.
-#[derive(Debug, Error)]
-pub enum AuthenticationError {
-    /// No JWT header found in the request.
-    #[error("JWT header not found")]
-    HeaderNotFound,
-    /// The JWT header is invalid.
-    #[error("Invalid JWT header")]
-    InvalidHeader,
-    /// The JWT header is unparsable.
-    #[error("Invalid JWT header")]
-    MalformedHeader,
-    /// The JWT token is not formatted properly, or outdated.
-    #[error("Invalid JWT token, perhaps malformatted or outdated")]
-    InvalidJWT,
-    /// The user contained in the JWT token is invalid.
-    #[error("JWT token user not found")]
-    UserNotFound,
-}
+impl ConnectedUser {
+    /// checks if the user is impersonated by an administrator.
+    pub fn is_impersonated(&self) -> bool {
+        self.impersonator.is_some()
+    }
 
-#[rocket::async_trait]
-impl<'r> FromRequest<'r> for ConnectedUser {
-    /// error type returned in case of authentication error.
-    type Error = AuthenticationError;
+    /// checks if the JWT was issued to an OAuth client on behalf of the user, its rights being then limited to its scopes.
+    pub fn is_delegated(&self) -> bool {
+        self.claims.custom.client_id.is_some()
+    }
 
-    /// Guard interceptor extracting connected user from the request.
-    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+    /// authenticates the user of the JWT sent in the request, whether it was issued to the user or to an OAuth client on its behalf.
+    pub fn authenticate(req: &Request<'_>) -> Outcome<Self, AuthenticationError> {
         let user_middleware = req.rocket().state::<UserMiddleware>().unwrap();
+        let role_middleware = req.rocket().state::<RoleMiddleware>().unwrap();
 
         let jwt_header = req.headers().get("Authorization").next();
 
@@ -62,7 +57,7 @@ impl<'r> FromRequest<'r> for ConnectedUser {
             req.local_cache(|| ErrorMessage {
                 message: "Invalid JWT header".into(),
             });
-            return Outcome::Failure((Status::BadRequest, AuthenticationError::InvalidHeader));
+            return Outcome::Failure((Status::Unauthorized, AuthenticationError::InvalidHeader));
         }
 
         let jwt_token_parsed = jwt_token_unparsed.split(' ').collect::<Vec<&str>>();
@@ -71,35 +66,108 @@ impl<'r> FromRequest<'r> for ConnectedUser {
             req.local_cache(|| ErrorMessage {
                 message: "Malformed JWT header".into(),
             });
-            return Outcome::Failure((Status::BadRequest, AuthenticationError::MalformedHeader));
+            return Outcome::Failure((Status::Unauthorized, AuthenticationError::MalformedHeader));
         }
 
         let jwt_token = *jwt_token_parsed.get(1).unwrap();
 
-        let authenticated_user_result =
-            req.local_cache(|| user_middleware.authenticate_user_from_jwt(jwt_token));
+        // effective roles are resolved once per request, so voters see the roles implied by the hierarchy.
+        let authenticated_user_result = req.local_cache(|| {
+            user_middleware
+                .authenticate_user_from_jwt(jwt_token)
+                .and_then(|(mut user, claims)| {
+                    user.roles = role_middleware.resolve_roles(&user)?;
+
+                    let impersonator = user_middleware.find_impersonator(&claims)?;
+
+                    Ok((user, claims, impersonator))
+                })
+        });
 
         match authenticated_user_result {
-            Ok(user) => {
-                return Outcome::Success(ConnectedUser { user: user.clone() });
-            }
-            Err(error) => match error.downcast_ref::<JWTAuthenticationError>().unwrap() {
-                JWTAuthenticationError::InvalidToken => {
+            Ok((user, claims, impersonator)) => Outcome::Success(ConnectedUser {
+                user: user.clone(),
+                claims: claims.clone(),
+                impersonator: impersonator.clone(),
+            }),
+            Err(error) => match error.downcast_ref::<JWTAuthenticationError>() {
+                None => {
+                    Outcome::Failure((Status::InternalServerError, AuthenticationError::Internal))
+                }
+                Some(JWTAuthenticationError::RevokedToken) => {
+                    req.local_cache(|| ErrorMessage {
+                        message: "JWT token revoked".into(),
+                    });
+                    Outcome::Failure((Status::Unauthorized, AuthenticationError::RevokedJWT))
+                }
+                Some(JWTAuthenticationError::InvalidToken) => {
                     req.local_cache(|| ErrorMessage {
                         message: "Invalid JWT token, perhaps malformatted or outdated".into(),
                     });
-                    return Outcome::Failure((
-                        Status::Unauthorized,
-                        AuthenticationError::InvalidJWT,
-                    ));
+                    Outcome::Failure((Status::Unauthorized, AuthenticationError::InvalidJWT))
                 }
-                JWTAuthenticationError::UserNotFound(_) => {
+                Some(JWTAuthenticationError::UserNotFound(_)) => {
                     req.local_cache(|| ErrorMessage {
                         message: "Invalid JWT token".into(),
                     });
-                    return Outcome::Failure((Status::NotFound, AuthenticationError::UserNotFound));
+                    Outcome::Failure((Status::Unauthorized, AuthenticationError::UserNotFound))
                 }
             },
         }
     }
 }
+
+/// Every error state that could happen during user authentication & validation.
+#[derive(Debug, Error)]
+pub enum AuthenticationError {
+    /// No JWT header found in the request.
+    #[error("JWT header not found")]
+    HeaderNotFound,
+    /// The JWT header is invalid.
+    #[error("Invalid JWT header")]
+    InvalidHeader,
+    /// The JWT header is unparsable.
+    #[error("Invalid JWT header")]
+    MalformedHeader,
+    /// The JWT token is not formatted properly, or outdated.
+    #[error("Invalid JWT token, perhaps malformatted or outdated")]
+    InvalidJWT,
+    /// The JWT token has been revoked (e.g. after a logout).
+    #[error("JWT token revoked")]
+    RevokedJWT,
+    /// The user contained in the JWT token is invalid.
+    #[error("JWT token user not found")]
+    UserNotFound,
+    /// The JWT token was issued to an OAuth client, and the route grants no scope.
+    #[error("JWT token issued to an OAuth client")]
+    DelegatedJWT,
+    /// The authentication could not be processed.
+    #[error("Authentication could not be processed")]
+    Internal,
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for ConnectedUser {
+    /// error type returned in case of authentication error.
+    type Error = AuthenticationError;
+
+    /// Guard interceptor extracting connected user from the request.
+    ///
+    /// Tokens issued to OAuth clients on behalf of the user are refused : their scopes only cover routes declaring a right (e.g. with `Granted`).
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let connected_user = match ConnectedUser::authenticate(req) {
+            Outcome::Success(connected_user) => connected_user,
+            failure => return failure,
+        };
+
+        if connected_user.is_delegated() {
+            req.local_cache(|| ErrorMessage {
+                message: "This route cannot be accessed with a token issued to an OAuth client."
+                    .into(),
+            });
+            return Outcome::Failure((Status::Forbidden, AuthenticationError::DelegatedJWT));
+        }
+
+        Outcome::Success(connected_user)
+    }
+}
@@ -0,0 +1,161 @@
+use chrono::{DateTime, Utc};
+use rocket::{
+    http::Status,
+    request::{FromRequest, Outcome},
+    Request,
+};
+
+use crate::core::{
+    cursor::{Cursor, CursorSigner},
+    guards::pagination::{get_max_per_page, DEFAULT_PER_PAGE},
+    response::ErrorMessage,
+};
+
+/// A struct representing keyset pagination options for listings (e.g. `?cursor=...&limit=...`), ordered by `(created_date, id)`.
+///
+/// Unlike `Pagination`, pages stay consistent under concurrent inserts and do not slow down on large tables.
+/// Requests without `cursor` nor `limit` are forwarded, so a listing can also be mounted with `Pagination` at a lower rank.
+#[derive(Clone)]
+pub struct CursorPagination {
+    /// the position to continue after, `None` for the first page.
+    pub cursor: Option<Cursor>,
+    pub limit: u16,
+    /// path of the listing, the cursors are bound to.
+    path: String,
+    /// raw query segments of the listing other than `cursor` and `limit` (e.g. filters), kept when linking the next page.
+    query: Vec<String>,
+    signer: CursorSigner,
+}
+
+impl CursorPagination {
+    /// constructor.
+    pub fn new(cursor: Option<Cursor>, limit: u16, path: &str, signer: CursorSigner) -> Self {
+        Self {
+            cursor,
+            limit,
+            path: path.into(),
+            query: Vec::new(),
+            signer,
+        }
+    }
+
+    /// gets the cursor of the next page, e.g. after the last item of a full page.
+    pub fn get_next_cursor<T>(
+        &self,
+        items: &[T],
+        position: impl Fn(&T) -> (DateTime<Utc>, i32),
+    ) -> Option<String> {
+        if items.len() < usize::from(self.limit) {
+            return None;
+        }
+
+        items.last().map(|item| {
+            let (created_date, id) = position(item);
+
+            self.signer.encode(&self.path, &Cursor { created_date, id })
+        })
+    }
+
+    /// gets the URI of the page after a cursor, keeping the other query parameters.
+    pub fn get_page_uri(&self, cursor: &str) -> String {
+        let mut query = self.query.clone();
+        query.push(format!("cursor={cursor}"));
+        query.push(format!("limit={}", self.limit));
+
+        format!("{}?{}", self.path, query.join("&"))
+    }
+}
+
+/// Different states of errors that can encounter cursor pagination extraction.
+#[derive(Debug)]
+pub enum CursorPaginationError {
+    /// the limit parameter is invalid.
+    LimitParseError,
+    /// the limit is <= 0
+    ZeroLimit,
+    /// the cursor is malformed, forged or comes from another listing.
+    InvalidCursor,
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for CursorPagination {
+    type Error = CursorPaginationError;
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let query_cursor = req.query_value::<&str>("cursor");
+        let query_limit = req.query_value::<u16>("limit");
+
+        if query_cursor.is_none() && query_limit.is_none() {
+            return Outcome::Forward(());
+        }
+
+        let mut limit = DEFAULT_PER_PAGE;
+
+        if let Some(parsed_limit) = query_limit {
+            if parsed_limit.is_err() {
+                req.local_cache(|| ErrorMessage {
+                    message: "limit parameter cannot be parsed".into(),
+                });
+                return Outcome::Failure((
+                    Status::BadRequest,
+                    CursorPaginationError::LimitParseError,
+                ));
+            }
+
+            limit = parsed_limit.unwrap();
+        }
+
+        if limit == 0 {
+            req.local_cache(|| ErrorMessage {
+                message: "limit must be a positive integer".into(),
+            });
+            return Outcome::Failure((Status::BadRequest, CursorPaginationError::ZeroLimit));
+        }
+
+        // the limit is capped like per_page.
+        limit = limit.min(get_max_per_page(req));
+
+        let signer = req.rocket().state::<CursorSigner>().unwrap().clone();
+        let path = req.uri().path().to_string();
+
+        let cursor = match query_cursor {
+            Some(Ok(token)) if !token.is_empty() => match signer.decode(&path, token) {
+                Ok(cursor) => Some(cursor),
+                Err(_) => {
+                    req.local_cache(|| ErrorMessage {
+                        message: "cursor is invalid".into(),
+                    });
+                    return Outcome::Failure((
+                        Status::BadRequest,
+                        CursorPaginationError::InvalidCursor,
+                    ));
+                }
+            },
+            _ => None,
+        };
+
+        let query = req
+            .uri()
+            .query()
+            .map(|query| {
+                query
+                    .raw_segments()
+                    .filter(|segment| {
+                        let name = segment.as_str().split('=').next().unwrap_or_default();
+
+                        name != "cursor" && name != "limit"
+                    })
+                    .map(|segment| segment.to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Outcome::Success(CursorPagination {
+            cursor,
+            limit,
+            path,
+            query,
+            signer,
+        })
+    }
+}
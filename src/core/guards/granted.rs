@@ -0,0 +1,139 @@
+use std::marker::PhantomData;
+
+use jwt_simple::prelude::JWTClaims;
+use rocket::{
+    http::Status,
+    request::{FromRequest, Outcome},
+    Request,
+};
+use thiserror::Error;
+
+use crate::{
+    core::{
+        guards::connected_user::ConnectedUser,
+        jwt::APIClaim,
+        response::ErrorMessage,
+        security::{is_scope_granted, AccessDecision, Security, SecurityVoter},
+    },
+    domain::model::user::User,
+    security_right,
+};
+
+/// A security subject (e.g. `user`), as a type to use with the `Granted` guard.
+///
+/// Declare one with the `security_subject!` macro.
+pub trait SecuritySubject: Send + Sync + 'static {
+    /// name of the subject, as supported by a voter.
+    const NAME: &'static str;
+}
+
+/// A right on a security subject (e.g. `list`), as a type to use with the `Granted` guard.
+///
+/// Declare one with the `security_right!` macro.
+pub trait SecurityRight: Send + Sync + 'static {
+    /// name of the right, as handled by a voter.
+    const NAME: &'static str;
+}
+
+security_right!(ListRight, "list");
+security_right!(DetailsRight, "details");
+security_right!(CreateRight, "create");
+security_right!(UpdateRight, "update");
+security_right!(DeleteRight, "delete");
+security_right!(RestoreRight, "restore");
+
+/// Guard granting access to a right on a subject to the connected user, before the controller runs.
+///
+/// It replaces `ConnectedUser` and `deny_access_unless_granted!` for checks without context :
+/// ```rust
+/// #[get("/users")]
+/// pub fn user_list(granted: Granted<UserSubject, ListRight>) -> ... {
+///     let user = &granted.user;
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Granted<S: SecuritySubject, R: SecurityRight> {
+    /// the currently connected user, with its effective roles.
+    pub user: User,
+    /// the decoded claims of the JWT used to authenticate.
+    pub claims: JWTClaims<APIClaim>,
+    /// the administrator acting as the user, when the JWT was issued to impersonate it.
+    pub impersonator: Option<User>,
+    permission: PhantomData<(S, R)>,
+}
+
+impl<S: SecuritySubject, R: SecurityRight> Granted<S, R> {
+    /// checks if the user is impersonated by an administrator.
+    pub fn is_impersonated(&self) -> bool {
+        self.impersonator.is_some()
+    }
+}
+
+/// Every error state that could happen while granting access.
+#[derive(Debug, Error)]
+pub enum GrantedError {
+    /// The user is not authenticated (see `ConnectedUser` errors).
+    #[error("User not authenticated")]
+    NotAuthenticated,
+    /// The voter refused the access.
+    #[error("Access denied")]
+    Denied,
+    /// The access could not be decided (e.g. unknown subject or right).
+    #[error("Access could not be decided")]
+    Internal,
+}
+
+#[rocket::async_trait]
+impl<'r, S: SecuritySubject, R: SecurityRight> FromRequest<'r> for Granted<S, R> {
+    /// error type returned in case of authentication or authorization error.
+    type Error = GrantedError;
+
+    /// Guard interceptor authenticating the user, then checking its access.
+    ///
+    /// Tokens issued to OAuth clients on behalf of the user must also be granted the `subject:right` scope.
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let connected_user = match ConnectedUser::authenticate(req) {
+            Outcome::Success(connected_user) => connected_user,
+            Outcome::Failure((status, _)) => {
+                return Outcome::Failure((status, GrantedError::NotAuthenticated));
+            }
+            Outcome::Forward(forward) => return Outcome::Forward(forward),
+        };
+
+        let scope = format!("{}:{}", S::NAME, R::NAME);
+
+        if connected_user.is_delegated()
+            && !is_scope_granted(&connected_user.claims.custom.scopes, &scope)
+        {
+            req.local_cache(|| ErrorMessage {
+                message: format!("The token is not granted the {scope} scope."),
+            });
+
+            return Outcome::Failure((Status::Forbidden, GrantedError::Denied));
+        }
+
+        let security = req.rocket().state::<Security<dyn SecurityVoter>>().unwrap();
+
+        match security.has_access(S::NAME, R::NAME, &connected_user.user, None) {
+            Ok(AccessDecision::Granted) => Outcome::Success(Granted {
+                user: connected_user.user,
+                claims: connected_user.claims,
+                impersonator: connected_user.impersonator,
+                permission: PhantomData,
+            }),
+            Ok(AccessDecision::Denied(reason)) => {
+                req.local_cache(|| ErrorMessage {
+                    message: reason.unwrap_or_else(|| "Access denied.".into()),
+                });
+
+                Outcome::Failure((Status::Forbidden, GrantedError::Denied))
+            }
+            Err(error) => {
+                // misconfiguration : logged, never exposed to clients.
+                log::error!(target: "security", "Cannot decide access : {error}");
+
+                Outcome::Failure((Status::InternalServerError, GrantedError::Internal))
+            }
+        }
+    }
+}
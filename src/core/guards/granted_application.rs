@@ -0,0 +1,75 @@
+use std::marker::PhantomData;
+
+use rocket::{
+    http::Status,
+    request::{FromRequest, Outcome},
+    Request,
+};
+
+use crate::core::{
+    guards::{
+        connected_application::ConnectedApplication,
+        granted::{GrantedError, SecurityRight, SecuritySubject},
+    },
+    response::ErrorMessage,
+    security::{AccessDecision, Security, SecurityVoter},
+};
+
+/// Guard granting access to a right on a subject to the application authenticated with an API key or a client token, before the controller runs.
+///
+/// It forwards requests without such credentials (e.g. sent by users), so a route using it can be ranked before the same route using `Granted` :
+/// ```rust
+/// #[get("/users", rank = 0)]
+/// pub fn user_list_for_application(granted: GrantedApplication<UserSubject, ListRight>) -> ... {
+///     let application_id = granted.application.application.id;
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct GrantedApplication<S: SecuritySubject, R: SecurityRight> {
+    /// the application authenticated with an API key or a client token.
+    pub application: ConnectedApplication,
+    permission: PhantomData<(S, R)>,
+}
+
+#[rocket::async_trait]
+impl<'r, S: SecuritySubject, R: SecurityRight> FromRequest<'r> for GrantedApplication<S, R> {
+    /// error type returned in case of authentication or authorization error.
+    type Error = GrantedError;
+
+    /// Guard interceptor authenticating the application, then checking its access.
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        if !ConnectedApplication::has_credentials(req) {
+            return Outcome::Forward(());
+        }
+
+        let connected_application = match req.guard::<ConnectedApplication>().await {
+            Outcome::Success(connected_application) => connected_application,
+            Outcome::Failure((status, _)) => {
+                return Outcome::Failure((status, GrantedError::NotAuthenticated));
+            }
+            Outcome::Forward(forward) => return Outcome::Forward(forward),
+        };
+
+        let security = req.rocket().state::<Security<dyn SecurityVoter>>().unwrap();
+
+        match security.has_access(S::NAME, R::NAME, &connected_application, None) {
+            Ok(AccessDecision::Granted) => Outcome::Success(GrantedApplication {
+                application: connected_application,
+                permission: PhantomData,
+            }),
+            Ok(AccessDecision::Denied(reason)) => {
+                req.local_cache(|| ErrorMessage {
+                    message: reason.unwrap_or_else(|| "Access denied.".into()),
+                });
+
+                Outcome::Failure((Status::Forbidden, GrantedError::Denied))
+            }
+            Err(error) => {
+                // misconfiguration : logged, never exposed to clients.
+                log::error!(target: "security", "Cannot decide access : {error}");
+
+                Outcome::Failure((Status::InternalServerError, GrantedError::Internal))
+            }
+        }
+    }
+}
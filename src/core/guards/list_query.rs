@@ -0,0 +1,404 @@
+use std::marker::PhantomData;
+
+use chrono::{DateTime, Utc};
+use rocket::{
+    http::Status,
+    request::{FromRequest, Outcome},
+    Request,
+};
+
+use crate::core::response::ErrorMessage;
+
+/// Kinds of filterable fields, telling which values and operators are accepted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldKind {
+    /// compared as text, the `like` operator being available.
+    Text,
+    /// an integer, such as an ID.
+    Integer,
+    /// a RFC 3339 date, such as `2023-01-31T00:00:00Z`.
+    Date,
+}
+
+/// A trait for DTOs to whitelist the fields listings can be filtered and sorted by :
+/// ```rust
+/// impl ListFields for UserListItemDTO {
+///     const FILTERABLE: &'static [(&'static str, FieldKind)] =
+///         &[("login", FieldKind::Text), ("created_date", FieldKind::Date)];
+///     const SORTABLE: &'static [&'static str] = &["login", "created_date"];
+/// }
+/// ```
+pub trait ListFields {
+    /// fields which can be filtered, with their kind.
+    const FILTERABLE: &'static [(&'static str, FieldKind)];
+    /// fields which can be sorted.
+    const SORTABLE: &'static [&'static str];
+}
+
+/// Comparison operators of filters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterOperator {
+    Eq,
+    Ne,
+    Lt,
+    Lte,
+    Gt,
+    Gte,
+    /// the field contains the value, case-insensitively (text fields only).
+    Like,
+}
+
+impl FilterOperator {
+    /// every operator, by name.
+    pub const ALL: [(&'static str, FilterOperator); 7] = [
+        ("eq", FilterOperator::Eq),
+        ("ne", FilterOperator::Ne),
+        ("lt", FilterOperator::Lt),
+        ("lte", FilterOperator::Lte),
+        ("gt", FilterOperator::Gt),
+        ("gte", FilterOperator::Gte),
+        ("like", FilterOperator::Like),
+    ];
+
+    /// gets an operator by its name (e.g. `gte`).
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .find(|(operator_name, _)| *operator_name == name)
+            .map(|(_, operator)| *operator)
+    }
+}
+
+/// A filter on a whitelisted field, whose value is checked against the field kind.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Filter {
+    pub field: String,
+    pub operator: FilterOperator,
+    pub value: String,
+}
+
+impl Filter {
+    /// gets the pattern of a `like` filter, matching the value anywhere with its `%`, `_` and `\` taken literally.
+    pub fn get_like_pattern(&self) -> String {
+        let mut pattern = String::with_capacity(self.value.len() + 2);
+        pattern.push('%');
+
+        for character in self.value.chars() {
+            if matches!(character, '%' | '_' | '\\') {
+                pattern.push('\\');
+            }
+
+            pattern.push(character);
+        }
+
+        pattern.push('%');
+
+        pattern
+    }
+}
+
+/// A sort on a whitelisted field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sort {
+    pub field: String,
+    pub descending: bool,
+}
+
+/// A struct representing the filters and sorts of a listing, such as `?filter[login][like]=john&sort=-created_date,login`.
+///
+/// The operator defaults to `eq` (e.g. `filter[login]=john`), and a leading `-` sorts in descending order.
+/// Fields are checked against the whitelist of the listed DTO `D`, and repositories apply them to their boxed queries
+/// with the `filter_by!` and `sort_by!` macros.
+#[derive(Debug)]
+pub struct ListQuery<D> {
+    pub filters: Vec<Filter>,
+    pub sorts: Vec<Sort>,
+    dto: PhantomData<fn() -> D>,
+}
+
+// implemented by hand, as deriving would require DTOs to be `Clone`.
+impl<D> Clone for ListQuery<D> {
+    fn clone(&self) -> Self {
+        Self {
+            filters: self.filters.clone(),
+            sorts: self.sorts.clone(),
+            dto: PhantomData,
+        }
+    }
+}
+
+impl<D> Default for ListQuery<D> {
+    fn default() -> Self {
+        Self {
+            filters: Vec::new(),
+            sorts: Vec::new(),
+            dto: PhantomData,
+        }
+    }
+}
+
+/// Different states of errors that can encounter list query extraction.
+#[derive(Debug, PartialEq, Eq)]
+pub enum ListQueryError {
+    /// the filter parameter is not like `filter[field][operator]`.
+    MalformedFilter(String),
+    /// the field cannot be filtered.
+    UnknownFilterField(String),
+    /// the operator does not exist, or does not apply to the field.
+    UnknownOperator(String),
+    /// the value does not match the field kind.
+    InvalidValue(String),
+    /// the field cannot be sorted.
+    UnknownSortField(String),
+}
+
+impl ListQueryError {
+    /// gets the message given to the client, listing what is allowed.
+    pub fn get_message<D: ListFields>(&self) -> String {
+        match self {
+            ListQueryError::MalformedFilter(name) => {
+                format!("Malformed filter {name}, expected filter[field][operator]")
+            }
+            ListQueryError::UnknownFilterField(field) => format!(
+                "Unknown filter field {field}, allowed fields are : {}",
+                D::FILTERABLE
+                    .iter()
+                    .map(|(name, _)| *name)
+                    .collect::<Vec<&str>>()
+                    .join(", ")
+            ),
+            ListQueryError::UnknownOperator(operator) => format!(
+                "Unknown filter operator {operator}, allowed operators are : {} (like on text fields only)",
+                FilterOperator::ALL
+                    .iter()
+                    .map(|(name, _)| *name)
+                    .collect::<Vec<&str>>()
+                    .join(", ")
+            ),
+            ListQueryError::InvalidValue(field) => format!("Invalid value for filter field {field}"),
+            ListQueryError::UnknownSortField(field) => format!(
+                "Unknown sort field {field}, allowed fields are : {}",
+                D::SORTABLE.join(", ")
+            ),
+        }
+    }
+}
+
+impl<D> ListQuery<D> {
+    /// adds a filter, without checking the whitelist (e.g. to restrict a listing to the connected user).
+    pub fn with_filter(mut self, field: &str, operator: FilterOperator, value: &str) -> Self {
+        self.filters.push(Filter {
+            field: field.into(),
+            operator,
+            value: value.into(),
+        });
+
+        self
+    }
+}
+
+impl<D: ListFields> ListQuery<D> {
+    /// parses filters and sorts from query segments (e.g. `("filter[login][eq]", "john")` and `("sort", "-created_date")`).
+    pub fn parse<'a>(
+        segments: impl IntoIterator<Item = (&'a str, &'a str)>,
+    ) -> Result<Self, ListQueryError> {
+        let mut list_query = Self::default();
+
+        for (name, value) in segments {
+            if name == "sort" {
+                for field in value.split(',').filter(|field| !field.is_empty()) {
+                    list_query.sorts.push(Self::parse_sort(field)?);
+                }
+            } else if name.starts_with("filter[") {
+                list_query.filters.push(Self::parse_filter(name, value)?);
+            }
+        }
+
+        Ok(list_query)
+    }
+
+    /// PRIVATE - parses a filter, such as `filter[login][like]=john`.
+    fn parse_filter(name: &str, value: &str) -> Result<Filter, ListQueryError> {
+        let parts = name
+            .strip_prefix("filter[")
+            .and_then(|name| name.strip_suffix(']'))
+            .map(|name| name.split("][").collect::<Vec<&str>>())
+            .unwrap_or_default();
+
+        let (field, operator) = match parts[..] {
+            [field] => (field, "eq"),
+            [field, operator] => (field, operator),
+            _ => return Err(ListQueryError::MalformedFilter(name.into())),
+        };
+
+        let kind = D::FILTERABLE
+            .iter()
+            .find(|(filterable, _)| *filterable == field)
+            .map(|(_, kind)| *kind)
+            .ok_or_else(|| ListQueryError::UnknownFilterField(field.into()))?;
+
+        let operator = FilterOperator::from_name(operator)
+            .filter(|operator| *operator != FilterOperator::Like || kind == FieldKind::Text)
+            .ok_or_else(|| ListQueryError::UnknownOperator(operator.into()))?;
+
+        let is_valid = match kind {
+            FieldKind::Text => true,
+            FieldKind::Integer => value.parse::<i32>().is_ok(),
+            FieldKind::Date => value.parse::<DateTime<Utc>>().is_ok(),
+        };
+
+        if !is_valid {
+            return Err(ListQueryError::InvalidValue(field.into()));
+        }
+
+        Ok(Filter {
+            field: field.into(),
+            operator,
+            value: value.into(),
+        })
+    }
+
+    /// PRIVATE - parses a sort, such as `-created_date`.
+    fn parse_sort(field: &str) -> Result<Sort, ListQueryError> {
+        let (field, descending) = match field.strip_prefix('-') {
+            Some(field) => (field, true),
+            None => (field, false),
+        };
+
+        if !D::SORTABLE.contains(&field) {
+            return Err(ListQueryError::UnknownSortField(field.into()));
+        }
+
+        Ok(Sort {
+            field: field.into(),
+            descending,
+        })
+    }
+}
+
+#[rocket::async_trait]
+impl<'r, D: ListFields> FromRequest<'r> for ListQuery<D> {
+    type Error = ListQueryError;
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let segments = req
+            .uri()
+            .query()
+            .map(|query| query.segments().collect::<Vec<(&str, &str)>>())
+            .unwrap_or_default();
+
+        match Self::parse(segments) {
+            Ok(list_query) => Outcome::Success(list_query),
+            Err(error) => {
+                req.local_cache(|| ErrorMessage {
+                    message: error.get_message::<D>(),
+                });
+
+                Outcome::Failure((Status::BadRequest, error))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct TestDTO;
+
+    impl ListFields for TestDTO {
+        const FILTERABLE: &'static [(&'static str, FieldKind)] = &[
+            ("id", FieldKind::Integer),
+            ("login", FieldKind::Text),
+            ("created_date", FieldKind::Date),
+        ];
+        const SORTABLE: &'static [&'static str] = &["login", "created_date"];
+    }
+
+    #[test]
+    fn test_parse_filters_and_sorts() {
+        let list_query = ListQuery::<TestDTO>::parse([
+            ("filter[login][like]", "john"),
+            ("filter[id]", "3"),
+            ("sort", "-created_date,login"),
+            ("page", "2"),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            list_query.filters,
+            vec![
+                Filter {
+                    field: "login".into(),
+                    operator: FilterOperator::Like,
+                    value: "john".into(),
+                },
+                Filter {
+                    field: "id".into(),
+                    operator: FilterOperator::Eq,
+                    value: "3".into(),
+                },
+            ]
+        );
+        assert_eq!(
+            list_query.sorts,
+            vec![
+                Sort {
+                    field: "created_date".into(),
+                    descending: true,
+                },
+                Sort {
+                    field: "login".into(),
+                    descending: false,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_unknown_fields_and_invalid_values() {
+        let parse = |name, value| ListQuery::<TestDTO>::parse([(name, value)]).err();
+
+        assert_eq!(
+            parse("filter[password][eq]", "secret"),
+            Some(ListQueryError::UnknownFilterField("password".into()))
+        );
+        assert_eq!(
+            parse("filter[login][regex]", "j.*"),
+            Some(ListQueryError::UnknownOperator("regex".into()))
+        );
+        assert_eq!(
+            parse("filter[id][like]", "3"),
+            Some(ListQueryError::UnknownOperator("like".into()))
+        );
+        assert_eq!(
+            parse("filter[created_date][gte]", "yesterday"),
+            Some(ListQueryError::InvalidValue("created_date".into()))
+        );
+        assert_eq!(
+            parse("filter[login][eq][x]", "john"),
+            Some(ListQueryError::MalformedFilter(
+                "filter[login][eq][x]".into()
+            ))
+        );
+        assert_eq!(
+            parse("sort", "-password"),
+            Some(ListQueryError::UnknownSortField("password".into()))
+        );
+        assert_eq!(
+            ListQueryError::UnknownFilterField("password".into()).get_message::<TestDTO>(),
+            "Unknown filter field password, allowed fields are : id, login, created_date"
+        );
+    }
+
+    #[test]
+    fn test_like_pattern_escapes_wildcards() {
+        let filter = Filter {
+            field: "login".into(),
+            operator: FilterOperator::Like,
+            value: r"50%_off\".into(),
+        };
+
+        assert_eq!(filter.get_like_pattern(), r"%50\%\_off\\%");
+    }
+}
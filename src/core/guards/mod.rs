@@ -1,2 +1,9 @@
+pub mod client_credentials;
+pub mod client_ip;
+pub mod connected_application;
 pub mod connected_user;
+pub mod cursor_pagination;
+pub mod granted;
+pub mod granted_application;
+pub mod list_query;
 pub mod pagination;
@@ -4,18 +4,58 @@ use rocket::{
     Request,
 };
 
-use crate::core::response::ErrorMessage;
+use crate::core::{configuration::ConfigState, response::ErrorMessage};
 
 /// Default page number for pagination.
-const DEFAULT_PAGE: u16 = 0;
+const DEFAULT_PAGE: u16 = 1;
 /// Default number of item per page for pagination.
-const DEFAULT_PER_PAGE: u16 = 25;
+pub const DEFAULT_PER_PAGE: u16 = 25;
+/// Default maximum number of item per page, e.g. when `pagination_max_per_page` is not configured.
+const DEFAULT_MAX_PER_PAGE: i64 = 100;
 
 /// A struct representing pagination options for listings.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct Pagination {
     pub page: u16,
     pub per_page: u16,
+    /// path of the listing, to link other pages.
+    path: String,
+    /// raw query segments of the listing other than `page` and `per_page` (e.g. filters), kept when linking other pages.
+    query: Vec<String>,
+}
+
+impl Pagination {
+    /// constructor.
+    pub fn new(page: u16, per_page: u16, path: &str) -> Self {
+        Self {
+            page,
+            per_page,
+            path: path.into(),
+            query: Vec::new(),
+        }
+    }
+
+    /// gets the URI of a page of the listing, keeping the other query parameters.
+    pub fn get_page_uri(&self, page: i64) -> String {
+        let mut query = self.query.clone();
+        query.push(format!("page={page}"));
+        query.push(format!("per_page={}", self.per_page));
+
+        format!("{}?{}", self.path, query.join("&"))
+    }
+}
+
+/// gets the maximum number of item per page, set by the `pagination_max_per_page` configuration.
+pub fn get_max_per_page(req: &Request<'_>) -> u16 {
+    let max_per_page = req
+        .rocket()
+        .state::<ConfigState>()
+        .map(|configuration| {
+            configuration.get_int_or_default("pagination_max_per_page", DEFAULT_MAX_PER_PAGE)
+        })
+        .unwrap_or(DEFAULT_MAX_PER_PAGE);
+
+    max_per_page.clamp(1, u16::MAX.into()) as u16
 }
 
 /// Different states of errors that can encounter pagination extraction.
@@ -68,7 +108,7 @@ impl<'r> FromRequest<'r> for Pagination {
             req.local_cache(|| ErrorMessage {
                 message: "page must be a positive integer".into(),
             });
-            return Outcome::Failure((Status::BadRequest, PaginationError::ZeroPerPage));
+            return Outcome::Failure((Status::BadRequest, PaginationError::ZeroPage));
         }
 
         if per_page == 0 {
@@ -78,6 +118,30 @@ impl<'r> FromRequest<'r> for Pagination {
             return Outcome::Failure((Status::BadRequest, PaginationError::ZeroPerPage));
         }
 
-        Outcome::Success(Pagination { page, per_page })
+        // per_page is capped, to protect the database from huge listings.
+        per_page = per_page.min(get_max_per_page(req));
+
+        let query = req
+            .uri()
+            .query()
+            .map(|query| {
+                query
+                    .raw_segments()
+                    .filter(|segment| {
+                        let name = segment.as_str().split('=').next().unwrap_or_default();
+
+                        name != "page" && name != "per_page"
+                    })
+                    .map(|segment| segment.to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Outcome::Success(Pagination {
+            page,
+            per_page,
+            path: req.uri().path().to_string(),
+            query,
+        })
     }
 }
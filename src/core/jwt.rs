@@ -1,71 +1,184 @@
-use std::{collections::HashSet, fs, path::PathBuf};
+use std::{collections::HashSet, path::PathBuf};
+
+use chrono::{DateTime, TimeZone, Utc};
 
 use jwt_simple::prelude::*;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
+use ulid::Ulid;
 
-use super::dirs;
+use super::{
+    dirs,
+    jwt_keys::{JWTKeyStore, JWTSigningKey, JWTVerificationKey},
+};
 
 /// A struct representing JWT claims for this API.
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct APIClaim {
-    /// the user ID of this JWT token.
-    pub user_id: i32,
+    /// the user ID of this JWT token, if issued for a user.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<i32>,
     /// the user's roles.
     pub roles: Vec<String>,
-    /// the user's username (or login).
+    /// the user's username (or login), or the client ID for tokens issued to an OAuth client.
     pub username: String,
+    /// the ID of the OAuth client the token was issued to, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+    /// the OAuth scopes granted to the token.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub scopes: Vec<String>,
+    /// the ID of the administrator impersonating the user, if the token was issued to switch user.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub impersonator_id: Option<i32>,
+}
+
+/// A struct representing a public key as a JSON Web Key (RFC 7517 and RFC 8037), to be published in a JWKS.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, JsonSchema)]
+pub struct JWK {
+    /// key type (e.g. `RSA`, `EC` or `OKP`).
+    pub kty: String,
+    /// intended use of the key, always `sig`.
+    #[serde(rename = "use")]
+    pub key_use: String,
+    /// algorithm the key is used with.
+    pub alg: String,
+    /// key ID, matching the `kid` header of tokens.
+    pub kid: String,
+    /// RSA modulus, base64url encoded.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub n: Option<String>,
+    /// RSA public exponent, base64url encoded.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub e: Option<String>,
+    /// curve of elliptic curve keys.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub crv: Option<String>,
+    /// x coordinate (or public key for OKP keys), base64url encoded.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub x: Option<String>,
+    /// y coordinate of EC keys, base64url encoded.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub y: Option<String>,
 }
 
 /// Utility function to encode a JWT token, given claims, TTL and issuer for the token to generate.
-pub fn encode(claim: APIClaim, jwt_ttl: i64, issuer: &str) -> Result<String> {
-    let key_pair = RS512KeyPair::from_pem(&get_private_certificate_content()?)
-        .with_context(|| "Cannot acquire private key.")?;
+///
+/// Each token is given a unique ID (e.g. `jti` claim), allowing it to be denied before its expiration.
+/// The token is signed with the current signing key, whose ID is sent in the `kid` header.
+pub fn encode(
+    key_store: &JWTKeyStore,
+    claim: APIClaim,
+    jwt_ttl: i64,
+    issuer: &str,
+) -> Result<String> {
+    let keys = key_store.get()?;
+
+    let signing_key = keys
+        .get_signing_key()
+        .with_context(|| "Cannot find a JWT signing key.")?;
 
+    encode_with_key(signing_key, claim, jwt_ttl, issuer)
+}
+
+/// Utility function to encode a JWT token with the given key material.
+pub fn encode_with_key(
+    signing_key: &JWTSigningKey,
+    claim: APIClaim,
+    jwt_ttl: i64,
+    issuer: &str,
+) -> Result<String> {
     let claims = Claims::with_custom_claims(claim, Duration::from_secs(jwt_ttl.unsigned_abs()))
         .with_issuer(issuer)
-        .with_subject("authorization");
-
-    let token = key_pair
-        .sign(claims)
-        .with_context(|| "Cannot sign JWT claims")?;
+        .with_subject("authorization")
+        .with_jwt_id(Ulid::new());
 
-    Ok(token)
+    signing_key.sign(claims)
 }
 
 /// Utility function to decode a given JWT token as a `&str` for a given issuer.
-pub fn decode(token: &str, issuer: &str) -> Result<JWTClaims<APIClaim>> {
-    let public_key = RS512PublicKey::from_pem(&get_public_certificate_content()?)
-        .with_context(|| "Cannot acquire public key.")?;
+///
+/// The token is verified with the key matching its `kid` header. Tokens without `kid` (e.g. signed
+/// before key rotation was available) are checked against every key still valid for verification.
+pub fn decode(key_store: &JWTKeyStore, token: &str, issuer: &str) -> Result<JWTClaims<APIClaim>> {
+    let keys = key_store.get()?;
 
-    let options = VerificationOptions {
-        accept_future: true,
-        allowed_issuers: Some(HashSet::from_strings(&[issuer])),
-        ..Default::default()
+    let metadata = Token::decode_metadata(token).with_context(|| "Cannot decode token header")?;
+
+    let verification_keys = match metadata.key_id() {
+        Some(kid) => vec![keys
+            .find_verification_key(kid)
+            .with_context(|| format!("Cannot find a public key for kid {kid}"))?],
+        None => keys
+            .get_verification_keys()
+            .into_iter()
+            .map(|(_, verification_key)| verification_key)
+            .collect(),
     };
 
-    let claims = public_key
-        .verify_token::<APIClaim>(token, Some(options))
-        .with_context(|| "Cannot verify token with public key")?;
+    decode_with_keys(token, verification_keys, issuer)
+}
+
+/// Utility function to decode a given JWT token with the first of the given keys able to verify it.
+pub fn decode_with_keys<'k>(
+    token: &str,
+    verification_keys: impl IntoIterator<Item = &'k JWTVerificationKey>,
+    issuer: &str,
+) -> Result<JWTClaims<APIClaim>> {
+    for verification_key in verification_keys {
+        let options = VerificationOptions {
+            accept_future: true,
+            allowed_issuers: Some(HashSet::from_strings(&[issuer])),
+            ..Default::default()
+        };
+
+        if let Ok(claims) = verification_key.verify::<APIClaim>(token, Some(options)) {
+            return Ok(claims);
+        }
+    }
 
-    Ok(claims)
+    bail!("Cannot verify token with public key")
 }
 
-/// Utility function to get API's private key file.
-pub fn get_private_certificate_path() -> PathBuf {
-    let mut path: PathBuf = get_certificate_dir();
-    path.push("private.pem");
+/// Utility function to get all public keys able to verify tokens, as JSON Web Keys.
+///
+/// Shared secrets (e.g. HMAC keys) are never published.
+pub fn get_jwks(key_store: &JWTKeyStore) -> Result<Vec<JWK>> {
+    let keys = key_store.get()?;
+
+    let mut jwks = Vec::<JWK>::new();
 
-    path
+    for (key, verification_key) in keys.get_verification_keys().into_iter() {
+        if let Some(jwk) = to_jwk(&key.kid, verification_key)? {
+            jwks.push(jwk);
+        }
+    }
+
+    Ok(jwks)
+}
+
+/// Utility function to get the names of the algorithms of all keys able to verify tokens.
+pub fn get_signing_algorithms(key_store: &JWTKeyStore) -> Result<Vec<String>> {
+    let keys = key_store.get()?;
+
+    let mut algorithms = Vec::<String>::new();
+
+    for (key, _) in keys.get_verification_keys().into_iter() {
+        if !algorithms.contains(&key.alg.to_string()) {
+            algorithms.push(key.alg.to_string());
+        }
+    }
+
+    Ok(algorithms)
 }
 
-/// Utility function to get API's public key file.
-pub fn get_public_certificate_path() -> PathBuf {
-    let mut path: PathBuf = get_certificate_dir();
-    path.push("public.pem");
+/// Utility function to get the expiration date of decoded JWT claims, if any.
+pub fn get_expiration_date(claims: &JWTClaims<APIClaim>) -> Option<DateTime<Utc>> {
+    let expires_at = claims.expires_at?;
 
-    path
+    Utc.timestamp_opt(expires_at.as_secs() as i64, 0).single()
 }
 
 /// Utility function to get API's certificate directory path.
@@ -73,22 +186,247 @@ pub fn get_certificate_dir() -> PathBuf {
     dirs::certificate_dir()
 }
 
-/// PRIVATE - Utility function to gather private certificate file content
-fn get_private_certificate_content() -> Result<String> {
-    let content = fs::read_to_string(get_private_certificate_path())
-        .with_context(|| "Cannot find private certificate file")?
-        .parse()
-        .with_context(|| "Cannot parse private certificate file")?;
+/// PRIVATE - converts verification key material as a JSON Web Key, if it can be published.
+fn to_jwk(kid: &str, verification_key: &JWTVerificationKey) -> Result<Option<JWK>> {
+    let base_jwk = JWK {
+        key_use: "sig".into(),
+        kid: kid.into(),
+        ..Default::default()
+    };
+
+    let jwk = match verification_key {
+        JWTVerificationKey::RS256(public_key) => {
+            let components = public_key.to_components();
+
+            JWK {
+                kty: "RSA".into(),
+                alg: "RS256".into(),
+                n: Some(Base64UrlSafeNoPadding::encode_to_string(components.n)?),
+                e: Some(Base64UrlSafeNoPadding::encode_to_string(components.e)?),
+                ..base_jwk
+            }
+        }
+        JWTVerificationKey::RS512(public_key) => {
+            let components = public_key.to_components();
+
+            JWK {
+                kty: "RSA".into(),
+                alg: "RS512".into(),
+                n: Some(Base64UrlSafeNoPadding::encode_to_string(components.n)?),
+                e: Some(Base64UrlSafeNoPadding::encode_to_string(components.e)?),
+                ..base_jwk
+            }
+        }
+        JWTVerificationKey::ES256(public_key) => {
+            // uncompressed SEC1 point : 0x04 || x || y
+            let point = public_key.public_key().to_bytes_uncompressed();
+
+            JWK {
+                kty: "EC".into(),
+                alg: "ES256".into(),
+                crv: Some("P-256".into()),
+                x: Some(Base64UrlSafeNoPadding::encode_to_string(&point[1..33])?),
+                y: Some(Base64UrlSafeNoPadding::encode_to_string(&point[33..65])?),
+                ..base_jwk
+            }
+        }
+        JWTVerificationKey::EdDSA(public_key) => JWK {
+            kty: "OKP".into(),
+            alg: "EdDSA".into(),
+            crv: Some("Ed25519".into()),
+            x: Some(Base64UrlSafeNoPadding::encode_to_string(
+                public_key.to_bytes(),
+            )?),
+            ..base_jwk
+        },
+        JWTVerificationKey::HS256(_) => return Ok(None),
+    };
 
-    Ok(content)
+    Ok(Some(jwk))
 }
 
-/// PRIVATE - Utility function to gather public certificate file content
-fn get_public_certificate_content() -> Result<String> {
-    let content = fs::read_to_string(get_public_certificate_path())
-        .with_context(|| "Cannot find public certificate file")?
-        .parse()
-        .with_context(|| "Cannot parse public certificate file")?;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::jwt_keys::{JWTAlgorithm, JWTKey};
+
+    const ISSUER: &str = "jwt-tests";
+
+    /// gets a registered key, retired and verifying tokens for `verification_ttl` seconds if given.
+    fn get_key(kid: &str, alg: JWTAlgorithm, verification_ttl: Option<i64>) -> JWTKey {
+        let now = Utc::now();
+
+        JWTKey {
+            kid: kid.into(),
+            alg,
+            created_at: now,
+            retired_at: verification_ttl.map(|_| now),
+            expires_at: verification_ttl.map(|ttl| now + chrono::Duration::seconds(ttl)),
+        }
+    }
+
+    fn get_claim() -> APIClaim {
+        APIClaim {
+            user_id: Some(42),
+            roles: vec!["ROLE_USER".into(), "ROLE_ADMIN".into()],
+            username: "john.doe".into(),
+            client_id: Some("client".into()),
+            scopes: vec!["user:list".into()],
+            impersonator_id: None,
+        }
+    }
+
+    #[test]
+    fn test_round_trip_for_every_algorithm() {
+        for algorithm in JWTAlgorithm::ALL {
+            let signing_key = JWTSigningKey::generate(algorithm)
+                .unwrap()
+                .with_key_id("test-key");
+
+            let token = encode_with_key(&signing_key, get_claim(), 3600, ISSUER).unwrap();
+
+            let metadata = Token::decode_metadata(&token).unwrap();
+            assert_eq!(metadata.algorithm(), algorithm.as_str());
+            assert_eq!(metadata.key_id(), Some("test-key"));
+
+            let claims =
+                decode_with_keys(&token, &[signing_key.verification_key()], ISSUER).unwrap();
+
+            assert_eq!(claims.custom.user_id, Some(42), "{algorithm}");
+            assert_eq!(
+                claims.custom.client_id.as_deref(),
+                Some("client"),
+                "{algorithm}"
+            );
+            assert_eq!(claims.custom.scopes, get_claim().scopes, "{algorithm}");
+            assert_eq!(claims.custom.username, "john.doe", "{algorithm}");
+            assert_eq!(claims.custom.roles, get_claim().roles, "{algorithm}");
+            assert!(claims.jwt_id.is_some(), "{algorithm}");
+        }
+    }
+
+    #[test]
+    fn test_decode_rejects_other_keys_and_issuers() {
+        let signing_key = JWTSigningKey::generate(JWTAlgorithm::ES256).unwrap();
+        let other_key = JWTSigningKey::generate(JWTAlgorithm::ES256).unwrap();
+        let hmac_key = JWTSigningKey::generate(JWTAlgorithm::HS256).unwrap();
+
+        let token = encode_with_key(&signing_key, get_claim(), 3600, ISSUER).unwrap();
+
+        assert!(decode_with_keys(&token, &[other_key.verification_key()], ISSUER).is_err());
+        assert!(decode_with_keys(&token, &[hmac_key.verification_key()], ISSUER).is_err());
+        assert!(
+            decode_with_keys(&token, &[signing_key.verification_key()], "another-issuer").is_err()
+        );
+        assert!(decode_with_keys(
+            &token,
+            &[other_key.verification_key(), signing_key.verification_key()],
+            ISSUER
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn test_algorithm_parsing() {
+        assert_eq!(
+            "rs256".parse::<JWTAlgorithm>().unwrap(),
+            JWTAlgorithm::RS256
+        );
+        assert_eq!(
+            "EDDSA".parse::<JWTAlgorithm>().unwrap(),
+            JWTAlgorithm::EdDSA
+        );
+        assert_eq!(
+            " HS256 ".parse::<JWTAlgorithm>().unwrap(),
+            JWTAlgorithm::HS256
+        );
+        assert!("none".parse::<JWTAlgorithm>().is_err());
+    }
+
+    #[test]
+    fn test_retired_keys_verify_tokens_until_they_expire() {
+        let retired_key = JWTSigningKey::generate(JWTAlgorithm::ES256)
+            .unwrap()
+            .with_key_id("retired");
+        let expired_key = JWTSigningKey::generate(JWTAlgorithm::ES256)
+            .unwrap()
+            .with_key_id("expired");
+
+        // tokens signed before the rotation.
+        let retired_token = encode_with_key(&retired_key, get_claim(), 3600, ISSUER).unwrap();
+        let expired_token = encode_with_key(&expired_key, get_claim(), 3600, ISSUER).unwrap();
+
+        let key_store = JWTKeyStore::from_keys(vec![
+            (
+                get_key("retired", JWTAlgorithm::ES256, Some(3600)),
+                retired_key,
+            ),
+            (
+                get_key("expired", JWTAlgorithm::ES256, Some(-1)),
+                expired_key,
+            ),
+            (
+                get_key("current", JWTAlgorithm::EdDSA, None),
+                JWTSigningKey::generate(JWTAlgorithm::EdDSA).unwrap(),
+            ),
+        ]);
+
+        let token = encode(&key_store, get_claim(), 3600, ISSUER).unwrap();
+
+        assert_eq!(
+            Token::decode_metadata(&token).unwrap().key_id(),
+            Some("current")
+        );
+        assert!(decode(&key_store, &token, ISSUER).is_ok());
+        assert!(decode(&key_store, &retired_token, ISSUER).is_ok());
+        assert!(decode(&key_store, &expired_token, ISSUER).is_err());
+    }
+
+    #[test]
+    fn test_decode_rejects_unknown_key_ids() {
+        let key_store = JWTKeyStore::from_keys(vec![(
+            get_key("current", JWTAlgorithm::ES256, None),
+            JWTSigningKey::generate(JWTAlgorithm::ES256).unwrap(),
+        )]);
+
+        let unknown_key = JWTSigningKey::generate(JWTAlgorithm::ES256)
+            .unwrap()
+            .with_key_id("unknown");
+        let token = encode_with_key(&unknown_key, get_claim(), 3600, ISSUER).unwrap();
+
+        let error = decode(&key_store, &token, ISSUER).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "Cannot find a public key for kid unknown"
+        );
+    }
+
+    #[test]
+    fn test_jwks_publish_public_keys_only() {
+        let key_store = JWTKeyStore::from_keys(vec![
+            (
+                get_key("secret", JWTAlgorithm::HS256, Some(3600)),
+                JWTSigningKey::generate(JWTAlgorithm::HS256).unwrap(),
+            ),
+            (
+                get_key("current", JWTAlgorithm::ES256, None),
+                JWTSigningKey::generate(JWTAlgorithm::ES256).unwrap(),
+            ),
+        ]);
+
+        let jwks = get_jwks(&key_store).unwrap();
 
-    Ok(content)
+        assert_eq!(jwks.len(), 1);
+        assert_eq!(jwks[0].kid, "current");
+        assert_eq!(jwks[0].kty, "EC");
+        assert_eq!(jwks[0].alg, "ES256");
+        assert_eq!(jwks[0].key_use, "sig");
+        assert_eq!(jwks[0].crv.as_deref(), Some("P-256"));
+        assert!(jwks[0].x.is_some() && jwks[0].y.is_some());
+        assert_eq!(
+            get_signing_algorithms(&key_store).unwrap(),
+            vec!["HS256".to_string(), "ES256".to_string()]
+        );
+    }
 }
@@ -0,0 +1,699 @@
+use std::{
+    fmt::Display,
+    fs,
+    path::PathBuf,
+    str::FromStr,
+    sync::{Arc, RwLock},
+    time::SystemTime,
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use jwt_simple::prelude::*;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use ulid::Ulid;
+
+use super::{configuration::ConfigState, dirs};
+
+/// Size in bits of generated RSA keys.
+const RSA_KEY_SIZE: usize = 2048;
+
+/// Name of the manifest file listing all JWT keys.
+const MANIFEST_FILE: &str = "keys.json";
+
+/// Algorithms available to sign JWT tokens.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum JWTAlgorithm {
+    /// RSA PKCS#1 v1.5 with SHA-256.
+    RS256,
+    /// RSA PKCS#1 v1.5 with SHA-512, the historical algorithm of this API.
+    #[default]
+    RS512,
+    /// ECDSA on the P-256 curve with SHA-256.
+    ES256,
+    /// EdDSA on the Ed25519 curve.
+    EdDSA,
+    /// HMAC with SHA-256, using a shared secret instead of a key pair.
+    HS256,
+}
+
+impl JWTAlgorithm {
+    /// all supported algorithms.
+    pub const ALL: [JWTAlgorithm; 5] = [
+        JWTAlgorithm::RS256,
+        JWTAlgorithm::RS512,
+        JWTAlgorithm::ES256,
+        JWTAlgorithm::EdDSA,
+        JWTAlgorithm::HS256,
+    ];
+
+    /// gets the algorithm configured to sign new tokens (e.g. `APP_JWT_ALGORITHM`), RS512 by default.
+    pub fn from_config(configuration: &ConfigState) -> Result<Self> {
+        configuration
+            .get_string_or_default("jwt_algorithm", JWTAlgorithm::default().as_str())
+            .parse()
+    }
+
+    /// gets the algorithm name, as sent in the `alg` header of tokens.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JWTAlgorithm::RS256 => "RS256",
+            JWTAlgorithm::RS512 => "RS512",
+            JWTAlgorithm::ES256 => "ES256",
+            JWTAlgorithm::EdDSA => "EdDSA",
+            JWTAlgorithm::HS256 => "HS256",
+        }
+    }
+
+    /// checks if the algorithm relies on a shared secret, which must never be published.
+    pub fn is_symmetric(&self) -> bool {
+        matches!(self, JWTAlgorithm::HS256)
+    }
+}
+
+impl FromStr for JWTAlgorithm {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match JWTAlgorithm::ALL
+            .into_iter()
+            .find(|algorithm| algorithm.as_str().eq_ignore_ascii_case(value.trim()))
+        {
+            Some(algorithm) => Ok(algorithm),
+            None => bail!("Unsupported JWT algorithm {value}"),
+        }
+    }
+}
+
+impl Display for JWTAlgorithm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Key material able to sign tokens, for each supported algorithm.
+pub enum JWTSigningKey {
+    RS256(RS256KeyPair),
+    RS512(RS512KeyPair),
+    ES256(ES256KeyPair),
+    EdDSA(Ed25519KeyPair),
+    HS256(HS256Key),
+}
+
+impl JWTSigningKey {
+    /// generates new key material for the given algorithm.
+    pub fn generate(algorithm: JWTAlgorithm) -> Result<Self> {
+        let key = match algorithm {
+            JWTAlgorithm::RS256 => JWTSigningKey::RS256(
+                RS256KeyPair::generate(RSA_KEY_SIZE)
+                    .with_context(|| "Cannot generate RSA key pair")?,
+            ),
+            JWTAlgorithm::RS512 => JWTSigningKey::RS512(
+                RS512KeyPair::generate(RSA_KEY_SIZE)
+                    .with_context(|| "Cannot generate RSA key pair")?,
+            ),
+            JWTAlgorithm::ES256 => JWTSigningKey::ES256(ES256KeyPair::generate()),
+            JWTAlgorithm::EdDSA => JWTSigningKey::EdDSA(Ed25519KeyPair::generate()),
+            JWTAlgorithm::HS256 => JWTSigningKey::HS256(HS256Key::generate()),
+        };
+
+        Ok(key)
+    }
+
+    /// loads the signing key material of a registered key from the certificate directory.
+    pub fn load(key: &JWTKey) -> Result<Self> {
+        let content = read_private_key(key)?;
+
+        Self::from_private_material(key.alg, &content)
+            .map(|signing_key| signing_key.with_key_id(&key.kid))
+            .with_context(|| format!("Cannot parse private key {}", key.kid))
+    }
+
+    /// gets the algorithm of the key material.
+    pub fn algorithm(&self) -> JWTAlgorithm {
+        match self {
+            JWTSigningKey::RS256(_) => JWTAlgorithm::RS256,
+            JWTSigningKey::RS512(_) => JWTAlgorithm::RS512,
+            JWTSigningKey::ES256(_) => JWTAlgorithm::ES256,
+            JWTSigningKey::EdDSA(_) => JWTAlgorithm::EdDSA,
+            JWTSigningKey::HS256(_) => JWTAlgorithm::HS256,
+        }
+    }
+
+    /// sets the key ID sent in the `kid` header of signed tokens.
+    pub fn with_key_id(self, kid: &str) -> Self {
+        match self {
+            JWTSigningKey::RS256(key) => JWTSigningKey::RS256(key.with_key_id(kid)),
+            JWTSigningKey::RS512(key) => JWTSigningKey::RS512(key.with_key_id(kid)),
+            JWTSigningKey::ES256(key) => JWTSigningKey::ES256(key.with_key_id(kid)),
+            JWTSigningKey::EdDSA(key) => JWTSigningKey::EdDSA(key.with_key_id(kid)),
+            JWTSigningKey::HS256(key) => JWTSigningKey::HS256(key.with_key_id(kid)),
+        }
+    }
+
+    /// gets the key material able to verify tokens signed by this key.
+    pub fn verification_key(&self) -> JWTVerificationKey {
+        match self {
+            JWTSigningKey::RS256(key) => JWTVerificationKey::RS256(key.public_key()),
+            JWTSigningKey::RS512(key) => JWTVerificationKey::RS512(key.public_key()),
+            JWTSigningKey::ES256(key) => JWTVerificationKey::ES256(key.public_key()),
+            JWTSigningKey::EdDSA(key) => JWTVerificationKey::EdDSA(key.public_key()),
+            JWTSigningKey::HS256(key) => JWTVerificationKey::HS256(key.clone()),
+        }
+    }
+
+    /// signs the given claims as a JWT token.
+    pub fn sign<CustomClaims: Serialize + DeserializeOwned>(
+        &self,
+        claims: JWTClaims<CustomClaims>,
+    ) -> Result<String> {
+        let token = match self {
+            JWTSigningKey::RS256(key) => key.sign(claims),
+            JWTSigningKey::RS512(key) => key.sign(claims),
+            JWTSigningKey::ES256(key) => key.sign(claims),
+            JWTSigningKey::EdDSA(key) => key.sign(claims),
+            JWTSigningKey::HS256(key) => key.authenticate(claims),
+        }
+        .with_context(|| "Cannot sign JWT claims")?;
+
+        Ok(token)
+    }
+
+    /// PRIVATE - exports the key material as stored in the certificate directory.
+    fn to_private_material(&self) -> Result<String> {
+        let content = match self {
+            JWTSigningKey::RS256(key) => key.to_pem()?,
+            JWTSigningKey::RS512(key) => key.to_pem()?,
+            JWTSigningKey::ES256(key) => key.to_pem()?,
+            JWTSigningKey::EdDSA(key) => key.to_pem(),
+            JWTSigningKey::HS256(key) => Base64UrlSafeNoPadding::encode_to_string(key.to_bytes())?,
+        };
+
+        Ok(content)
+    }
+
+    /// PRIVATE - parses key material as stored in the certificate directory.
+    fn from_private_material(algorithm: JWTAlgorithm, content: &str) -> Result<Self> {
+        let key = match algorithm {
+            JWTAlgorithm::RS256 => JWTSigningKey::RS256(RS256KeyPair::from_pem(content)?),
+            JWTAlgorithm::RS512 => JWTSigningKey::RS512(RS512KeyPair::from_pem(content)?),
+            JWTAlgorithm::ES256 => JWTSigningKey::ES256(ES256KeyPair::from_pem(content)?),
+            JWTAlgorithm::EdDSA => JWTSigningKey::EdDSA(Ed25519KeyPair::from_pem(content)?),
+            JWTAlgorithm::HS256 => JWTSigningKey::HS256(HS256Key::from_bytes(
+                &Base64UrlSafeNoPadding::decode_to_vec(content.trim(), None)?,
+            )),
+        };
+
+        Ok(key)
+    }
+}
+
+/// Key material able to verify tokens, for each supported algorithm.
+///
+/// For HMAC, the verification key is the shared secret itself.
+pub enum JWTVerificationKey {
+    RS256(RS256PublicKey),
+    RS512(RS512PublicKey),
+    ES256(ES256PublicKey),
+    EdDSA(Ed25519PublicKey),
+    HS256(HS256Key),
+}
+
+impl JWTVerificationKey {
+    /// loads the verification key material of a registered key from the certificate directory.
+    pub fn load(key: &JWTKey) -> Result<Self> {
+        if key.alg.is_symmetric() {
+            return Ok(JWTSigningKey::load(key)?.verification_key());
+        }
+
+        let content = read_public_key(&key.kid)?;
+
+        let verification_key = match key.alg {
+            JWTAlgorithm::RS256 => {
+                RS256PublicKey::from_pem(&content).map(JWTVerificationKey::RS256)
+            }
+            JWTAlgorithm::RS512 => {
+                RS512PublicKey::from_pem(&content).map(JWTVerificationKey::RS512)
+            }
+            JWTAlgorithm::ES256 => {
+                ES256PublicKey::from_pem(&content).map(JWTVerificationKey::ES256)
+            }
+            JWTAlgorithm::EdDSA => {
+                Ed25519PublicKey::from_pem(&content).map(JWTVerificationKey::EdDSA)
+            }
+            JWTAlgorithm::HS256 => unreachable!(),
+        }
+        .with_context(|| format!("Cannot parse public key {}", key.kid))?;
+
+        Ok(verification_key)
+    }
+
+    /// verifies a token, returning its claims.
+    pub fn verify<CustomClaims: Serialize + DeserializeOwned>(
+        &self,
+        token: &str,
+        options: Option<VerificationOptions>,
+    ) -> Result<JWTClaims<CustomClaims>> {
+        let claims = match self {
+            JWTVerificationKey::RS256(key) => key.verify_token(token, options),
+            JWTVerificationKey::RS512(key) => key.verify_token(token, options),
+            JWTVerificationKey::ES256(key) => key.verify_token(token, options),
+            JWTVerificationKey::EdDSA(key) => key.verify_token(token, options),
+            JWTVerificationKey::HS256(key) => key.verify_token(token, options),
+        }?;
+
+        Ok(claims)
+    }
+
+    /// PRIVATE - exports the public key as stored in the certificate directory, if the key is not a shared secret.
+    fn to_public_material(&self) -> Result<Option<String>> {
+        let content = match self {
+            JWTVerificationKey::RS256(key) => Some(key.to_pem()?),
+            JWTVerificationKey::RS512(key) => Some(key.to_pem()?),
+            JWTVerificationKey::ES256(key) => Some(key.to_pem()?),
+            JWTVerificationKey::EdDSA(key) => Some(key.to_pem()),
+            JWTVerificationKey::HS256(_) => None,
+        };
+
+        Ok(content)
+    }
+}
+
+/// A struct representing a JWT key pair registered in the key manifest.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct JWTKey {
+    /// the key ID, sent in the `kid` header of tokens signed with this key.
+    pub kid: String,
+    /// the algorithm of the key (keys registered before algorithms were configurable are RS512 ones).
+    #[serde(default)]
+    pub alg: JWTAlgorithm,
+    /// creation date of the key.
+    pub created_at: DateTime<Utc>,
+    /// date from which the key stopped signing tokens, if it has been rotated.
+    pub retired_at: Option<DateTime<Utc>>,
+    /// date from which the key is no longer accepted to verify tokens, if it has been rotated.
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl JWTKey {
+    /// checks if the key can still be used to verify tokens.
+    pub fn is_valid_for_verification(&self) -> bool {
+        match self.expires_at {
+            Some(expires_at) => Utc::now() < expires_at,
+            None => true,
+        }
+    }
+}
+
+/// A struct representing the manifest of all JWT keys stored in the certificate directory.
+///
+/// Only one key (e.g. the last created non-retired one) signs tokens, while all non-expired keys verify them.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct JWTKeyManifest {
+    /// all registered keys.
+    pub keys: Vec<JWTKey>,
+}
+
+impl JWTKeyManifest {
+    /// checks if a manifest has already been written.
+    pub fn exists() -> bool {
+        get_manifest_path().exists()
+    }
+
+    /// loads the manifest from the certificate directory.
+    pub fn load() -> Result<Self> {
+        let content = fs::read_to_string(get_manifest_path())
+            .with_context(|| "Cannot find JWT key manifest file")?;
+
+        let manifest =
+            serde_json::from_str(&content).with_context(|| "Cannot parse JWT key manifest file")?;
+
+        Ok(manifest)
+    }
+
+    /// saves the manifest into the certificate directory.
+    pub fn save(&self) -> Result<()> {
+        let content = serde_json::to_string_pretty(self)?;
+
+        write_atomically(&get_manifest_path(), &content)
+    }
+
+    /// gets the key currently used to sign tokens.
+    pub fn get_signing_key(&self) -> Option<&JWTKey> {
+        self.keys
+            .iter()
+            .filter(|key| key.retired_at.is_none())
+            .max_by_key(|key| key.created_at)
+    }
+
+    /// gets all keys that can be used to verify tokens.
+    pub fn get_verification_keys(&self) -> Vec<&JWTKey> {
+        self.keys
+            .iter()
+            .filter(|key| key.is_valid_for_verification())
+            .collect()
+    }
+
+    /// finds a key able to verify tokens by its ID.
+    pub fn find_verification_key(&self, kid: &str) -> Option<&JWTKey> {
+        self.get_verification_keys()
+            .into_iter()
+            .find(|key| key.kid == kid)
+    }
+}
+
+/// Key material of the manifest, loaded once in memory and shared by everything signing or verifying tokens.
+///
+/// Keys are reloaded when the manifest changes on disk (e.g. after a rotation made by the `app:jwt-keys-rotate`
+/// command), so only the manifest modification date is checked on each use :
+/// ```rust
+/// let key_store = JWTKeyStore::default();
+/// key_store.reload()?;
+///
+/// let token = jwt::encode(&key_store, claim, 3600, "my-api")?;
+/// ```
+#[derive(Clone, Default)]
+pub struct JWTKeyStore {
+    loaded_keys: Arc<RwLock<Option<Arc<JWTLoadedKeys>>>>,
+}
+
+impl JWTKeyStore {
+    /// creates a store holding the given keys, never reloaded from the certificate directory (e.g. for tests).
+    pub fn from_keys(keys: Vec<(JWTKey, JWTSigningKey)>) -> Self {
+        let manifest = JWTKeyManifest {
+            keys: keys.iter().map(|(key, _)| key.clone()).collect(),
+        };
+        let signing_kid = manifest.get_signing_key().map(|key| key.kid.clone());
+
+        let mut signing_key = None;
+        let mut verification_keys = Vec::<(JWTKey, JWTVerificationKey)>::new();
+
+        for (key, material) in keys.into_iter() {
+            let material = material.with_key_id(&key.kid);
+            verification_keys.push((key.clone(), material.verification_key()));
+
+            if signing_kid.as_ref() == Some(&key.kid) {
+                signing_key = Some(material);
+            }
+        }
+
+        let loaded_keys = JWTLoadedKeys {
+            manifest_modified: None,
+            signing_key,
+            verification_keys,
+        };
+
+        Self {
+            loaded_keys: Arc::new(RwLock::new(Some(Arc::new(loaded_keys)))),
+        }
+    }
+
+    /// loads the manifest and the material of its keys from the certificate directory, returning them.
+    pub fn reload(&self) -> Result<Arc<JWTLoadedKeys>> {
+        let loaded_keys = Arc::new(JWTLoadedKeys::load()?);
+
+        *self
+            .loaded_keys
+            .write()
+            .map_err(|_| anyhow!("JWT key store is poisoned"))? = Some(loaded_keys.clone());
+
+        Ok(loaded_keys)
+    }
+
+    /// gets the loaded keys, reloading them first if the manifest changed since they were loaded.
+    pub fn get(&self) -> Result<Arc<JWTLoadedKeys>> {
+        let loaded_keys = self
+            .loaded_keys
+            .read()
+            .map_err(|_| anyhow!("JWT key store is poisoned"))?
+            .clone();
+
+        if let Some(loaded_keys) = loaded_keys {
+            let is_up_to_date = match loaded_keys.manifest_modified {
+                Some(manifest_modified) => get_manifest_modified()? == manifest_modified,
+                None => true,
+            };
+
+            if is_up_to_date {
+                return Ok(loaded_keys);
+            }
+        }
+
+        self.reload()
+    }
+}
+
+/// Key material of a manifest, as loaded by a `JWTKeyStore`.
+pub struct JWTLoadedKeys {
+    /// modification date of the manifest file the keys were loaded from, `None` for keys given in memory.
+    manifest_modified: Option<SystemTime>,
+    /// material of the key currently used to sign tokens.
+    signing_key: Option<JWTSigningKey>,
+    /// every key of the manifest that could verify tokens when loaded, with its material.
+    verification_keys: Vec<(JWTKey, JWTVerificationKey)>,
+}
+
+impl JWTLoadedKeys {
+    /// PRIVATE - loads the manifest, the material of its signing key, and of every key still valid for verification.
+    fn load() -> Result<Self> {
+        let manifest_modified = get_manifest_modified()?;
+        let manifest = JWTKeyManifest::load()?;
+
+        let signing_key = manifest
+            .get_signing_key()
+            .map(JWTSigningKey::load)
+            .transpose()?;
+
+        let verification_keys = manifest
+            .get_verification_keys()
+            .into_iter()
+            .map(|key| Ok((key.clone(), JWTVerificationKey::load(key)?)))
+            .collect::<Result<Vec<(JWTKey, JWTVerificationKey)>>>()?;
+
+        Ok(Self {
+            manifest_modified: Some(manifest_modified),
+            signing_key,
+            verification_keys,
+        })
+    }
+
+    /// gets the material of the key currently used to sign tokens.
+    pub fn get_signing_key(&self) -> Option<&JWTSigningKey> {
+        self.signing_key.as_ref()
+    }
+
+    /// gets all keys that can be used to verify tokens, with their material.
+    pub fn get_verification_keys(&self) -> Vec<(&JWTKey, &JWTVerificationKey)> {
+        self.verification_keys
+            .iter()
+            .filter(|(key, _)| key.is_valid_for_verification())
+            .map(|(key, verification_key)| (key, verification_key))
+            .collect()
+    }
+
+    /// finds the material of a key able to verify tokens by its ID.
+    pub fn find_verification_key(&self, kid: &str) -> Option<&JWTVerificationKey> {
+        self.get_verification_keys()
+            .into_iter()
+            .find(|(key, _)| key.kid == kid)
+            .map(|(_, verification_key)| verification_key)
+    }
+}
+
+/// Generates a new key for the given algorithm and writes it in the certificate directory, without registering it in the manifest.
+pub fn generate_key_pair(algorithm: JWTAlgorithm) -> Result<JWTKey> {
+    let key = JWTKey {
+        kid: Ulid::new().to_string(),
+        alg: algorithm,
+        created_at: Utc::now(),
+        retired_at: None,
+        expires_at: None,
+    };
+
+    let signing_key = JWTSigningKey::generate(algorithm)?;
+
+    let private_material = signing_key
+        .to_private_material()
+        .with_context(|| "Cannot export private key")?;
+    let public_material = signing_key
+        .verification_key()
+        .to_public_material()
+        .with_context(|| "Cannot export public key")?;
+
+    if let Some(public_material) = public_material {
+        write_atomically(&get_public_key_path(&key.kid), &public_material)?;
+    }
+    write_atomically(&get_private_key_path(&key), &private_material)?;
+
+    Ok(key)
+}
+
+/// Rotates the signing key: a new key is generated for the given algorithm and becomes the signing key.
+///
+/// Previous signing keys are retired but kept for verification during `jwt_ttl` seconds,
+/// e.g. until every token they signed has expired. Keys whose verification period is over are purged.
+pub fn rotate(algorithm: JWTAlgorithm, jwt_ttl: i64) -> Result<JWTKey> {
+    let mut manifest = if JWTKeyManifest::exists() {
+        JWTKeyManifest::load()?
+    } else {
+        JWTKeyManifest::default()
+    };
+
+    let new_key = generate_key_pair(algorithm)?;
+    let now = Utc::now();
+
+    for key in manifest.keys.iter_mut() {
+        if key.retired_at.is_none() {
+            key.retired_at = Some(now);
+            key.expires_at = Some(now + Duration::seconds(jwt_ttl));
+        }
+    }
+
+    manifest.keys.push(new_key.clone());
+    manifest.save()?;
+
+    purge_expired()?;
+
+    Ok(new_key)
+}
+
+/// Registers an existing key pair written without manifest (e.g. `private.pem` and `public.pem`) as the signing key.
+pub fn adopt_legacy_key_pair() -> Result<JWTKey> {
+    let key = JWTKey {
+        kid: Ulid::new().to_string(),
+        alg: JWTAlgorithm::RS512,
+        created_at: Utc::now(),
+        retired_at: None,
+        expires_at: None,
+    };
+
+    fs::rename(get_legacy_public_key_path(), get_public_key_path(&key.kid))?;
+    fs::rename(get_legacy_private_key_path(), get_private_key_path(&key))?;
+
+    let manifest = JWTKeyManifest {
+        keys: vec![key.clone()],
+    };
+    manifest.save()?;
+
+    Ok(key)
+}
+
+/// Removes keys that can no longer verify tokens from the manifest and the certificate directory.
+///
+/// Returns the IDs of the purged keys.
+pub fn purge_expired() -> Result<Vec<String>> {
+    let mut manifest = JWTKeyManifest::load()?;
+
+    let (kept, expired): (Vec<JWTKey>, Vec<JWTKey>) = manifest
+        .keys
+        .into_iter()
+        .partition(|key| key.is_valid_for_verification());
+
+    manifest.keys = kept;
+    manifest.save()?;
+
+    let mut purged = Vec::<String>::new();
+
+    for key in expired.into_iter() {
+        fs::remove_file(get_private_key_path(&key)).ok();
+        fs::remove_file(get_public_key_path(&key.kid)).ok();
+
+        purged.push(key.kid);
+    }
+
+    Ok(purged)
+}
+
+/// Checks that the files of a key exist and can be parsed for its algorithm.
+pub fn check_key_pair(key: &JWTKey) -> Result<()> {
+    JWTSigningKey::load(key)?;
+    JWTVerificationKey::load(key)?;
+
+    Ok(())
+}
+
+/// Reads the private key (or shared secret) content of the given key.
+pub fn read_private_key(key: &JWTKey) -> Result<String> {
+    let content = fs::read_to_string(get_private_key_path(key))
+        .with_context(|| format!("Cannot find private certificate file for key {}", key.kid))?;
+
+    Ok(content)
+}
+
+/// Reads the public key content of the given key ID.
+pub fn read_public_key(kid: &str) -> Result<String> {
+    let content = fs::read_to_string(get_public_key_path(kid))
+        .with_context(|| format!("Cannot find public certificate file for key {kid}"))?;
+
+    Ok(content)
+}
+
+/// Utility function to get the private key (or shared secret) file of a given key.
+pub fn get_private_key_path(key: &JWTKey) -> PathBuf {
+    let mut path = dirs::certificate_dir();
+
+    if key.alg.is_symmetric() {
+        path.push(format!("{}.secret", key.kid));
+    } else {
+        path.push(format!("{}.private.pem", key.kid));
+    }
+
+    path
+}
+
+/// Utility function to get the public key file of a given key ID.
+pub fn get_public_key_path(kid: &str) -> PathBuf {
+    let mut path = dirs::certificate_dir();
+    path.push(format!("{kid}.public.pem"));
+
+    path
+}
+
+/// Utility function to get the private key file written by previous versions (e.g. without key ID).
+pub fn get_legacy_private_key_path() -> PathBuf {
+    let mut path = dirs::certificate_dir();
+    path.push("private.pem");
+
+    path
+}
+
+/// Utility function to get the public key file written by previous versions (e.g. without key ID).
+pub fn get_legacy_public_key_path() -> PathBuf {
+    let mut path = dirs::certificate_dir();
+    path.push("public.pem");
+
+    path
+}
+
+/// Utility function to get the modification date of the key manifest file.
+pub fn get_manifest_modified() -> Result<SystemTime> {
+    let modified = fs::metadata(get_manifest_path())
+        .and_then(|metadata| metadata.modified())
+        .with_context(|| "Cannot find JWT key manifest file")?;
+
+    Ok(modified)
+}
+
+/// Utility function to get the key manifest file.
+pub fn get_manifest_path() -> PathBuf {
+    let mut path = dirs::certificate_dir();
+    path.push(MANIFEST_FILE);
+
+    path
+}
+
+/// PRIVATE - writes a file through a temporary file, so a failure never leaves a half-written file behind.
+fn write_atomically(path: &PathBuf, content: &str) -> Result<()> {
+    let mut tmp_path = path.clone();
+    tmp_path.set_extension("tmp");
+
+    fs::write(&tmp_path, content)
+        .with_context(|| format!("Cannot write {}", tmp_path.to_string_lossy()))?;
+
+    if let Err(error) = fs::rename(&tmp_path, path) {
+        fs::remove_file(&tmp_path).ok();
+        bail!("Cannot write {}: {error}", path.to_string_lossy());
+    }
+
+    Ok(())
+}
@@ -8,40 +8,53 @@ macro_rules! http_exception {
     }};
 }
 
+/// checks the access of a principal to a right on a subject, returning early from the controller when not granted :
+///
+/// - a denied access is a 403, with the reason given by the voter ;
+/// - an access that cannot be decided (e.g. unknown right, missing context) is a misconfiguration : it is logged, and a bare 500 is returned.
 #[macro_export]
 macro_rules! deny_access_unless_granted {
-    ($security:expr, $user:expr, $subject:expr, $right:expr) => {{
+    (@decide $vote:expr) => {{
         use $crate::http_exception;
 
-        let vote = $security.has_access($subject, $right, $user, None);
-
-        if vote.is_err() {
-            let root_cause_message = format!("{}", vote.err().unwrap().root_cause());
+        match $vote {
+            Ok($crate::core::security::AccessDecision::Granted) => {}
+            Ok($crate::core::security::AccessDecision::Denied(reason)) => {
+                http_exception!(
+                    Status::Forbidden,
+                    reason.as_deref().unwrap_or("Access denied.")
+                );
+            }
+            Err(error) => {
+                ::log::error!(target: "security", "Cannot decide access : {error}");
 
-            http_exception!(Status::Unauthorized, &root_cause_message);
-        } else {
-            if let Ok(voted) = vote {
-                if !voted {
-                    http_exception!(Status::Unauthorized, "Unauthorized");
-                }
+                http_exception!(Status::InternalServerError);
             }
         }
     }};
+    ($security:expr, $user:expr, $subject:expr, $right:expr) => {{
+        $crate::deny_access_unless_granted!(@decide $security.has_access($subject, $right, $user, None))
+    }};
     ($security:expr, $user:expr, $subject:expr, $right:expr, $context:expr) => {{
-        use $crate::http_exception;
-
-        let vote = $security.has_access($subject, $right, $user, Some($context));
+        $crate::deny_access_unless_granted!(
+            @decide $security.has_access($subject, $right, $user, Some($context))
+        )
+    }};
+}
 
-        if vote.is_err() {
-            let root_cause_message = format!("{}", vote.err().unwrap().root_cause());
+/// refuses sensitive operations (e.g. password change, user deletion) to impersonated sessions, returning early with a 403.
+///
+/// It takes any guard having an `is_impersonated()` method, such as `ConnectedUser` or `Granted`.
+#[macro_export]
+macro_rules! deny_access_if_impersonated {
+    ($guard:expr) => {{
+        use $crate::http_exception;
 
-            http_exception!(Status::Unauthorized, &root_cause_message);
-        } else {
-            if let Ok(voted) = vote {
-                if !voted {
-                    http_exception!(Status::Unauthorized, "Unauthorized");
-                }
-            }
+        if $guard.is_impersonated() {
+            http_exception!(
+                Status::Forbidden,
+                "This operation is not allowed while impersonating a user."
+            );
         }
     }};
 }
@@ -53,6 +66,14 @@ macro_rules! http_ok {
     }};
 }
 
+/// returns a page of a listing (e.g. `Paginated<T>`) with its `Link` and `X-Total-Count` headers.
+#[macro_export]
+macro_rules! http_paginated {
+    ($output:expr) => {{
+        return Ok(ApiResponse::paginated($output));
+    }};
+}
+
 #[macro_export]
 macro_rules! http_no_content {
     () => {{
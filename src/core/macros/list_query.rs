@@ -0,0 +1,62 @@
+/// applies a filter of a `ListQuery` on a column of a boxed Diesel query, returning the filtered query :
+/// ```rust
+/// query = match filter.field.as_str() {
+///     "login" => filter_by!(query, login, filter),
+///     "created_date" => filter_by!(query, created_date, filter, DateTime<Utc>),
+///     _ => bail!("Unknown filter field {}", filter.field),
+/// };
+/// ```
+///
+/// Text columns take the value as is, other columns give the type to parse it to, within a function returning an `anyhow::Result`.
+#[macro_export]
+macro_rules! filter_by {
+    ($query:expr, $column:expr, $filter:expr) => {{
+        use $crate::core::guards::list_query::FilterOperator;
+
+        let value = $filter.value.clone();
+
+        match $filter.operator {
+            FilterOperator::Eq => $query.filter($column.eq(value)),
+            FilterOperator::Ne => $query.filter($column.ne(value)),
+            FilterOperator::Lt => $query.filter($column.lt(value)),
+            FilterOperator::Lte => $query.filter($column.le(value)),
+            FilterOperator::Gt => $query.filter($column.gt(value)),
+            FilterOperator::Gte => $query.filter($column.ge(value)),
+            FilterOperator::Like => $query.filter($column.ilike($filter.get_like_pattern())),
+        }
+    }};
+    ($query:expr, $column:expr, $filter:expr, $type:ty) => {{
+        use $crate::core::guards::list_query::FilterOperator;
+
+        let value = $filter.value.parse::<$type>()?;
+
+        match $filter.operator {
+            FilterOperator::Eq => $query.filter($column.eq(value)),
+            FilterOperator::Ne => $query.filter($column.ne(value)),
+            FilterOperator::Lt => $query.filter($column.lt(value)),
+            FilterOperator::Lte => $query.filter($column.le(value)),
+            FilterOperator::Gt => $query.filter($column.gt(value)),
+            FilterOperator::Gte => $query.filter($column.ge(value)),
+            // the ListQuery guard only accepts like on text fields.
+            FilterOperator::Like => ::anyhow::bail!("Cannot filter {} with like", $filter.field),
+        }
+    }};
+}
+
+/// applies a sort of a `ListQuery` on a column of a boxed Diesel query, returning the sorted query :
+/// ```rust
+/// query = match sort.field.as_str() {
+///     "login" => sort_by!(query, login, sort),
+///     _ => bail!("Unknown sort field {}", sort.field),
+/// };
+/// ```
+#[macro_export]
+macro_rules! sort_by {
+    ($query:expr, $column:expr, $sort:expr) => {{
+        if $sort.descending {
+            $query.then_order_by($column.desc())
+        } else {
+            $query.then_order_by($column.asc())
+        }
+    }};
+}
@@ -2,3 +2,5 @@ pub mod command;
 pub mod crash;
 pub mod error;
 pub mod http;
+pub mod list_query;
+pub mod security;
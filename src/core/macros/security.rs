@@ -0,0 +1,33 @@
+/// declares a security subject type, to use with the `Granted` guard :
+/// ```rust
+/// security_subject!(BookSubject, "book");
+/// ```
+#[macro_export]
+macro_rules! security_subject {
+    ($name:ident, $subject:expr) => {
+        #[doc = concat!("The `", $subject, "` security subject.")]
+        #[derive(Debug, Clone)]
+        pub struct $name;
+
+        impl $crate::core::guards::granted::SecuritySubject for $name {
+            const NAME: &'static str = $subject;
+        }
+    };
+}
+
+/// declares a security right type, to use with the `Granted` guard :
+/// ```rust
+/// security_right!(BorrowRight, "borrow");
+/// ```
+#[macro_export]
+macro_rules! security_right {
+    ($name:ident, $right:expr) => {
+        #[doc = concat!("The `", $right, "` security right.")]
+        #[derive(Debug, Clone)]
+        pub struct $name;
+
+        impl $crate::core::guards::granted::SecurityRight for $name {
+            const NAME: &'static str = $right;
+        }
+    };
+}
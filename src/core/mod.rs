@@ -1,16 +1,27 @@
 pub mod catcher;
 pub mod commands;
 pub mod configuration;
+pub mod cursor;
 pub mod database;
 pub mod dirs;
 pub mod fairings;
 pub mod guards;
 pub mod jwt;
+pub mod jwt_keys;
 pub mod launcher;
 pub mod macros;
+pub mod notifier;
+pub mod openapi;
 pub mod password;
 pub mod password_generator;
 pub mod response;
 pub mod rocket_factory;
+pub mod role_hierarchy;
+pub mod secret_cipher;
 pub mod security;
+pub mod security_audit;
+pub mod security_policy;
+#[cfg(test)]
+pub mod testing;
+pub mod totp;
 pub mod validation;
@@ -0,0 +1,91 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+
+use crate::domain::model::user::User;
+
+use super::commands::console_command_utils::ConsoleIO;
+
+/// A trait to deliver out-of-band messages (e.g. password reset tokens) to users.
+///
+/// Implement it to plug a mailer, a queue or any other transport, then give it to the middlewares
+/// in the rocket factory.
+pub trait Notifier: Send + Sync {
+    /// sends a password reset token to the given user.
+    fn notify_password_reset(
+        &self,
+        user: &User,
+        token: &str,
+        expiration_date: DateTime<Utc>,
+    ) -> Result<()>;
+}
+
+/// A notifier writing messages on the console, for development purposes.
+///
+/// Tokens are only displayed on debug builds, they are never written on release builds.
+#[derive(Default)]
+pub struct ConsoleNotifier {}
+
+impl Notifier for ConsoleNotifier {
+    fn notify_password_reset(
+        &self,
+        user: &User,
+        token: &str,
+        expiration_date: DateTime<Utc>,
+    ) -> Result<()> {
+        let io = ConsoleIO::new();
+
+        if cfg!(debug_assertions) {
+            io.info(&format!(
+                "Password reset token for user {} (valid until {}) : {token}",
+                user.login,
+                expiration_date.to_rfc3339()
+            ));
+        } else {
+            io.warning(&format!(
+                "A password reset has been requested for user {}, but no notifier is configured to deliver it.",
+                user.login
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// A notifier keeping the sent tokens in memory, so tests can capture them without a mail server.
+#[cfg(test)]
+#[derive(Default)]
+pub struct CapturingNotifier {
+    /// password reset tokens, along with the ID of the user they were sent to.
+    password_reset_tokens: std::sync::Mutex<Vec<(i32, String)>>,
+}
+
+#[cfg(test)]
+impl CapturingNotifier {
+    /// gets the last password reset token sent to a user.
+    pub fn get_password_reset_token(&self, user: &User) -> Option<String> {
+        self.password_reset_tokens
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .find(|(user_id, _)| *user_id == user.id)
+            .map(|(_, token)| token.clone())
+    }
+}
+
+#[cfg(test)]
+impl Notifier for CapturingNotifier {
+    fn notify_password_reset(
+        &self,
+        user: &User,
+        token: &str,
+        _expiration_date: DateTime<Utc>,
+    ) -> Result<()> {
+        self.password_reset_tokens
+            .lock()
+            .unwrap()
+            .push((user.id, token.into()));
+
+        Ok(())
+    }
+}
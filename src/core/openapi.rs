@@ -0,0 +1,621 @@
+use std::collections::{BTreeMap, HashMap};
+
+use rocket::{http::Method, Route};
+use schemars::{
+    generate::{SchemaGenerator, SchemaSettings},
+    JsonSchema, Schema,
+};
+use serde_json::{json, Map, Value};
+
+use crate::{
+    core::{
+        guards::{
+            connected_application::API_KEY_HEADER,
+            list_query::{FieldKind, FilterOperator, ListFields},
+        },
+        response::{CursorPaginated, Paginated, LINK_HEADER, TOTAL_COUNT_HEADER},
+    },
+    exceptions::dto::http_exception::HttpException,
+};
+
+/// OpenAPI version of the generated documents.
+pub const OPENAPI_VERSION: &str = "3.1.0";
+
+/// Name of the security scheme used by operations requiring a JWT.
+const BEARER_SECURITY_SCHEME: &str = "bearer";
+
+/// Name of the security scheme used by operations accepting an API key.
+const API_KEY_SECURITY_SCHEME: &str = "api_key";
+
+/// A function adding the schema of a type to a generator, returning a reference to it.
+type SchemaFn = fn(&mut SchemaGenerator) -> Schema;
+
+/// Documentation of an operation, completing what the mounted route tells (e.g. method, path and its parameters).
+#[derive(Clone, Default)]
+pub struct OpenApiOperation {
+    summary: &'static str,
+    request_body: Option<SchemaFn>,
+    /// whether the request body is sent as `application/x-www-form-urlencoded` rather than JSON.
+    is_form_input: bool,
+    /// schemas of a successful response, one of them being returned (e.g. depending on the pagination mode).
+    responses: Vec<SchemaFn>,
+    /// schema of an error response, `HttpException` if not set.
+    error_response: Option<SchemaFn>,
+    is_authenticated: bool,
+    is_api_key_accepted: bool,
+    is_paginated: bool,
+    is_cursor_paginated: bool,
+    /// fields of the `ListQuery` guard, which can be filtered and sorted.
+    filterable: &'static [(&'static str, FieldKind)],
+    sortable: &'static [&'static str],
+}
+
+impl OpenApiOperation {
+    /// constructor.
+    pub fn new(summary: &'static str) -> Self {
+        Self {
+            summary,
+            ..Default::default()
+        }
+    }
+
+    /// sets the JSON body of the request, whose schema includes its `validator` constraints.
+    pub fn input<T: JsonSchema>(mut self) -> Self {
+        self.request_body = Some(SchemaGenerator::subschema_for::<T>);
+
+        self
+    }
+
+    /// sets the `application/x-www-form-urlencoded` body of the request (e.g. read with a `Form` guard).
+    pub fn form_input<T: JsonSchema>(mut self) -> Self {
+        self.request_body = Some(SchemaGenerator::subschema_for::<T>);
+        self.is_form_input = true;
+
+        self
+    }
+
+    /// sets the JSON body of a successful response. Operations without output answer `204 No Content`.
+    pub fn output<T: JsonSchema>(mut self) -> Self {
+        self.responses.push(SchemaGenerator::subschema_for::<T>);
+
+        self
+    }
+
+    /// sets the JSON body of an error response, for operations not answering an `HttpException` (e.g. OAuth2 endpoints).
+    pub fn error<T: JsonSchema>(mut self) -> Self {
+        self.error_response = Some(SchemaGenerator::subschema_for::<T>);
+
+        self
+    }
+
+    /// marks the operation as requiring a JWT bearer token.
+    pub fn authenticated(mut self) -> Self {
+        self.is_authenticated = true;
+
+        self
+    }
+
+    /// marks the operation as also accepting an API key, in the `X-Api-Key` header.
+    pub fn api_key(mut self) -> Self {
+        self.is_api_key_accepted = true;
+
+        self
+    }
+
+    /// adds the `page` and `per_page` query parameters of the `Pagination` guard, answered with a `Paginated` page of items.
+    pub fn paginated<T: JsonSchema>(mut self) -> Self {
+        self.is_paginated = true;
+
+        self.output::<Paginated<T>>()
+    }
+
+    /// adds the `cursor` and `limit` query parameters of the `CursorPagination` guard, answered with a `CursorPaginated` page of items.
+    pub fn cursor_paginated<T: JsonSchema>(mut self) -> Self {
+        self.is_cursor_paginated = true;
+
+        self.output::<CursorPaginated<T>>()
+    }
+
+    /// adds the `filter[...]` and `sort` query parameters of the `ListQuery` guard, for the whitelisted fields of a DTO.
+    pub fn list_query<D: ListFields>(mut self) -> Self {
+        self.filterable = D::FILTERABLE;
+        self.sortable = D::SORTABLE;
+
+        self
+    }
+}
+
+/// A registry of operation documentations, generating an OpenAPI 3.1 document from mounted routes.
+///
+/// Operations are documented next to their handlers, whose method and path are read from the route declaration.
+/// Every mounted route is listed, documented or not :
+/// ```rust
+/// let mut openapi = OpenApi::new("my-api", "1.0.0");
+/// openapi.document(
+///     "/api",
+///     routes![user::user_list],
+///     OpenApiOperation::new("List users").paginated::<UserListItemDTO>().authenticated(),
+/// );
+///
+/// let document = openapi.generate(rocket.routes());
+/// ```
+#[derive(Clone)]
+pub struct OpenApi {
+    title: String,
+    version: String,
+    /// operations by method and full path (e.g. `/api/users/<id>`).
+    operations: HashMap<(Method, String), OpenApiOperation>,
+}
+
+impl OpenApi {
+    /// constructor.
+    pub fn new(title: &str, version: &str) -> Self {
+        Self {
+            title: title.into(),
+            version: version.into(),
+            operations: HashMap::new(),
+        }
+    }
+
+    /// documents the operation of routes mounted on `base`, e.g. ranked handlers of a same listing.
+    ///
+    /// Panics if `base` is not a valid URI, as `Rocket::mount` does.
+    pub fn document(
+        &mut self,
+        base: &str,
+        routes: Vec<Route>,
+        operation: OpenApiOperation,
+    ) -> &mut Self {
+        for route in routes {
+            let route = route
+                .map_base(|old| format!("{base}{old}"))
+                .unwrap_or_else(|error| panic!("Invalid OpenAPI base '{base}' : {error}"));
+
+            self.operations.insert(
+                (route.method, route.uri.path().to_string()),
+                operation.clone(),
+            );
+        }
+
+        self
+    }
+
+    /// tells whether the operation of a route is documented, given its method and full path.
+    pub fn is_documented(&self, method: Method, path: &str) -> bool {
+        self.operations
+            .keys()
+            .any(|(documented_method, documented_path)| {
+                *documented_method == method && *documented_path == path
+            })
+    }
+
+    /// generates the OpenAPI document of the given routes.
+    pub fn generate<'r>(&self, routes: impl IntoIterator<Item = &'r Route>) -> Value {
+        let mut generator = SchemaSettings::draft2020_12()
+            .with(|settings| settings.definitions_path = "/components/schemas".into())
+            .into_generator();
+
+        let error_schema = generator.subschema_for::<HttpException>();
+
+        let mut paths = BTreeMap::<String, Map<String, Value>>::new();
+
+        for route in routes {
+            let operation = self
+                .operations
+                .get(&(route.method, route.uri.path().to_string()))
+                .cloned()
+                .unwrap_or_default();
+
+            let path_item = paths.entry(to_openapi_path(route.uri.path())).or_default();
+            let method = route.method.as_str().to_lowercase();
+
+            // ranked routes of a same operation (e.g. both pagination modes of a listing) are documented once.
+            if path_item.contains_key(&method) {
+                continue;
+            }
+
+            path_item.insert(
+                method,
+                Self::generate_operation(route, &operation, &mut generator, &error_schema),
+            );
+        }
+
+        json!({
+            "openapi": OPENAPI_VERSION,
+            "info": {
+                "title": self.title,
+                "version": self.version,
+            },
+            "paths": paths,
+            "components": {
+                "schemas": generator.take_definitions(true),
+                "securitySchemes": {
+                    BEARER_SECURITY_SCHEME: {
+                        "type": "http",
+                        "scheme": "bearer",
+                        "bearerFormat": "JWT",
+                    },
+                    API_KEY_SECURITY_SCHEME: {
+                        "type": "apiKey",
+                        "in": "header",
+                        "name": API_KEY_HEADER,
+                    },
+                },
+            },
+        })
+    }
+
+    /// PRIVATE - generates the operation object of a route.
+    fn generate_operation(
+        route: &Route,
+        operation: &OpenApiOperation,
+        generator: &mut SchemaGenerator,
+        error_schema: &Schema,
+    ) -> Value {
+        let mut parameters = get_path_parameters(route.uri.path())
+            .into_iter()
+            .map(|name| {
+                json!({
+                    "name": name,
+                    "in": "path",
+                    "required": true,
+                    "schema": { "type": "string" },
+                })
+            })
+            .collect::<Vec<Value>>();
+
+        if operation.is_paginated {
+            for name in ["page", "per_page"] {
+                parameters.push(json!({
+                    "name": name,
+                    "in": "query",
+                    "required": false,
+                    "schema": { "type": "integer", "minimum": 1 },
+                }));
+            }
+        }
+
+        if operation.is_cursor_paginated {
+            parameters.push(json!({
+                "name": "cursor",
+                "in": "query",
+                "required": false,
+                "description": "The `next_cursor` of the previous page.",
+                "schema": { "type": "string" },
+            }));
+            parameters.push(json!({
+                "name": "limit",
+                "in": "query",
+                "required": false,
+                "schema": { "type": "integer", "minimum": 1 },
+            }));
+        }
+
+        let operators = FilterOperator::ALL
+            .iter()
+            .map(|(name, _)| *name)
+            .collect::<Vec<&str>>()
+            .join(", ");
+
+        for (field, kind) in operation.filterable {
+            let schema = match kind {
+                FieldKind::Text => json!({ "type": "string" }),
+                FieldKind::Integer => json!({ "type": "integer" }),
+                FieldKind::Date => json!({ "type": "string", "format": "date-time" }),
+            };
+
+            parameters.push(json!({
+                "name": format!("filter[{field}]"),
+                "in": "query",
+                "required": false,
+                "description": format!(
+                    "Filters on `{field}` (equal to), or with an operator such as `filter[{field}][gte]` ({operators})."
+                ),
+                "schema": schema,
+            }));
+        }
+
+        if !operation.sortable.is_empty() {
+            parameters.push(json!({
+                "name": "sort",
+                "in": "query",
+                "required": false,
+                "description": format!(
+                    "Comma-separated fields to sort by, descending when prefixed by `-` (e.g. `-created_date`), among : {}.",
+                    operation.sortable.join(", ")
+                ),
+                "schema": { "type": "string" },
+            }));
+        }
+
+        let mut responses = Map::new();
+
+        let mut schemas = operation
+            .responses
+            .iter()
+            .map(|response| response(generator))
+            .collect::<Vec<Schema>>();
+
+        let schema = match schemas.len() {
+            0 => None,
+            1 => schemas.pop().map(|schema| json!(schema)),
+            _ => Some(json!({ "oneOf": schemas })),
+        };
+
+        match (schema, operation.summary.is_empty()) {
+            (Some(schema), _) => {
+                let mut success = json!({
+                    "description": "Success",
+                    "content": { "application/json": { "schema": schema } },
+                });
+
+                let mut headers = Map::new();
+
+                if operation.is_paginated || operation.is_cursor_paginated {
+                    headers.insert(
+                        LINK_HEADER.into(),
+                        json!({
+                            "description": "Links to other pages (RFC 8288).",
+                            "schema": { "type": "string" },
+                        }),
+                    );
+                }
+
+                if operation.is_paginated {
+                    headers.insert(
+                        TOTAL_COUNT_HEADER.into(),
+                        json!({
+                            "description": "Total number of items.",
+                            "schema": { "type": "integer" },
+                        }),
+                    );
+                }
+
+                if !headers.is_empty() {
+                    success["headers"] = Value::Object(headers);
+                }
+
+                responses.insert("200".into(), success);
+            }
+            // undocumented routes may have any output.
+            (None, true) => {
+                responses.insert("200".into(), json!({ "description": "Success" }));
+            }
+            (None, false) => {
+                responses.insert("204".into(), json!({ "description": "No content" }));
+            }
+        }
+
+        let error_schema = match operation.error_response {
+            Some(error_response) => error_response(generator),
+            None => error_schema.clone(),
+        };
+
+        // error statuses follow from the declaration of the operation, e.g. a validated body answers `400` when invalid.
+        let mut errors = Vec::<(&str, &str)>::new();
+
+        if operation.request_body.is_some()
+            || parameters
+                .iter()
+                .any(|parameter| parameter["in"] == "query")
+        {
+            errors.push(("400", "Invalid input"));
+        }
+
+        if operation.is_authenticated || operation.is_api_key_accepted {
+            errors.push(("401", "Missing or invalid credentials"));
+        }
+
+        if operation.is_authenticated {
+            errors.push(("403", "Access denied"));
+        }
+
+        if !get_path_parameters(route.uri.path()).is_empty() {
+            errors.push(("404", "Not found"));
+        }
+
+        errors.push(("default", "Error"));
+
+        for (status, description) in errors {
+            responses.insert(
+                status.into(),
+                json!({
+                    "description": description,
+                    "content": { "application/json": { "schema": error_schema } },
+                }),
+            );
+        }
+
+        let mut operation_object = Map::new();
+
+        if let Some(name) = &route.name {
+            operation_object.insert("operationId".into(), json!(name));
+        }
+
+        if let Some(tag) = get_tag(route.uri.path()) {
+            operation_object.insert("tags".into(), json!([tag]));
+        }
+
+        if !operation.summary.is_empty() {
+            operation_object.insert("summary".into(), json!(operation.summary));
+        }
+
+        if !parameters.is_empty() {
+            operation_object.insert("parameters".into(), json!(parameters));
+        }
+
+        if let Some(request_body) = operation.request_body {
+            let content_type = if operation.is_form_input {
+                "application/x-www-form-urlencoded"
+            } else {
+                "application/json"
+            };
+
+            operation_object.insert(
+                "requestBody".into(),
+                json!({
+                    "required": true,
+                    "content": { content_type: { "schema": request_body(generator) } },
+                }),
+            );
+        }
+
+        operation_object.insert("responses".into(), Value::Object(responses));
+
+        let mut security = Vec::<Value>::new();
+
+        if operation.is_authenticated {
+            security.push(json!({ BEARER_SECURITY_SCHEME: [] }));
+        }
+
+        if operation.is_api_key_accepted {
+            security.push(json!({ API_KEY_SECURITY_SCHEME: [] }));
+        }
+
+        if !security.is_empty() {
+            operation_object.insert("security".into(), json!(security));
+        }
+
+        Value::Object(operation_object)
+    }
+}
+
+/// The OpenAPI document of the API, generated once routes are mounted.
+#[derive(Clone)]
+pub struct OpenApiDocument(pub Value);
+
+/// converts a route path (e.g. `/users/<id>` or `/files/<path..>`) to an OpenAPI path (e.g. `/users/{id}`).
+fn to_openapi_path(path: &str) -> String {
+    path.split('/')
+        .map(|segment| match get_parameter_name(segment) {
+            Some(name) => format!("{{{name}}}"),
+            None => segment.to_string(),
+        })
+        .collect::<Vec<String>>()
+        .join("/")
+}
+
+/// gets the names of the dynamic segments of a route path.
+fn get_path_parameters(path: &str) -> Vec<&str> {
+    path.split('/').filter_map(get_parameter_name).collect()
+}
+
+/// gets the parameter name of a dynamic segment (e.g. `<id>`), if it is one.
+fn get_parameter_name(segment: &str) -> Option<&str> {
+    segment
+        .strip_prefix('<')
+        .and_then(|segment| segment.strip_suffix('>'))
+        .map(|name| name.trim_end_matches(".."))
+}
+
+/// gets the tag of a route, e.g. the first static segment after the `/api` prefix (`users` for `/api/users/<id>`).
+fn get_tag(path: &str) -> Option<&str> {
+    path.trim_start_matches('/')
+        .trim_start_matches("api")
+        .split('/')
+        .find(|segment| !segment.is_empty() && get_parameter_name(segment).is_none())
+}
+
+#[cfg(test)]
+// route macros re-export a URI macro, unused by tests.
+#[allow(unused_imports)]
+mod tests {
+    use super::*;
+    use crate::domain::dto::{
+        oauth::{OAuthErrorOutputDTO, OAuthTokenInputDTO, OAuthTokenOutputDTO},
+        user::{NewUserInputDTO, UserDetailsDTO},
+    };
+
+    #[post("/users/<_id>/roles")]
+    fn handler(_id: i32) -> &'static str {
+        "roles"
+    }
+
+    #[post("/token")]
+    fn form_handler() -> &'static str {
+        "token"
+    }
+
+    #[test]
+    fn test_generate_documents_routes_and_validation_constraints() {
+        let routes = routes![handler]
+            .into_iter()
+            .map(|route| route.map_base(|base| format!("/api{base}")).unwrap())
+            .collect::<Vec<Route>>();
+
+        let mut openapi = OpenApi::new("test", "1.0.0");
+        openapi.document(
+            "/api",
+            routes![handler],
+            OpenApiOperation::new("Test")
+                .input::<NewUserInputDTO>()
+                .output::<UserDetailsDTO>()
+                .authenticated(),
+        );
+
+        let document = openapi.generate(routes.iter());
+        let operation = &document["paths"]["/api/users/{_id}/roles"]["post"];
+
+        assert_eq!(document["openapi"], OPENAPI_VERSION);
+        assert_eq!(operation["operationId"], "handler");
+        assert_eq!(operation["tags"][0], "users");
+        assert_eq!(operation["parameters"][0]["name"], "_id");
+        assert_eq!(
+            operation["requestBody"]["content"]["application/json"]["schema"]["$ref"],
+            "#/components/schemas/NewUserInputDTO"
+        );
+        assert_eq!(
+            document["components"]["schemas"]["NewUserInputDTO"]["properties"]["login"]
+                ["maxLength"],
+            180
+        );
+        assert_eq!(
+            document["components"]["schemas"]["NewUserInputDTO"]["properties"]["email"]["format"],
+            "email"
+        );
+        assert!(document["components"]["schemas"]["HttpException"].is_object());
+
+        for status in ["400", "401", "403", "404", "default"] {
+            assert_eq!(
+                operation["responses"][status]["content"]["application/json"]["schema"]["$ref"],
+                "#/components/schemas/HttpException"
+            );
+        }
+    }
+
+    #[test]
+    fn test_generate_documents_form_bodies_and_error_schemas() {
+        let routes = routes![form_handler]
+            .into_iter()
+            .map(|route| route.map_base(|base| format!("/oauth{base}")).unwrap())
+            .collect::<Vec<Route>>();
+
+        let mut openapi = OpenApi::new("test", "1.0.0");
+        openapi.document(
+            "/oauth",
+            routes![form_handler],
+            OpenApiOperation::new("Test")
+                .form_input::<OAuthTokenInputDTO>()
+                .output::<OAuthTokenOutputDTO>()
+                .error::<OAuthErrorOutputDTO>(),
+        );
+
+        let document = openapi.generate(routes.iter());
+        let operation = &document["paths"]["/oauth/token"]["post"];
+
+        assert!(openapi.is_documented(Method::Post, "/oauth/token"));
+        assert!(!openapi.is_documented(Method::Get, "/oauth/token"));
+        assert_eq!(
+            operation["requestBody"]["content"]["application/x-www-form-urlencoded"]["schema"]
+                ["$ref"],
+            "#/components/schemas/OAuthTokenInputDTO"
+        );
+        assert_eq!(
+            operation["responses"]["default"]["content"]["application/json"]["schema"]["$ref"],
+            "#/components/schemas/OAuthErrorOutputDTO"
+        );
+        assert!(operation["responses"]["400"].is_object());
+        assert!(operation["responses"]["401"].is_null());
+        assert!(operation["responses"]["404"].is_null());
+    }
+}
@@ -1,7 +1,11 @@
-use argon2::{password_hash::SaltString, Argon2, PasswordHash, PasswordHasher, PasswordVerifier};
+use argon2::{
+    password_hash::SaltString, Algorithm, Argon2, Params, PasswordHash, PasswordHasher,
+    PasswordVerifier, Version,
+};
 use rand_core::OsRng;
+use sha2::{Digest, Sha256};
 
-use super::password_generator;
+use super::{configuration::ConfigState, password_generator};
 
 use fancy_regex::Regex;
 
@@ -27,14 +31,61 @@ pub fn generate_salt() -> SaltString {
     SaltString::generate(&mut OsRng)
 }
 
-/// hashes a password.
+/// hashes a password, with argon2 default parameters.
 pub fn hash(clear_password: &str, salt: SaltString) -> String {
-    Argon2::default()
+    hash_with_params(clear_password, salt, &Params::default())
+}
+
+/// hashes a password with the given argon2 parameters.
+pub fn hash_with_params(clear_password: &str, salt: SaltString, params: &Params) -> String {
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params.clone())
         .hash_password(clear_password.as_bytes(), &salt)
         .unwrap()
         .to_string()
 }
 
+/// Gets argon2 parameters from configuration (e.g. `APP_PASSWORD_HASH_MEMORY_COST`, `APP_PASSWORD_HASH_TIME_COST`
+/// and `APP_PASSWORD_HASH_PARALLELISM`), falling back on argon2 defaults.
+pub fn get_hash_params(configuration: &ConfigState) -> Params {
+    let get_cost = |key: &str, default: u32| {
+        u32::try_from(configuration.get_int_or_default(key, default.into())).unwrap_or(default)
+    };
+
+    Params::new(
+        get_cost("password_hash_memory_cost", Params::DEFAULT_M_COST),
+        get_cost("password_hash_time_cost", Params::DEFAULT_T_COST),
+        get_cost("password_hash_parallelism", Params::DEFAULT_P_COST),
+        None,
+    )
+    .unwrap_or_default()
+}
+
+/// Checks if a hashed password has been hashed with another algorithm or other parameters than the given ones.
+pub fn needs_rehash(hashed_password: &str, params: &Params) -> bool {
+    let parsed_hash = PasswordHash::new(hashed_password);
+
+    if parsed_hash.is_err() {
+        return true;
+    }
+
+    let parsed_hash = parsed_hash.unwrap();
+
+    if parsed_hash.algorithm != Algorithm::Argon2id.ident()
+        || parsed_hash.version != Some(Version::V0x13.into())
+    {
+        return true;
+    }
+
+    match Params::try_from(&parsed_hash) {
+        Ok(hash_params) => {
+            hash_params.m_cost() != params.m_cost()
+                || hash_params.t_cost() != params.t_cost()
+                || hash_params.p_cost() != params.p_cost()
+        }
+        Err(_) => true,
+    }
+}
+
 /// Compares a clean password (from user input) to a hashed password.
 pub fn compare_hashed(clear_password: &str, hashed_password: &str) -> bool {
     let parsed_hash = PasswordHash::new(hashed_password).unwrap();
@@ -44,6 +95,13 @@ pub fn compare_hashed(clear_password: &str, hashed_password: &str) -> bool {
         .is_ok()
 }
 
+/// Hashes a random token (e.g. a password reset token) as an hexadecimal SHA-256 digest.
+///
+/// Unlike passwords, tokens carry enough entropy to be hashed without salt, so they can be looked up by hash.
+pub fn hash_token(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
+}
+
 /// Validates that a password is a "strong" password.
 pub fn is_strong(password: String) -> bool {
     let re =
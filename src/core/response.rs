@@ -1,12 +1,24 @@
-use rocket::http::{ContentType, Status};
+use chrono::{DateTime, Utc};
+use rocket::http::{ContentType, Header, Status};
 use rocket::request::Request;
 use rocket::response;
 use rocket::response::{Responder, Response};
 use rocket::serde::json::Json;
+use schemars::JsonSchema;
 use serde::Serialize;
 
+use crate::core::guards::{cursor_pagination::CursorPagination, pagination::Pagination};
 use crate::exceptions::dto::http_exception::HttpException;
 
+/// header telling the client how to authenticate, on 401 responses.
+pub const WWW_AUTHENTICATE_HEADER: &str = "WWW-Authenticate";
+
+/// header linking the other pages of a listing (RFC 8288).
+pub const LINK_HEADER: &str = "Link";
+
+/// header giving the total number of items of a listing.
+pub const TOTAL_COUNT_HEADER: &str = "X-Total-Count";
+
 /// Generic struct to represent an JSON HTTP response transport (e.g. representation in rocket processes).
 #[derive(Debug)]
 pub struct ApiResponse<T> {
@@ -14,6 +26,8 @@ pub struct ApiResponse<T> {
     pub json: Json<T>,
     /// HTTP status of the response
     pub status: Status,
+    /// additional headers of the response.
+    pub headers: Vec<Header<'static>>,
 }
 
 impl<T> ApiResponse<T> {
@@ -22,6 +36,7 @@ impl<T> ApiResponse<T> {
         ApiResponse {
             json: output,
             status: Status::Ok,
+            headers: Vec::new(),
         }
     }
 
@@ -30,8 +45,16 @@ impl<T> ApiResponse<T> {
         ApiResponse {
             json: output,
             status,
+            headers: Vec::new(),
         }
     }
+
+    /// Adds a header to the response (e.g. `Retry-After`).
+    pub fn with_header(mut self, header: Header<'static>) -> Self {
+        self.headers.push(header);
+
+        self
+    }
 }
 
 /// Implementation of the ApiResponse transport for HttpException responses.
@@ -42,6 +65,7 @@ impl ApiResponse<HttpException> {
         ApiResponse {
             json: Json(HttpException::from_status(status)),
             status,
+            headers: Vec::new(),
         }
     }
 
@@ -53,6 +77,7 @@ impl ApiResponse<HttpException> {
                 Some(reason.into()),
             )),
             status,
+            headers: Vec::new(),
         }
     }
 }
@@ -68,6 +93,157 @@ impl ApiResponse<NoContentResponse> {
         ApiResponse {
             json: Json(NoContentResponse {}),
             status: Status::NoContent,
+            headers: Vec::new(),
+        }
+    }
+}
+
+/// A page of a listing, with the total number of items.
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct Paginated<T> {
+    pub items: Vec<T>,
+    pub page: i64,
+    pub per_page: i64,
+    pub total: i64,
+    pub total_pages: i64,
+    /// links to the first, previous, next and last pages, sent in the `Link` header.
+    #[serde(skip)]
+    #[schemars(skip)]
+    links: Vec<(&'static str, String)>,
+}
+
+impl<T> Paginated<T> {
+    /// constructor, linking the other pages of the listing.
+    pub fn new(items: Vec<T>, pagination: &Pagination, total: i64) -> Self {
+        let page = i64::from(pagination.page);
+        let per_page = i64::from(pagination.per_page);
+        let total_pages = (total + per_page - 1) / per_page;
+
+        // an empty listing still has a first (and last) page.
+        let last_page = total_pages.max(1);
+
+        let mut links = vec![("first", pagination.get_page_uri(1))];
+
+        if page > 1 {
+            links.push(("prev", pagination.get_page_uri((page - 1).min(last_page))));
+        }
+
+        if page < last_page {
+            links.push(("next", pagination.get_page_uri(page + 1)));
+        }
+
+        links.push(("last", pagination.get_page_uri(last_page)));
+
+        Self {
+            items,
+            page,
+            per_page,
+            total,
+            total_pages,
+            links,
+        }
+    }
+
+    /// maps the items of the page (e.g. to their DTO).
+    pub fn map<U>(self, f: impl FnOnce(Vec<T>) -> Vec<U>) -> Paginated<U> {
+        Paginated {
+            items: f(self.items),
+            page: self.page,
+            per_page: self.per_page,
+            total: self.total,
+            total_pages: self.total_pages,
+            links: self.links,
+        }
+    }
+
+    /// gets the `Link` header value, e.g. `</api/users?page=2&per_page=25>; rel="next", ...`.
+    pub fn get_link_header(&self) -> String {
+        self.links
+            .iter()
+            .map(|(rel, uri)| format!("<{uri}>; rel=\"{rel}\""))
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+}
+
+impl<T> Page for Paginated<T> {
+    fn get_headers(&self) -> Vec<Header<'static>> {
+        vec![
+            Header::new(LINK_HEADER, self.get_link_header()),
+            Header::new(TOTAL_COUNT_HEADER, self.total.to_string()),
+        ]
+    }
+}
+
+/// A page of a listing using keyset pagination, with the cursor of the next page if any.
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct CursorPaginated<T> {
+    pub items: Vec<T>,
+    pub limit: i64,
+    pub next_cursor: Option<String>,
+    /// link to the next page, sent in the `Link` header.
+    #[serde(skip)]
+    #[schemars(skip)]
+    next_link: Option<String>,
+}
+
+impl<T> CursorPaginated<T> {
+    /// constructor, given the position (e.g. `created_date` and `id`) of an item.
+    pub fn new(
+        items: Vec<T>,
+        pagination: &CursorPagination,
+        position: impl Fn(&T) -> (DateTime<Utc>, i32),
+    ) -> Self {
+        let next_cursor = pagination.get_next_cursor(&items, position);
+        let next_link = next_cursor
+            .as_ref()
+            .map(|cursor| pagination.get_page_uri(cursor));
+
+        Self {
+            items,
+            limit: pagination.limit.into(),
+            next_cursor,
+            next_link,
+        }
+    }
+
+    /// maps the items of the page (e.g. to their DTO).
+    pub fn map<U>(self, f: impl FnOnce(Vec<T>) -> Vec<U>) -> CursorPaginated<U> {
+        CursorPaginated {
+            items: f(self.items),
+            limit: self.limit,
+            next_cursor: self.next_cursor,
+            next_link: self.next_link,
+        }
+    }
+}
+
+impl<T> Page for CursorPaginated<T> {
+    fn get_headers(&self) -> Vec<Header<'static>> {
+        self.next_link
+            .iter()
+            .map(|uri| Header::new(LINK_HEADER, format!("<{uri}>; rel=\"next\"")))
+            .collect()
+    }
+}
+
+/// A page of a listing, e.g. `Paginated` or `CursorPaginated`.
+pub trait Page {
+    /// gets the headers linking the other pages.
+    fn get_headers(&self) -> Vec<Header<'static>>;
+}
+
+impl<T: Page> ApiResponse<T> {
+    /// Shorthand method to generate an HTTP 200 - OK with a page of a listing, and its headers (e.g. `Link` and `X-Total-Count`).
+    pub fn paginated(output: T) -> Self {
+        let headers = output.get_headers();
+
+        ApiResponse {
+            json: Json(output),
+            status: Status::Ok,
+            headers,
         }
     }
 }
@@ -76,26 +252,35 @@ impl ApiResponse<NoContentResponse> {
 impl<'r, T: serde::Serialize> Responder<'r, 'r> for ApiResponse<T> {
     /// Responder to handle ApiResponse transport
     fn respond_to(self, req: &Request) -> response::Result<'r> {
-        if self.status == Status::NoContent {
+        let mut response = if self.status == Status::NoContent {
             // builds a response with no content
-            return Response::build()
-                .status(self.status)
-                .header(ContentType::JSON)
-                .ok();
+            Response::build()
         } else if self.status == Status::InternalServerError && !cfg!(debug_assertions) {
             // intercepts 500 errors to avoid runtime error diffusion (e.g. database errors or potentialy secure information about the application).
             let json = Json(HttpException::from_status(self.status));
 
-            return Response::build_from(json.respond_to(req).unwrap())
-                .status(self.status)
-                .header(ContentType::JSON)
-                .ok();
+            Response::build_from(json.respond_to(req).unwrap())
+        } else {
+            Response::build_from(self.json.respond_to(req).unwrap())
+        };
+
+        response.status(self.status).header(ContentType::JSON);
+
+        // missing or invalid credentials must tell how to authenticate (RFC 7235, section 3.1).
+        if self.status == Status::Unauthorized
+            && !self
+                .headers
+                .iter()
+                .any(|header| header.name() == WWW_AUTHENTICATE_HEADER)
+        {
+            response.header(Header::new(WWW_AUTHENTICATE_HEADER, "Bearer"));
+        }
+
+        for header in self.headers.into_iter() {
+            response.header(header);
         }
 
-        Response::build_from(self.json.respond_to(req).unwrap())
-            .status(self.status)
-            .header(ContentType::JSON)
-            .ok()
+        response.ok()
     }
 }
 
@@ -105,3 +290,34 @@ pub struct ErrorMessage {
     /// The error message
     pub message: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paginated_links() {
+        let mut pagination = Pagination::new(2, 10, "/api/users");
+
+        let paginated = Paginated::new(vec![1, 2, 3], &pagination, 35);
+
+        assert_eq!(paginated.total_pages, 4);
+        assert_eq!(
+            paginated.get_link_header(),
+            "</api/users?page=1&per_page=10>; rel=\"first\", \
+            </api/users?page=1&per_page=10>; rel=\"prev\", \
+            </api/users?page=3&per_page=10>; rel=\"next\", \
+            </api/users?page=4&per_page=10>; rel=\"last\""
+        );
+
+        pagination.page = 1;
+        let paginated = Paginated::<i32>::new(vec![], &pagination, 0);
+
+        assert_eq!(paginated.total_pages, 0);
+        assert_eq!(
+            paginated.get_link_header(),
+            "</api/users?page=1&per_page=10>; rel=\"first\", \
+            </api/users?page=1&per_page=10>; rel=\"last\""
+        );
+    }
+}
@@ -1,35 +1,70 @@
 use super::{
     commands::console_command_registry::ConsoleCommandRegistry,
     configuration::ConfigState,
+    cursor::CursorSigner,
     database::{get_connection_pool, DbPoolState},
     fairings::{
-        database_migrations::DatabaseMigrations, fixture::FixtureLoader,
+        cors::Cors, database_migrations::DatabaseMigrations, fixture::FixtureLoader,
         jwt_certificates::JWTCertificatesFairing,
     },
+    jwt_keys::JWTKeyStore,
+    notifier::{ConsoleNotifier, Notifier},
+    role_hierarchy::RoleHierarchy,
+    secret_cipher::SecretCipher,
     security::{Security, SecurityVoter},
+    security_audit::create_audit_sink,
+    security_policy::SecurityPolicy,
 };
+use crate::core::openapi::{OpenApi, OpenApiDocument};
 use crate::{
     commands::test::test_command::TestCommand,
-    controllers::api::{account, application, auth},
+    controllers::api::{account, application, auth, me, role, totp},
     domain::repository::{
-        account_repository::AccountRepository, application_repository::ApplicationRepository,
-        cron_log_repository::CronLogRepository, refresh_token_repository::RefreshTokenRepository,
-        user_repository::UserRepository,
+        account_repository::AccountRepository, api_key_repository::ApiKeyRepository,
+        application_repository::ApplicationRepository, cron_log_repository::CronLogRepository,
+        impersonation_log_repository::ImpersonationLogRepository,
+        jwt_denylist_repository::JWTDenylistRepository,
+        login_throttle_repository::LoginThrottleRepository,
+        mfa_challenge_repository::MFAChallengeRepository,
+        oauth_client_repository::OAuthClientRepository,
+        password_reset_token_repository::PasswordResetTokenRepository,
+        recovery_code_repository::RecoveryCodeRepository,
+        refresh_token_repository::RefreshTokenRepository, role_repository::RoleRepository,
+        security_audit_log_repository::SecurityAuditLogRepository, user_repository::UserRepository,
+        user_totp_repository::UserTOTPRepository,
     },
     middlewares::{
-        account_middleware::AccountMiddleware, application_middleware::ApplicationMiddleware,
-        cron_log_middleware::CronLogMiddleware, refresh_token_middleware::RefreshTokenMiddleware,
-        user_middleware::UserMiddleware,
+        account_middleware::AccountMiddleware, api_key_middleware::ApiKeyMiddleware,
+        application_middleware::ApplicationMiddleware, cron_log_middleware::CronLogMiddleware,
+        impersonation_middleware::ImpersonationMiddleware,
+        jwt_denylist_middleware::JWTDenylistMiddleware,
+        login_throttle_middleware::LoginThrottleMiddleware, oauth_middleware::OAuthMiddleware,
+        password_reset_middleware::PasswordResetMiddleware,
+        refresh_token_middleware::RefreshTokenMiddleware, role_middleware::RoleMiddleware,
+        totp_middleware::TOTPMiddleware, user_middleware::UserMiddleware,
+    },
+    security::voters::{
+        application_security::ApplicationSecurityVoter, role_security::RoleSecurityVoter,
     },
-    security::voters::application_security::ApplicationSecurityVoter,
 };
 use crate::{
     commands::{
         app::{
+            clear_lockouts_command::ClearLockoutsCommand,
             create_account_command::CreateAccountCommand,
+            create_api_key_command::CreateApiKeyCommand,
             create_application_command::CreateApplicationCommand,
-            create_user_command::CreateUserCommand, demote_user_command::DemoteUserCommand,
-            promote_user_command::PromoteUserCommand,
+            create_oauth_client_command::CreateOAuthClientCommand,
+            create_user_command::CreateUserCommand, export_openapi_command::ExportOpenApiCommand,
+            grant_user_role_command::GrantUserRoleCommand,
+            list_api_keys_command::ListApiKeysCommand, list_lockouts_command::ListLockoutsCommand,
+            list_oauth_clients_command::ListOAuthClientsCommand,
+            permission_matrix_command::PermissionMatrixCommand,
+            purge_jwt_denylist_command::PurgeJWTDenylistCommand,
+            revoke_api_key_command::RevokeApiKeyCommand,
+            revoke_oauth_client_command::RevokeOAuthClientCommand,
+            revoke_user_role_command::RevokeUserRoleCommand,
+            rotate_jwt_keys_command::RotateJWTKeysCommand,
         },
         scaffold::generate_scaffold::GenerateScaffold,
     },
@@ -65,19 +100,93 @@ pub fn build() -> Rocket<Build> {
     let cron_log_rep = CronLogRepository::new(db_state.clone());
     let application_rep = ApplicationRepository::new(db_state.clone());
     let account_rep = AccountRepository::new(db_state.clone());
+    let jwt_denylist_rep = JWTDenylistRepository::new(db_state.clone());
+    let password_reset_token_rep = PasswordResetTokenRepository::new(db_state.clone());
+    let user_totp_rep = UserTOTPRepository::new(db_state.clone());
+    let recovery_code_rep = RecoveryCodeRepository::new(db_state.clone());
+    let mfa_challenge_rep = MFAChallengeRepository::new(db_state.clone());
+    let login_throttle_rep = LoginThrottleRepository::new(db_state.clone());
+    let api_key_rep = ApiKeyRepository::new(db_state.clone());
+    let oauth_client_rep = OAuthClientRepository::new(db_state.clone());
+    let role_rep = RoleRepository::new(db_state.clone());
+    let security_audit_log_rep = SecurityAuditLogRepository::new(db_state.clone());
+    let impersonation_log_rep = ImpersonationLogRepository::new(db_state.clone());
     // __REPOSITORY__
 
+    //
+    // -- notifier initialisation --
+    //
+    let notifier: Arc<dyn Notifier> = Arc::new(ConsoleNotifier::default());
+
+    //
+    // -- JWT keys, loaded on ignite --
+    //
+    let jwt_key_store = JWTKeyStore::default();
+
     //
     // -- middleware initialisation --
     //
-    let user_middleware = UserMiddleware::new(user_rep.clone(), configuration.clone());
+    let jwt_denylist_middleware = JWTDenylistMiddleware::new(jwt_denylist_rep.clone());
+    let user_middleware = UserMiddleware::new(
+        user_rep.clone(),
+        jwt_denylist_middleware.clone(),
+        jwt_key_store.clone(),
+        configuration.clone(),
+    );
     let refresh_token_middleware =
         RefreshTokenMiddleware::new(refresh_token_rep.clone(), configuration.clone());
     let cron_log_middleware = CronLogMiddleware::new(cron_log_rep.clone());
-    let application_middleware = ApplicationMiddleware::new(application_rep.clone());
-    let account_middleware = AccountMiddleware::new(account_rep.clone());
+    let application_middleware =
+        ApplicationMiddleware::new(application_rep.clone(), account_rep.clone());
+    let account_middleware = AccountMiddleware::new(account_rep.clone(), application_rep.clone());
+    let password_reset_middleware = PasswordResetMiddleware::new(
+        password_reset_token_rep.clone(),
+        user_rep.clone(),
+        notifier.clone(),
+        configuration.clone(),
+    );
+    let login_throttle_middleware =
+        LoginThrottleMiddleware::new(login_throttle_rep.clone(), configuration.clone());
+    let totp_middleware = TOTPMiddleware::new(
+        user_totp_rep.clone(),
+        recovery_code_rep.clone(),
+        mfa_challenge_rep.clone(),
+        user_rep.clone(),
+        configuration.clone(),
+        SecretCipher::from_config(&configuration, "totp_encryption_key"),
+    );
+    let api_key_middleware = ApiKeyMiddleware::new(api_key_rep.clone(), application_rep.clone());
+    let oauth_middleware = OAuthMiddleware::new(
+        oauth_client_rep.clone(),
+        user_rep.clone(),
+        application_rep.clone(),
+        refresh_token_rep.clone(),
+        jwt_denylist_middleware.clone(),
+        jwt_key_store.clone(),
+        configuration.clone(),
+    );
+    let role_hierarchy = RoleHierarchy::from_config(&configuration).unwrap();
+    let role_middleware =
+        RoleMiddleware::new(role_rep.clone(), user_rep.clone(), role_hierarchy.clone());
+    let impersonation_middleware = ImpersonationMiddleware::new(
+        impersonation_log_rep.clone(),
+        user_rep.clone(),
+        jwt_key_store.clone(),
+        configuration.clone(),
+    );
     // __MIDDLEWARE__
 
+    //
+    // -- security --
+    //
+    let security_policy = SecurityPolicy::from_config(&configuration).unwrap();
+    let mut security = create_security(security_policy.clone());
+    security.set_audit_sink(create_audit_sink(
+        &configuration,
+        security_audit_log_rep.clone(),
+    ));
+    let cursor_signer = CursorSigner::from_config(&configuration);
+
     //
     // -- command registry initialization --
     //
@@ -101,23 +210,63 @@ pub fn build() -> Rocket<Build> {
         application_middleware.clone(),
         user_middleware.clone(),
     )));
-    command_registry.add(Arc::new(PromoteUserCommand::new(
+    command_registry.add(Arc::new(GrantUserRoleCommand::new(
         cron_log_middleware.clone(),
         user_middleware.clone(),
+        role_middleware.clone(),
     )));
-    command_registry.add(Arc::new(DemoteUserCommand::new(
+    command_registry.add(Arc::new(RevokeUserRoleCommand::new(
         cron_log_middleware.clone(),
         user_middleware.clone(),
     )));
-
-    //
-    // -- security --
-    //
-    let mut security = Security::<dyn SecurityVoter>::new();
-    security.add_voter(Box::<AccountSecurityVoter>::default());
-    security.add_voter(Box::<ApplicationSecurityVoter>::default());
-    security.add_voter(Box::<UserSecurityVoter>::default());
-    // __SECURITY__
+    command_registry.add(Arc::new(PurgeJWTDenylistCommand::new(
+        cron_log_middleware.clone(),
+        jwt_denylist_middleware.clone(),
+    )));
+    command_registry.add(Arc::new(RotateJWTKeysCommand::new(
+        cron_log_middleware.clone(),
+        jwt_key_store.clone(),
+        configuration.clone(),
+    )));
+    command_registry.add(Arc::new(ListLockoutsCommand::new(
+        cron_log_middleware.clone(),
+        login_throttle_middleware.clone(),
+    )));
+    command_registry.add(Arc::new(ClearLockoutsCommand::new(
+        cron_log_middleware.clone(),
+        login_throttle_middleware.clone(),
+    )));
+    command_registry.add(Arc::new(CreateApiKeyCommand::new(
+        cron_log_middleware.clone(),
+        application_middleware.clone(),
+        api_key_middleware.clone(),
+    )));
+    command_registry.add(Arc::new(ListApiKeysCommand::new(
+        cron_log_middleware.clone(),
+        api_key_middleware.clone(),
+    )));
+    command_registry.add(Arc::new(RevokeApiKeyCommand::new(
+        cron_log_middleware.clone(),
+        api_key_middleware.clone(),
+    )));
+    command_registry.add(Arc::new(CreateOAuthClientCommand::new(
+        cron_log_middleware.clone(),
+        application_middleware.clone(),
+        oauth_middleware.clone(),
+    )));
+    command_registry.add(Arc::new(ListOAuthClientsCommand::new(
+        cron_log_middleware.clone(),
+        oauth_middleware.clone(),
+    )));
+    command_registry.add(Arc::new(RevokeOAuthClientCommand::new(
+        cron_log_middleware.clone(),
+        oauth_middleware.clone(),
+    )));
+    command_registry.add(Arc::new(PermissionMatrixCommand::new(
+        cron_log_middleware.clone(),
+        create_security(security_policy.clone()),
+        role_hierarchy.clone(),
+    )));
 
     //
     // -- fixtures --
@@ -140,25 +289,32 @@ pub fn build() -> Rocket<Build> {
         command_registry.add(Arc::new(GenerateScaffold::new(cron_log_middleware.clone())));
     }
 
+    build = mount_routes(build);
+
+    //
+    // -- API documentation --
+    //
+    let openapi_document = OpenApiDocument(create_openapi(&configuration).generate(build.routes()));
+    command_registry.add(Arc::new(ExportOpenApiCommand::new(
+        cron_log_middleware.clone(),
+        openapi_document.clone(),
+    )));
+
+    if configuration.get_bool_or_default("openapi_ui", false) {
+        // the Redoc script is only loaded once browsers can check its integrity.
+        if configuration
+            .get_string_or_default("openapi_ui_integrity", "")
+            .is_empty()
+        {
+            log::warn!("No openapi_ui_integrity configured, the API documentation UI is disabled.");
+        } else {
+            build = build.mount("/api", routes![app::openapi::openapi_ui]);
+        }
+    }
+
+    let cors = Cors::from_config(&configuration);
+
     build = build
-        // routes
-        .mount("/", routes![app::index::index])
-        .mount("/api/auth", routes![auth::token, auth::refresh_token])
-        .mount(
-            "/api",
-            routes![
-                account::account_list,
-                account::account_details,
-                application::application_list,
-                application::application_details,
-                user::user_list,
-                user::user_details,
-                user::user_create,
-                user::user_update,
-                user::user_delete,
-                // __CONTROLLERS__
-            ],
-        )
         // catchers
         .register("/", catchers![catcher::default_catcher])
         // managed global states
@@ -166,17 +322,156 @@ pub fn build() -> Rocket<Build> {
         .manage(db_state)
         .manage(security)
         .manage(command_registry)
+        .manage(openapi_document)
+        .manage(cursor_signer)
+        .manage(jwt_key_store.clone())
         // managed middlewares
         .manage(user_middleware)
         .manage(refresh_token_middleware)
         .manage(cron_log_middleware)
         .manage(application_middleware)
         .manage(account_middleware)
+        .manage(jwt_denylist_middleware)
+        .manage(password_reset_middleware)
+        .manage(totp_middleware)
+        .manage(login_throttle_middleware)
+        .manage(api_key_middleware)
+        .manage(oauth_middleware)
+        .manage(role_middleware)
+        .manage(impersonation_middleware)
         // __MANAGE__
         // fairings
         .attach(DatabaseMigrations::default())
-        .attach(JWTCertificatesFairing::default())
-        .attach(fixture_loader);
+        .attach(JWTCertificatesFairing::new(jwt_key_store.clone()))
+        .attach(fixture_loader)
+        .attach(cors);
 
     build
 }
+
+/// mounts every route of the API, documented in `create_openapi`.
+pub fn mount_routes(build: Rocket<Build>) -> Rocket<Build> {
+    build
+        .mount("/", routes![app::index::index])
+        .mount(
+            "/.well-known",
+            routes![app::well_known::jwks, app::well_known::openid_configuration],
+        )
+        .mount("/api", routes![app::openapi::openapi_json])
+        .mount(
+            "/oauth",
+            routes![
+                app::oauth::token,
+                app::oauth::introspect,
+                app::oauth::revoke
+            ],
+        )
+        .mount(
+            "/api/auth",
+            routes![
+                auth::token,
+                auth::token_mfa,
+                auth::refresh_token,
+                auth::logout,
+                auth::logout_all,
+                auth::delegate,
+                auth::password_reset_request,
+                auth::password_reset_confirm
+            ],
+        )
+        .mount(
+            "/api",
+            routes![
+                account::account_list,
+                account::account_details,
+                account::account_create,
+                account::account_update,
+                account::account_delete,
+                account::account_restore,
+                application::application_list_by_cursor,
+                application::application_list,
+                application::application_details,
+                application::application_create,
+                application::application_update,
+                application::application_delete,
+                application::application_restore,
+                user::user_list_for_application,
+                user::user_list_by_cursor,
+                user::user_list,
+                user::user_details,
+                user::user_create,
+                user::user_update,
+                user::user_update_password,
+                totp::totp_enroll,
+                totp::totp_verify,
+                user::user_delete,
+                user::user_role_grant,
+                user::user_role_revoke,
+                user::user_impersonate,
+                me::me_details,
+                me::me_update,
+                role::role_list,
+                role::role_create,
+                role::role_delete,
+                // __CONTROLLERS__
+            ],
+        )
+}
+
+/// creates the security registry, with every voter and the declarative policies as a fallback.
+pub fn create_security(policy: SecurityPolicy) -> Security<'static, dyn SecurityVoter<'static>> {
+    let mut security = Security::<dyn SecurityVoter>::new();
+    security.add_voter(Box::<AccountSecurityVoter>::default());
+    security.add_voter(Box::<ApplicationSecurityVoter>::default());
+    security.add_voter(Box::<RoleSecurityVoter>::default());
+    security.add_voter(Box::<UserSecurityVoter>::default());
+    // __SECURITY__
+    security.set_fallback_policy(policy);
+
+    security
+}
+
+/// creates the OpenAPI registry, each controller documenting its routes on the base they are mounted on.
+pub fn create_openapi(configuration: &ConfigState) -> OpenApi {
+    let mut openapi = OpenApi::new(
+        &configuration.get_string_or_default("package.name", "api"),
+        &configuration.get_string_or_default("package.version", "1.0.0"),
+    );
+
+    app::index::document(&mut openapi, "/");
+    app::well_known::document(&mut openapi, "/.well-known");
+    app::openapi::document(&mut openapi, "/api");
+    app::oauth::document(&mut openapi, "/oauth");
+    auth::document(&mut openapi, "/api/auth");
+    account::document(&mut openapi, "/api");
+    application::document(&mut openapi, "/api");
+    user::document(&mut openapi, "/api");
+    totp::document(&mut openapi, "/api");
+    me::document(&mut openapi, "/api");
+    role::document(&mut openapi, "/api");
+    // __OPENAPI__
+
+    openapi
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_mounted_route_is_documented() {
+        let openapi = create_openapi(&ConfigState::default());
+
+        let undocumented = mount_routes(rocket::build())
+            .routes()
+            .filter(|route| !openapi.is_documented(route.method, route.uri.path()))
+            .map(|route| format!("{} {}", route.method, route.uri.path()))
+            .collect::<Vec<String>>();
+
+        assert!(
+            undocumented.is_empty(),
+            "Routes missing from create_openapi : {}",
+            undocumented.join(", ")
+        );
+    }
+}
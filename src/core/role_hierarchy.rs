@@ -0,0 +1,179 @@
+use std::collections::HashMap;
+
+use anyhow::{bail, Result};
+
+use super::configuration::ConfigState;
+use crate::domain::model::{
+    role::Role,
+    user::{ROLE_SUPER_ADMIN, ROLE_USER, ROLE_USER_ADMIN},
+};
+
+/// Default role hierarchy : a super administrator is an administrator, which is a user.
+pub const DEFAULT_ROLE_HIERARCHY: &str =
+    "ROLE_SUPER_ADMIN>ROLE_USER_ADMIN,ROLE_USER_ADMIN>ROLE_USER";
+
+/// A struct representing which roles are implied by others (e.g. `ROLE_USER_ADMIN` implies `ROLE_USER`).
+#[derive(Debug, Clone, Default)]
+pub struct RoleHierarchy {
+    /// roles directly implied by each role.
+    implied_roles: HashMap<String, Vec<String>>,
+}
+
+impl RoleHierarchy {
+    /// loads the hierarchy from the `role_hierarchy` configuration, or the default one.
+    pub fn from_config(configuration: &ConfigState) -> Result<Self> {
+        Self::parse(&configuration.get_string_or_default("role_hierarchy", DEFAULT_ROLE_HIERARCHY))
+    }
+
+    /// parses a hierarchy definition, given as comma separated `ROLE>IMPLIED_ROLE` rules.
+    pub fn parse(definition: &str) -> Result<Self> {
+        let mut hierarchy = Self::default();
+
+        for rule in definition.split(',').map(|rule| rule.trim()) {
+            if rule.is_empty() {
+                continue;
+            }
+
+            match rule.split_once('>') {
+                Some((role, implied_role))
+                    if !role.trim().is_empty() && !implied_role.trim().is_empty() =>
+                {
+                    hierarchy.add(role.trim(), implied_role.trim());
+                }
+                _ => bail!("Invalid role hierarchy rule \"{rule}\", expected ROLE>IMPLIED_ROLE"),
+            }
+        }
+
+        Ok(hierarchy)
+    }
+
+    /// creates a copy of the hierarchy extended with an application's custom roles.
+    pub fn with_roles(&self, roles: &[Role]) -> Self {
+        let mut hierarchy = self.clone();
+
+        for role in roles.iter() {
+            for implied_role in role.implied_roles.iter() {
+                hierarchy.add(&role.name, implied_role);
+            }
+        }
+
+        hierarchy
+    }
+
+    /// gets every built-in role : the ones known by the API, and the ones named in the hierarchy.
+    pub fn get_role_names(&self) -> Vec<String> {
+        let mut role_names = vec![
+            ROLE_USER.to_string(),
+            ROLE_USER_ADMIN.to_string(),
+            ROLE_SUPER_ADMIN.to_string(),
+        ];
+
+        for (role, implied_roles) in self.implied_roles.iter() {
+            for role_name in std::iter::once(role).chain(implied_roles.iter()) {
+                if !role_names.contains(role_name) {
+                    role_names.push(role_name.clone());
+                }
+            }
+        }
+
+        role_names
+    }
+
+    /// gets the roles directly implied by a role.
+    pub fn get_implied_roles(&self, role: &str) -> Vec<String> {
+        self.implied_roles.get(role).cloned().unwrap_or_default()
+    }
+
+    /// resolves the effective roles of a list of roles, e.g. the roles themselves and every role they imply, transitively.
+    pub fn resolve(&self, roles: &[String]) -> Vec<String> {
+        let mut effective_roles = Vec::<String>::new();
+        let mut pending_roles = roles.to_vec();
+
+        while let Some(role) = pending_roles.pop() {
+            // already visited roles are skipped, so cycles cannot loop forever.
+            if effective_roles.contains(&role) {
+                continue;
+            }
+
+            pending_roles.extend(self.get_implied_roles(&role).into_iter().rev());
+            effective_roles.push(role);
+        }
+
+        effective_roles
+    }
+
+    /// PRIVATE - registers a role implied by another one.
+    fn add(&mut self, role: &str, implied_role: &str) {
+        let implied_roles = self.implied_roles.entry(role.into()).or_default();
+
+        if !implied_roles
+            .iter()
+            .any(|existing| existing == implied_role)
+        {
+            implied_roles.push(implied_role.into());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn to_roles(roles: &[&str]) -> Vec<String> {
+        roles.iter().map(|role| role.to_string()).collect()
+    }
+
+    #[test]
+    fn test_resolve_default_hierarchy() {
+        let hierarchy = RoleHierarchy::parse(DEFAULT_ROLE_HIERARCHY).unwrap();
+
+        let mut resolved = hierarchy.resolve(&to_roles(&["ROLE_SUPER_ADMIN"]));
+        resolved.sort();
+
+        assert_eq!(
+            resolved,
+            to_roles(&["ROLE_SUPER_ADMIN", "ROLE_USER", "ROLE_USER_ADMIN"])
+        );
+        assert_eq!(
+            hierarchy.resolve(&to_roles(&["ROLE_USER"])),
+            to_roles(&["ROLE_USER"])
+        );
+        assert_eq!(
+            hierarchy.resolve(&to_roles(&["ROLE_CUSTOM"])),
+            to_roles(&["ROLE_CUSTOM"])
+        );
+    }
+
+    #[test]
+    fn test_resolve_with_cycles_and_custom_roles() {
+        let hierarchy = RoleHierarchy::parse("ROLE_A>ROLE_B, ROLE_B>ROLE_A").unwrap();
+
+        let mut resolved = hierarchy.resolve(&to_roles(&["ROLE_A"]));
+        resolved.sort();
+
+        assert_eq!(resolved, to_roles(&["ROLE_A", "ROLE_B"]));
+
+        let hierarchy = RoleHierarchy::parse(DEFAULT_ROLE_HIERARCHY)
+            .unwrap()
+            .with_roles(&[Role {
+                name: "ROLE_SUPPORT".into(),
+                implied_roles: to_roles(&["ROLE_USER_ADMIN"]),
+                ..Default::default()
+            }]);
+
+        let mut resolved = hierarchy.resolve(&to_roles(&["ROLE_SUPPORT"]));
+        resolved.sort();
+
+        assert_eq!(
+            resolved,
+            to_roles(&["ROLE_SUPPORT", "ROLE_USER", "ROLE_USER_ADMIN"])
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_rules() {
+        assert!(RoleHierarchy::parse("ROLE_A").is_err());
+        assert!(RoleHierarchy::parse("ROLE_A>").is_err());
+        assert!(RoleHierarchy::parse("").is_ok());
+    }
+}
@@ -0,0 +1,126 @@
+use std::sync::Arc;
+
+use aes_gcm::{
+    aead::{Aead, KeyInit},
+    Aes256Gcm, Key, Nonce,
+};
+use anyhow::{anyhow, Context, Result};
+use data_encoding::BASE64URL_NOPAD;
+use rand_core::{OsRng, RngCore};
+use sha2::{Digest, Sha256};
+
+use super::configuration::ConfigState;
+
+/// Size in bytes of the nonce generated for each encryption, as expected by AES-GCM.
+const NONCE_SIZE: usize = 12;
+
+/// Encrypts secrets which must be read back (e.g. TOTP shared secrets), so a database read alone does not reveal them.
+///
+/// Secrets are encrypted with AES-256-GCM, under a key derived from a configured passphrase.
+/// Ciphertexts are `<nonce>.<ciphertext>`, both base64url encoded.
+#[derive(Clone)]
+pub struct SecretCipher {
+    /// the derived key, `None` when no passphrase is configured.
+    key: Option<Arc<Key<Aes256Gcm>>>,
+}
+
+impl SecretCipher {
+    /// constructor.
+    pub fn new(passphrase: &str) -> Self {
+        let key = Sha256::new()
+            .chain_update(b"secret-cipher\n")
+            .chain_update(passphrase.as_bytes())
+            .finalize();
+
+        Self {
+            key: Some(Arc::new(key)),
+        }
+    }
+
+    /// creates the cipher from a configuration key (e.g. `totp_encryption_key`).
+    ///
+    /// Without it, secrets can neither be encrypted nor decrypted : a random key would lose them on restart.
+    pub fn from_config(configuration: &ConfigState, config_key: &str) -> Self {
+        match configuration.get_string(config_key) {
+            Ok(passphrase) if !passphrase.is_empty() => Self::new(&passphrase),
+            _ => {
+                log::warn!("No {config_key} configured, secrets relying on it cannot be stored.");
+
+                Self { key: None }
+            }
+        }
+    }
+
+    /// encrypts a secret.
+    pub fn encrypt(&self, secret: &str) -> Result<String> {
+        let mut nonce = [0u8; NONCE_SIZE];
+        OsRng.fill_bytes(&mut nonce);
+
+        let ciphertext = self
+            .get_cipher()?
+            .encrypt(Nonce::from_slice(&nonce), secret.as_bytes())
+            .map_err(|_| anyhow!("Cannot encrypt secret"))?;
+
+        Ok(format!(
+            "{}.{}",
+            BASE64URL_NOPAD.encode(&nonce),
+            BASE64URL_NOPAD.encode(&ciphertext)
+        ))
+    }
+
+    /// decrypts a secret, failing if it was altered or encrypted with another key.
+    pub fn decrypt(&self, encrypted: &str) -> Result<String> {
+        let (nonce, ciphertext) = encrypted
+            .split_once('.')
+            .context("Malformed encrypted secret")?;
+
+        let nonce = BASE64URL_NOPAD
+            .decode(nonce.as_bytes())
+            .context("Malformed encrypted secret")?;
+        let ciphertext = BASE64URL_NOPAD
+            .decode(ciphertext.as_bytes())
+            .context("Malformed encrypted secret")?;
+
+        if nonce.len() != NONCE_SIZE {
+            return Err(anyhow!("Malformed encrypted secret"));
+        }
+
+        let secret = self
+            .get_cipher()?
+            .decrypt(Nonce::from_slice(&nonce), ciphertext.as_slice())
+            .map_err(|_| anyhow!("Cannot decrypt secret"))?;
+
+        Ok(String::from_utf8(secret)?)
+    }
+
+    /// PRIVATE - gets the AES-GCM cipher, if a key is configured.
+    fn get_cipher(&self) -> Result<Aes256Gcm> {
+        let key = self
+            .key
+            .as_ref()
+            .context("No encryption key configured for secrets")?;
+
+        Ok(Aes256Gcm::new(key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encrypt_and_decrypt() {
+        let cipher = SecretCipher::new("passphrase");
+
+        let encrypted = cipher.encrypt("JBSWY3DPEHPK3PXP").unwrap();
+
+        assert!(!encrypted.contains("JBSWY3DPEHPK3PXP"));
+        assert_ne!(encrypted, cipher.encrypt("JBSWY3DPEHPK3PXP").unwrap());
+        assert_eq!(cipher.decrypt(&encrypted).unwrap(), "JBSWY3DPEHPK3PXP");
+        // another key, or an altered ciphertext, is refused.
+        assert!(SecretCipher::new("other").decrypt(&encrypted).is_err());
+        assert!(cipher.decrypt(&format!("{encrypted}A")).is_err());
+        assert!(cipher.decrypt("garbage").is_err());
+        assert!(SecretCipher { key: None }.encrypt("secret").is_err());
+    }
+}
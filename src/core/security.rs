@@ -1,12 +1,100 @@
-use crate::domain::model::user::{User, ROLE_USER, ROLE_USER_ADMIN};
-use anyhow::{bail, Result};
+use crate::{
+    core::{
+        guards::connected_application::ConnectedApplication,
+        security_audit::{SecurityAuditSink, SecurityDecision, SecurityDecisionOutcome},
+        security_policy::SecurityPolicy,
+    },
+    domain::model::user::{User, ROLE_SUPER_ADMIN, ROLE_USER, ROLE_USER_ADMIN},
+};
+use chrono::Utc;
 use std::collections::HashMap;
+use thiserror::Error;
+
+/// The authenticated party an access is checked for.
+#[derive(Debug, Clone, Copy)]
+pub enum Principal<'p> {
+    /// a user, authenticated with a JWT.
+    User(&'p User),
+    /// an application, authenticated with an API key.
+    Application(&'p ConnectedApplication),
+}
+
+impl<'p> From<&'p User> for Principal<'p> {
+    fn from(user: &'p User) -> Self {
+        Principal::User(user)
+    }
+}
+
+impl<'p> From<&'p ConnectedApplication> for Principal<'p> {
+    fn from(application: &'p ConnectedApplication) -> Self {
+        Principal::Application(application)
+    }
+}
+
+/// The decision taken by a voter on a right.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AccessDecision {
+    /// the access is granted.
+    Granted,
+    /// the access is refused, with an optional reason which can be shown to the client.
+    Denied(Option<String>),
+}
+
+impl AccessDecision {
+    /// shorthand method to deny an access with a reason.
+    pub fn deny(reason: &str) -> Self {
+        AccessDecision::Denied(Some(reason.into()))
+    }
+
+    /// checks if the access is granted.
+    pub fn is_granted(&self) -> bool {
+        *self == AccessDecision::Granted
+    }
+}
+
+impl From<bool> for AccessDecision {
+    /// grants the access when true, denies it without reason otherwise.
+    fn from(granted: bool) -> Self {
+        if granted {
+            AccessDecision::Granted
+        } else {
+            AccessDecision::Denied(None)
+        }
+    }
+}
+
+/// Error states preventing a decision to be taken.
+///
+/// Those are misconfigurations (e.g. a controller asking for an unknown right), never a client error : they must be logged, and not exposed.
+#[derive(Debug, Error)]
+pub enum AccessError {
+    #[error("Cannot find a security handler for subject \"{}\"", _0)]
+    UnknownSubject(String),
+    #[error("No right \"{}\" found for subject \"{}\"", _1, _0)]
+    UnknownRight(String, String),
+    #[error("No \"{}\" context provided to security voter", _0)]
+    MissingContext(String),
+    #[error("Invalid \"{}\" context provided to security voter : {}", _0, _1)]
+    InvalidContext(String, String),
+    #[error("Internal error : {} ", _0)]
+    Internal(String),
+}
+
+impl From<anyhow::Error> for AccessError {
+    fn from(error: anyhow::Error) -> Self {
+        AccessError::Internal(error.to_string())
+    }
+}
 
 /// Security policy registry.
 ///
 /// This struct will store any SecurityVoter to handle security testing and validation against requests and connected users.
 pub struct Security<'a, T: ?Sized + Send + Sync> {
     voters: HashMap<&'a str, Box<T>>,
+    /// declarative policies, used for subjects no voter supports.
+    fallback_policy: SecurityPolicy,
+    /// where decisions are recorded, if anywhere.
+    audit_sink: Option<Box<dyn SecurityAuditSink>>,
 }
 
 impl<'a, T> Default for Security<'a, T>
@@ -27,6 +115,8 @@ where
     pub fn new() -> Self {
         Self {
             voters: HashMap::new(),
+            fallback_policy: SecurityPolicy::default(),
+            audit_sink: None,
         }
     }
 
@@ -39,6 +129,60 @@ where
         self
     }
 
+    /// Sets the declarative policies used when no SecurityVoter supports a subject.
+    pub fn set_fallback_policy(&mut self, policy: SecurityPolicy) -> &mut Self {
+        self.fallback_policy = policy;
+
+        self
+    }
+
+    /// Sets the sink recording every decision taken by the registry.
+    pub fn set_audit_sink(&mut self, sink: Option<Box<dyn SecurityAuditSink>>) -> &mut Self {
+        self.audit_sink = sink;
+
+        self
+    }
+
+    /// Gets every subject handled by the registry, with its rights, sorted by subject.
+    ///
+    /// Voters take precedence over the fallback policy for a same subject.
+    pub fn get_subjects(&self) -> Vec<(&str, Vec<&str>)> {
+        let mut subjects = self
+            .voters
+            .iter()
+            .map(|(subject, voter)| (*subject, voter.get_rights()))
+            .collect::<Vec<(&str, Vec<&str>)>>();
+
+        for (subject, rights) in self.fallback_policy.get_subjects() {
+            if !self.voters.contains_key(subject) {
+                subjects.push((subject, rights));
+            }
+        }
+
+        subjects.sort_by_key(|(subject, _)| *subject);
+
+        subjects
+    }
+
+    /// Lists the rights granted to a user regardless of any resource (e.g. `user:list`).
+    ///
+    /// Rights depending on a context (e.g. the resource owner) are left out. Checks are not audited, as they are not access attempts.
+    pub fn get_granted_rights(&self, user: &User) -> Vec<String> {
+        let mut granted_rights = Vec::<String>::new();
+
+        for (subject, rights) in self.get_subjects() {
+            for right in rights {
+                if let Ok(AccessDecision::Granted) =
+                    self.vote(subject, right, Principal::User(user), None).1
+                {
+                    granted_rights.push(format!("{subject}:{right}"));
+                }
+            }
+        }
+
+        granted_rights
+    }
+
     /// shorthand method to check if a user have all the roles in the `roles` vector.
     ///
     /// It can be used :
@@ -55,7 +199,7 @@ where
             .all(|item| user.roles.contains(&item.to_string()))
     }
 
-    /// Shorthand method to check if a given user has access to a particular right on a subject. It can optionally use a context to specify rights.
+    /// Shorthand method to check if a given principal (e.g. a user or an application) has access to a particular right on a subject. It can optionally use a context to specify rights.
     ///
     /// It can be used :
     /// ```rust
@@ -65,18 +209,113 @@ where
     /// ```rust
     /// security::has_access("books", "borrow", &user, map!{"book_id" => String::from(book.id)});
     /// ```
-    pub fn has_access(
+    /// or :
+    /// ```rust
+    /// security::has_access("books", "list", &connected_application);
+    /// ```
+    pub fn has_access<'p>(
         &self,
         subject: &str,
         right: &str,
-        user: &User,
+        principal: impl Into<Principal<'p>>,
+        context: Option<HashMap<&str, String>>,
+    ) -> Result<AccessDecision, AccessError> {
+        let principal = principal.into();
+
+        if self.audit_sink.is_none() {
+            return self.vote(subject, right, principal, context).1;
+        }
+
+        let audited_context = context
+            .iter()
+            .flatten()
+            .map(|(key, value)| (key.to_string(), value.clone()))
+            .collect();
+
+        let (voter, vote) = self.vote(subject, right, principal, context);
+
+        let (user_id, api_key_id, application_id) = match principal {
+            Principal::User(user) => (Some(user.id), None, user.application_id),
+            Principal::Application(application) => (
+                None,
+                application.api_key.as_ref().map(|api_key| api_key.id),
+                application.application.id,
+            ),
+        };
+
+        let (outcome, reason) = match &vote {
+            Ok(AccessDecision::Granted) => (SecurityDecisionOutcome::Granted, None),
+            Ok(AccessDecision::Denied(reason)) => (
+                SecurityDecisionOutcome::Denied,
+                Some(
+                    reason
+                        .clone()
+                        .unwrap_or_else(|| "Access denied by voter".into()),
+                ),
+            ),
+            Err(error) => (SecurityDecisionOutcome::Error, Some(error.to_string())),
+        };
+
+        self.audit(&SecurityDecision {
+            user_id,
+            api_key_id,
+            application_id,
+            subject: subject.into(),
+            right: right.into(),
+            context: audited_context,
+            voter: voter.map(|voter| voter.into()),
+            outcome,
+            reason,
+            date: Utc::now(),
+        });
+
+        vote
+    }
+
+    /// PRIVATE - takes the decision with the voter supporting the subject, or the fallback policy, and gives the name of who decided.
+    fn vote(
+        &self,
+        subject: &str,
+        right: &str,
+        principal: Principal,
         context: Option<HashMap<&str, String>>,
-    ) -> Result<bool> {
+    ) -> (Option<&'static str>, Result<AccessDecision, AccessError>) {
         if let Some(voter) = self.voters.get(subject) {
-            return voter.has_access(right, user, context);
+            let vote = match principal {
+                Principal::User(user) => voter.has_access(right, user, context),
+                Principal::Application(application) => {
+                    voter.has_application_access(right, application, context)
+                }
+            };
+
+            return (Some(voter.get_name()), vote);
         }
 
-        bail!("Cannot find a security handler for {subject}: {right}");
+        if self.fallback_policy.supports(subject) {
+            let vote =
+                match principal {
+                    Principal::User(user) => self
+                        .fallback_policy
+                        .has_access(subject, right, user, context),
+                    Principal::Application(application) => self
+                        .fallback_policy
+                        .has_application_access(subject, right, application, context),
+                };
+
+            return (Some("SecurityPolicy"), vote);
+        }
+
+        (None, Err(AccessError::UnknownSubject(subject.into())))
+    }
+
+    /// PRIVATE - gives a decision to the audit sink.
+    fn audit(&self, decision: &SecurityDecision) {
+        if let Some(sink) = &self.audit_sink {
+            // a failing sink must never change the decision.
+            if let Err(error) = sink.record(decision) {
+                log::error!(target: "security", "Cannot record security decision : {error}");
+            }
+        }
     }
 }
 
@@ -84,13 +323,46 @@ where
 pub trait SecurityVoter<'a>: Send + Sync {
     /// Gets the "subject" supported by the voter
     fn supports(&self) -> &'a str;
+
+    /// Gets the rights handled by the voter, e.g. to display the permission matrix.
+    fn get_rights(&self) -> Vec<&'a str> {
+        vec![]
+    }
+
+    /// Gets the name of the voter, e.g. to record which voter took a decision.
+    fn get_name(&self) -> &'static str {
+        let type_name = std::any::type_name::<Self>();
+
+        type_name.rsplit("::").next().unwrap_or(type_name)
+    }
+
     /// Takes a given right and a user, and checks if the user has_access to the action represented by the right.
     fn has_access(
         &self,
         right: &str,
         user: &User,
         context: Option<HashMap<&str, String>>,
-    ) -> Result<bool>;
+    ) -> Result<AccessDecision, AccessError>;
+
+    /// Takes a given right and an application authenticated with an API key, and checks if the application has_access to the action represented by the right.
+    ///
+    /// By default, access is denied : a scope alone does not tell which application a resource belongs to.
+    /// Voters opting in must check the scope (e.g. `application.has_scope(...)`) and the application owning the resource.
+    fn has_application_access(
+        &self,
+        _right: &str,
+        _application: &ConnectedApplication,
+        _context: Option<HashMap<&str, String>>,
+    ) -> Result<AccessDecision, AccessError> {
+        Ok(AccessDecision::deny(
+            "This resource cannot be accessed with an API key.",
+        ))
+    }
+
+    /// Gets the error of a right the voter does not handle.
+    fn unknown_right(&self, right: &str) -> AccessError {
+        AccessError::UnknownRight(self.supports().into(), right.into())
+    }
 
     /// Gets a given value in a `context` specified in the `has_access` method.
     fn get_context(&self, context: Option<HashMap<&str, String>>, key: &str) -> Option<String> {
@@ -106,6 +378,16 @@ pub trait SecurityVoter<'a>: Send + Sync {
 
         result
     }
+
+    /// Gets a given value in a `context`, failing if the controller did not provide it.
+    fn get_required_context(
+        &self,
+        context: Option<HashMap<&str, String>>,
+        key: &str,
+    ) -> Result<String, AccessError> {
+        self.get_context(context, key)
+            .ok_or_else(|| AccessError::MissingContext(key.into()))
+    }
 }
 
 /// shorthand method to check if a user has the role `ROLE_USER`.
@@ -118,7 +400,45 @@ pub fn is_admin(user: &User) -> bool {
     user.roles.contains(&ROLE_USER_ADMIN.into())
 }
 
+/// shorthand method to check if a user has the role `ROLE_SUPER_ADMIN`, managing every account and application.
+pub fn is_super_admin(user: &User) -> bool {
+    user.roles.contains(&ROLE_SUPER_ADMIN.into())
+}
+
 /// shorthand method to check if a user has the specified role.
 pub fn is_a(role: &str, user: &User) -> bool {
     user.roles.contains(&role.into())
 }
+
+/// shorthand method to check if a scope (e.g. `subject:right`) is covered by a list of granted scopes.
+///
+/// A granted scope covers the same scope, `subject:*` covers every right on the subject, and `*` covers everything.
+pub fn is_scope_granted(granted_scopes: &[String], scope: &str) -> bool {
+    granted_scopes.iter().any(|granted_scope| {
+        granted_scope == "*"
+            || granted_scope == scope
+            || granted_scope
+                .strip_suffix('*')
+                .filter(|subject| subject.ends_with(':'))
+                .is_some_and(|subject| scope.starts_with(subject))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scope_matching() {
+        let granted_scopes = vec!["user:list".to_string(), "account:*".to_string()];
+
+        assert!(is_scope_granted(&granted_scopes, "user:list"));
+        assert!(is_scope_granted(&granted_scopes, "account:details"));
+        assert!(is_scope_granted(&granted_scopes, "account:*"));
+        assert!(!is_scope_granted(&granted_scopes, "user:delete"));
+        assert!(!is_scope_granted(&granted_scopes, "accounts:list"));
+        assert!(!is_scope_granted(&granted_scopes, "*"));
+        assert!(is_scope_granted(&["*".to_string()], "user:delete"));
+        assert!(!is_scope_granted(&[], "user:list"));
+    }
+}
@@ -0,0 +1,159 @@
+use std::{collections::HashMap, fmt::Display};
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+
+use super::configuration::ConfigState;
+use crate::domain::{
+    model::security_audit_log::NewSecurityAuditLog,
+    repository::security_audit_log_repository::SecurityAuditLogRepository,
+};
+
+/// The outcome of a security decision.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SecurityDecisionOutcome {
+    /// the access was granted.
+    Granted,
+    /// the voter refused the access.
+    Denied,
+    /// the decision could not be taken (e.g. unknown subject or right, missing context).
+    Error,
+}
+
+impl SecurityDecisionOutcome {
+    /// gets the outcome as a lowercase string.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SecurityDecisionOutcome::Granted => "granted",
+            SecurityDecisionOutcome::Denied => "denied",
+            SecurityDecisionOutcome::Error => "error",
+        }
+    }
+}
+
+impl Display for SecurityDecisionOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// A structured event describing a decision taken by the `Security` registry.
+#[derive(Debug, Clone)]
+pub struct SecurityDecision {
+    /// the user the access was checked for, if any.
+    pub user_id: Option<i32>,
+    /// the API key the access was checked for, if any.
+    pub api_key_id: Option<i32>,
+    /// the application of the principal.
+    pub application_id: i32,
+    pub subject: String,
+    pub right: String,
+    pub context: HashMap<String, String>,
+    /// the voter which took the decision, if any handles the subject.
+    pub voter: Option<String>,
+    pub outcome: SecurityDecisionOutcome,
+    /// why the access was not granted.
+    pub reason: Option<String>,
+    pub date: DateTime<Utc>,
+}
+
+/// A trait to record security decisions (e.g. in logs, a database or a tracing pipeline).
+///
+/// Implement it to plug another destination, then give it to the `Security` registry in the rocket factory.
+pub trait SecurityAuditSink: Send + Sync {
+    /// records a decision. Errors are reported, but never change the decision.
+    fn record(&self, decision: &SecurityDecision) -> Result<()>;
+}
+
+/// A sink writing decisions in the application logs : denials as warnings, grants as debug messages.
+#[derive(Default)]
+pub struct LogSecurityAuditSink {}
+
+impl SecurityAuditSink for LogSecurityAuditSink {
+    fn record(&self, decision: &SecurityDecision) -> Result<()> {
+        let level = match decision.outcome {
+            SecurityDecisionOutcome::Granted => log::Level::Debug,
+            _ => log::Level::Warn,
+        };
+
+        log::log!(
+            target: "security",
+            level,
+            "{} {}:{} for user={} api_key={} application={} by {} (context: {:?}){}",
+            decision.outcome,
+            decision.subject,
+            decision.right,
+            display_option(decision.user_id),
+            display_option(decision.api_key_id),
+            decision.application_id,
+            decision.voter.as_deref().unwrap_or("no voter"),
+            decision.context,
+            decision
+                .reason
+                .as_ref()
+                .map(|reason| format!(" : {reason}"))
+                .unwrap_or_default(),
+        );
+
+        Ok(())
+    }
+}
+
+/// A sink storing refused decisions (e.g. denied or in error) in the `security_audit_log` table.
+///
+/// Granted decisions are not stored, as they would be recorded on every request.
+pub struct DatabaseSecurityAuditSink {
+    repository: SecurityAuditLogRepository,
+}
+
+impl DatabaseSecurityAuditSink {
+    /// constructor.
+    pub fn new(repository: SecurityAuditLogRepository) -> Self {
+        Self { repository }
+    }
+}
+
+impl SecurityAuditSink for DatabaseSecurityAuditSink {
+    fn record(&self, decision: &SecurityDecision) -> Result<()> {
+        if decision.outcome == SecurityDecisionOutcome::Granted {
+            return Ok(());
+        }
+
+        self.repository.insert(NewSecurityAuditLog {
+            user_id: decision.user_id,
+            api_key_id: decision.api_key_id,
+            application_id: decision.application_id,
+            subject: &decision.subject,
+            access_right: &decision.right,
+            context: serde_json::to_value(&decision.context)?,
+            voter: decision.voter.as_deref(),
+            outcome: decision.outcome.as_str(),
+            reason: decision.reason.as_deref(),
+            created_date: decision.date,
+        })?;
+
+        Ok(())
+    }
+}
+
+/// creates the sink set by the `security_audit_sink` configuration : `log` (default), `database` or `none`.
+pub fn create_audit_sink(
+    configuration: &ConfigState,
+    repository: SecurityAuditLogRepository,
+) -> Option<Box<dyn SecurityAuditSink>> {
+    match configuration
+        .get_string_or_default("security_audit_sink", "log")
+        .as_str()
+    {
+        "none" => None,
+        "database" => Some(Box::new(DatabaseSecurityAuditSink::new(repository))),
+        _ => Some(Box::<LogSecurityAuditSink>::default()),
+    }
+}
+
+/// PRIVATE - displays an optional ID, or `-`.
+fn display_option(value: Option<i32>) -> String {
+    value
+        .map(|value| value.to_string())
+        .unwrap_or_else(|| "-".into())
+}
@@ -0,0 +1,273 @@
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::Result;
+use config::{Config, File};
+use serde::Deserialize;
+
+use super::{
+    configuration::ConfigState,
+    guards::connected_application::ConnectedApplication,
+    security::{AccessDecision, AccessError},
+};
+use crate::domain::model::user::User;
+
+/// Default policy file name, looked up without extension so either `.toml` or `.yaml` can be used.
+pub const DEFAULT_SECURITY_POLICY_FILE: &str = "security_policy";
+
+/// A rule granting a right on a subject.
+///
+/// Access is granted when the user has one of the `roles`, or owns the resource (e.g. the `owner` context key is its ID).
+/// When `tenant` is set, the resource must also belong to the user's application (e.g. the `tenant` context key is its application ID).
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PolicyRule {
+    /// roles granted the right, checked against the effective roles of the user.
+    #[serde(default)]
+    pub roles: Vec<String>,
+    /// context key holding the ID of the user owning the resource.
+    pub owner: Option<String>,
+    /// context key holding the application ID of the resource.
+    pub tenant: Option<String>,
+}
+
+/// Declarative security policies, used as a fallback for subjects no `SecurityVoter` handles.
+///
+/// Policies are read from a TOML or YAML file, such as :
+/// ```toml
+/// [book.list]
+/// roles = ["ROLE_USER"]
+///
+/// [book.update]
+/// roles = ["ROLE_USER_ADMIN"]
+/// owner = "owner_id"
+/// tenant = "application_id"
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SecurityPolicy {
+    /// rules by right, by subject.
+    subjects: BTreeMap<String, BTreeMap<String, PolicyRule>>,
+}
+
+impl SecurityPolicy {
+    /// loads the policy file set by the `security_policy_file` configuration, if it exists.
+    pub fn from_config(configuration: &ConfigState) -> Result<Self> {
+        let path = configuration
+            .get_string_or_default("security_policy_file", DEFAULT_SECURITY_POLICY_FILE);
+
+        let subjects = Config::builder()
+            .add_source(File::with_name(&path).required(false))
+            .build()?
+            .try_deserialize::<BTreeMap<String, BTreeMap<String, PolicyRule>>>()?;
+
+        Ok(Self { subjects })
+    }
+
+    /// checks if the policy defines rules for a subject.
+    pub fn supports(&self, subject: &str) -> bool {
+        self.subjects.contains_key(subject)
+    }
+
+    /// gets every subject defined by the policy, with its rights.
+    pub fn get_subjects(&self) -> Vec<(&str, Vec<&str>)> {
+        self.subjects
+            .iter()
+            .map(|(subject, rules)| {
+                (
+                    subject.as_str(),
+                    rules.keys().map(|right| right.as_str()).collect(),
+                )
+            })
+            .collect()
+    }
+
+    /// gets the rule of a right on a subject.
+    pub fn get_rule(&self, subject: &str, right: &str) -> Result<&PolicyRule, AccessError> {
+        match self
+            .subjects
+            .get(subject)
+            .and_then(|rules| rules.get(right))
+        {
+            Some(rule) => Ok(rule),
+            None => Err(AccessError::UnknownRight(subject.into(), right.into())),
+        }
+    }
+
+    /// checks if a user has access to a right on a subject.
+    pub fn has_access(
+        &self,
+        subject: &str,
+        right: &str,
+        user: &User,
+        context: Option<HashMap<&str, String>>,
+    ) -> Result<AccessDecision, AccessError> {
+        let rule = self.get_rule(subject, right)?;
+        let context = context.unwrap_or_default();
+
+        if let Some(tenant_key) = &rule.tenant {
+            if *Self::get_context(&context, tenant_key)? != user.application_id.to_string() {
+                return Ok(AccessDecision::deny(
+                    "The resource belongs to another application.",
+                ));
+            }
+        }
+
+        if rule.roles.iter().any(|role| user.roles.contains(role)) {
+            return Ok(AccessDecision::Granted);
+        }
+
+        match &rule.owner {
+            Some(owner_key) => {
+                Ok((*Self::get_context(&context, owner_key)? == user.id.to_string()).into())
+            }
+            None => Ok(AccessDecision::Denied(None)),
+        }
+    }
+
+    /// checks if an application has access to a right on a subject, e.g. its API key has a matching scope.
+    ///
+    /// The API key scope stands for the `roles` of the rule : the `tenant` is checked the same way as for users, and an application never owns a resource.
+    pub fn has_application_access(
+        &self,
+        subject: &str,
+        right: &str,
+        application: &ConnectedApplication,
+        context: Option<HashMap<&str, String>>,
+    ) -> Result<AccessDecision, AccessError> {
+        let rule = self.get_rule(subject, right)?;
+        let context = context.unwrap_or_default();
+
+        if let Some(tenant_key) = &rule.tenant {
+            if *Self::get_context(&context, tenant_key)? != application.application.id.to_string() {
+                return Ok(AccessDecision::deny(
+                    "The resource belongs to another application.",
+                ));
+            }
+        }
+
+        Ok(application.has_scope(subject, right).into())
+    }
+
+    /// PRIVATE - gets a value required by a rule from the context.
+    fn get_context<'c>(
+        context: &'c HashMap<&str, String>,
+        key: &str,
+    ) -> Result<&'c String, AccessError> {
+        context
+            .get(key)
+            .ok_or_else(|| AccessError::MissingContext(key.into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::testing::get_test_application;
+
+    fn get_policy() -> SecurityPolicy {
+        let subjects = Config::builder()
+            .add_source(File::from_str(
+                r#"
+                [book.list]
+                roles = ["ROLE_USER"]
+
+                [book.update]
+                roles = ["ROLE_USER_ADMIN"]
+                owner = "owner_id"
+                tenant = "application_id"
+                "#,
+                config::FileFormat::Toml,
+            ))
+            .build()
+            .unwrap()
+            .try_deserialize()
+            .unwrap();
+
+        SecurityPolicy { subjects }
+    }
+
+    fn get_user(id: i32, roles: &[&str]) -> User {
+        User {
+            id,
+            application_id: 1,
+            roles: roles.iter().map(|role| role.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_policy_roles_ownership_and_tenant() {
+        let policy = get_policy();
+        let user = get_user(2, &["ROLE_USER"]);
+        let admin = get_user(3, &["ROLE_USER", "ROLE_USER_ADMIN"]);
+
+        let context = |owner_id: &str, application_id: &str| {
+            Some(HashMap::from([
+                ("owner_id", owner_id.to_string()),
+                ("application_id", application_id.to_string()),
+            ]))
+        };
+
+        assert!(policy.supports("book"));
+        assert!(policy
+            .has_access("book", "list", &user, None)
+            .unwrap()
+            .is_granted());
+        assert!(policy
+            .has_access("book", "update", &user, context("2", "1"))
+            .unwrap()
+            .is_granted());
+        assert!(!policy
+            .has_access("book", "update", &user, context("3", "1"))
+            .unwrap()
+            .is_granted());
+        assert!(policy
+            .has_access("book", "update", &admin, context("2", "1"))
+            .unwrap()
+            .is_granted());
+        assert!(!policy
+            .has_access("book", "update", &admin, context("2", "2"))
+            .unwrap()
+            .is_granted());
+        assert!(policy.has_access("book", "update", &user, None).is_err());
+        assert!(policy.has_access("book", "delete", &admin, None).is_err());
+    }
+
+    #[test]
+    fn test_policy_application_scope_and_tenant() {
+        let policy = get_policy();
+        let application = get_test_application(1, &["book:*"]);
+        let other_application = get_test_application(2, &["book:*"]);
+        let unscoped_application = get_test_application(1, &["user:list"]);
+
+        let context = |application_id: &str| {
+            Some(HashMap::from([
+                ("owner_id", "2".to_string()),
+                ("application_id", application_id.to_string()),
+            ]))
+        };
+
+        assert!(policy
+            .has_application_access("book", "list", &application, None)
+            .unwrap()
+            .is_granted());
+        assert!(!policy
+            .has_application_access("book", "list", &unscoped_application, None)
+            .unwrap()
+            .is_granted());
+        assert!(policy
+            .has_application_access("book", "update", &application, context("1"))
+            .unwrap()
+            .is_granted());
+        // a key of another application is denied, whatever its scopes.
+        assert!(!policy
+            .has_application_access("book", "update", &other_application, context("1"))
+            .unwrap()
+            .is_granted());
+        assert!(!policy
+            .has_application_access("book", "update", &unscoped_application, context("1"))
+            .unwrap()
+            .is_granted());
+        assert!(policy
+            .has_application_access("book", "update", &application, None)
+            .is_err());
+    }
+}
@@ -0,0 +1,122 @@
+use std::sync::OnceLock;
+
+use chrono::Utc;
+use diesel::{Connection, PgConnection};
+use diesel_migrations::MigrationHarness;
+use ulid::Ulid;
+
+use crate::{
+    core::{
+        configuration::ConfigState,
+        database::{get_connection_pool, DbPoolState},
+        fairings::database_migrations::MIGRATIONS,
+        jwt_keys::{JWTAlgorithm, JWTKey, JWTKeyStore, JWTSigningKey},
+    },
+    domain::{
+        model::{
+            account::NewAccount,
+            application::{Application, NewApplication},
+            user::{NewUser, User},
+        },
+        repository::{
+            account_repository::AccountRepository, application_repository::ApplicationRepository,
+            user_repository::UserRepository,
+        },
+    },
+};
+
+/// environment variable holding the URL of the database tests are run against (e.g. `postgres://postgres@localhost/test`).
+pub const TEST_DATABASE_URL: &str = "TEST_DATABASE_URL";
+
+/// issuer of the tokens issued with the test configuration.
+pub const TEST_ISSUER: &str = "test-api";
+
+/// gets a configuration holding what issuing tokens requires, and cheap password hashing parameters.
+pub fn get_test_configuration() -> ConfigState {
+    ConfigState::from_values(&[
+        ("package.name", TEST_ISSUER),
+        ("password_hash_memory_cost", "64"),
+        ("password_hash_time_cost", "1"),
+        ("password_hash_parallelism", "1"),
+    ])
+}
+
+/// gets a key store holding a single in-memory signing key, so tests do not depend on the certificate directory.
+pub fn get_test_key_store() -> JWTKeyStore {
+    JWTKeyStore::from_keys(vec![(
+        JWTKey {
+            kid: Ulid::new().to_string(),
+            alg: JWTAlgorithm::ES256,
+            created_at: Utc::now(),
+            retired_at: None,
+            expires_at: None,
+        },
+        JWTSigningKey::generate(JWTAlgorithm::ES256).unwrap(),
+    )])
+}
+
+/// gets the test database, migrated once per run.
+///
+/// Tests using it are ignored by default, run them with `TEST_DATABASE_URL=... cargo test -- --ignored`.
+pub fn get_test_db_state() -> DbPoolState {
+    static DB_STATE: OnceLock<DbPoolState> = OnceLock::new();
+
+    DB_STATE
+        .get_or_init(|| {
+            let conn_url = std::env::var(TEST_DATABASE_URL).unwrap_or_else(|_| {
+                panic!("{TEST_DATABASE_URL} must be set to run database tests")
+            });
+
+            PgConnection::establish(&conn_url)
+                .unwrap()
+                .run_pending_migrations(MIGRATIONS)
+                .unwrap();
+
+            DbPoolState {
+                db_pool: get_connection_pool(conn_url).unwrap(),
+            }
+        })
+        .clone()
+}
+
+/// creates an application with its account, uniquely named so tests can share the database.
+pub fn create_test_application(db_state: &DbPoolState) -> Application {
+    let name = Ulid::new().to_string();
+
+    let account = AccountRepository::new(db_state.clone())
+        .insert(NewAccount::new(&name))
+        .unwrap();
+
+    ApplicationRepository::new(db_state.clone())
+        .insert(NewApplication::new(
+            &name,
+            &name,
+            "test@example.com",
+            account.id,
+        ))
+        .unwrap()
+}
+
+/// creates a user of an application with the given roles, uniquely named so tests can share the database.
+pub fn create_test_user(db_state: &DbPoolState, application: &Application, roles: &[&str]) -> User {
+    let login = Ulid::new().to_string();
+    let email = format!("{login}@example.com");
+
+    UserRepository::new(db_state.clone())
+        .insert(NewUser {
+            email: Some(&email),
+            first_name: None,
+            last_name: None,
+            login: &login,
+            roles: roles.to_vec(),
+            password: "",
+            salt: None,
+            application_id: application.id,
+            created_date: Utc::now(),
+            created_by: None,
+            deleted_date: None,
+            deleted_by: None,
+            is_deleted: false,
+        })
+        .unwrap()
+}
@@ -0,0 +1,198 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use data_encoding::BASE32_NOPAD;
+use hmac::{Hmac, Mac};
+use rand_core::{OsRng, RngCore};
+use sha1::Sha1;
+use sha2::{Sha256, Sha512};
+
+/// Size in bytes of generated secrets (e.g. 160 bits, as recommended by RFC 4226).
+const SECRET_SIZE: usize = 20;
+
+/// Number of digits of codes given to authenticator apps.
+pub const DIGITS: u32 = 6;
+
+/// Duration in seconds of a time step.
+pub const STEP: u64 = 30;
+
+/// Number of time steps accepted before and after the current one, to tolerate clock drift.
+pub const SKEW: u64 = 1;
+
+/// HMAC algorithms available to compute codes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TOTPAlgorithm {
+    SHA1,
+    SHA256,
+    SHA512,
+}
+
+/// Generates a random secret, base32 encoded (without padding) to be typed or scanned in authenticator apps.
+pub fn generate_secret() -> String {
+    let mut secret = [0u8; SECRET_SIZE];
+    OsRng.fill_bytes(&mut secret);
+
+    BASE32_NOPAD.encode(&secret)
+}
+
+/// Decodes a base32 secret into raw bytes.
+pub fn decode_secret(secret: &str) -> Result<Vec<u8>> {
+    let secret = BASE32_NOPAD
+        .decode(secret.trim_end_matches('=').as_bytes())
+        .with_context(|| "Cannot decode TOTP secret")?;
+
+    Ok(secret)
+}
+
+/// Computes the time step of a given date.
+pub fn get_step(date: DateTime<Utc>) -> u64 {
+    date.timestamp().max(0) as u64 / STEP
+}
+
+/// Computes a HOTP code (RFC 4226) for a given counter, e.g. a TOTP code (RFC 6238) when the counter is a time step.
+pub fn generate_code(secret: &[u8], counter: u64, digits: u32, algorithm: TOTPAlgorithm) -> String {
+    let message = counter.to_be_bytes();
+
+    let digest = match algorithm {
+        TOTPAlgorithm::SHA1 => sign::<Hmac<Sha1>>(secret, &message),
+        TOTPAlgorithm::SHA256 => sign::<Hmac<Sha256>>(secret, &message),
+        TOTPAlgorithm::SHA512 => sign::<Hmac<Sha512>>(secret, &message),
+    };
+
+    // dynamic truncation
+    let offset = (digest[digest.len() - 1] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([
+        digest[offset] & 0x7f,
+        digest[offset + 1],
+        digest[offset + 2],
+        digest[offset + 3],
+    ]);
+
+    format!(
+        "{:0width$}",
+        binary % 10u32.pow(digits),
+        width = digits as usize
+    )
+}
+
+/// Verifies a code given by a user at a given date, tolerating a clock drift of `SKEW` steps.
+///
+/// Returns the matching time step, so callers can refuse a code from an already used step.
+pub fn verify(secret: &str, code: &str, date: DateTime<Utc>) -> Option<u64> {
+    let secret = decode_secret(secret).ok()?;
+    let code = code.trim();
+
+    let current_step = get_step(date);
+
+    (current_step.saturating_sub(SKEW)..=current_step + SKEW).find(|step| {
+        let expected = generate_code(&secret, *step, DIGITS, TOTPAlgorithm::SHA1);
+
+        constant_time_eq(expected.as_bytes(), code.as_bytes())
+    })
+}
+
+/// Builds the `otpauth://` URI of a secret, to be displayed as a QR code for authenticator apps.
+pub fn get_otpauth_uri(issuer: &str, account: &str, secret: &str) -> String {
+    let issuer = encode_uri_component(issuer);
+    let account = encode_uri_component(account);
+
+    format!(
+        "otpauth://totp/{issuer}:{account}?secret={secret}&issuer={issuer}&algorithm=SHA1&digits={DIGITS}&period={STEP}"
+    )
+}
+
+/// PRIVATE - computes the HMAC of a message.
+fn sign<M: Mac + hmac::digest::KeyInit>(secret: &[u8], message: &[u8]) -> Vec<u8> {
+    let mut mac = <M as hmac::digest::KeyInit>::new_from_slice(secret)
+        .expect("HMAC accepts keys of any size");
+    mac.update(message);
+
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// PRIVATE - compares two byte slices in constant time.
+fn constant_time_eq(left: &[u8], right: &[u8]) -> bool {
+    if left.len() != right.len() {
+        return false;
+    }
+
+    left.iter()
+        .zip(right.iter())
+        .fold(0u8, |acc, (l, r)| acc | (l ^ r))
+        == 0
+}
+
+/// PRIVATE - percent-encodes a URI component (RFC 3986 unreserved chars are kept).
+fn encode_uri_component(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{byte:02X}"),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    const SEED_SHA1: &[u8] = b"12345678901234567890";
+    const SEED_SHA256: &[u8] = b"12345678901234567890123456789012";
+    const SEED_SHA512: &[u8] = b"1234567890123456789012345678901234567890123456789012345678901234";
+
+    /// RFC 6238, appendix B : (time, TOTP SHA1, TOTP SHA256, TOTP SHA512)
+    const RFC_6238_VECTORS: [(i64, &str, &str, &str); 6] = [
+        (59, "94287082", "46119246", "90693936"),
+        (1111111109, "07081804", "68084774", "25091201"),
+        (1111111111, "14050471", "67062674", "99943326"),
+        (1234567890, "89005924", "91819424", "93441116"),
+        (2000000000, "69279037", "90698825", "38618901"),
+        (20000000000, "65353130", "77737706", "47863826"),
+    ];
+
+    #[test]
+    fn test_rfc_6238_vectors() {
+        for (time, sha1, sha256, sha512) in RFC_6238_VECTORS {
+            let step = get_step(Utc.timestamp_opt(time, 0).unwrap());
+
+            assert_eq!(generate_code(SEED_SHA1, step, 8, TOTPAlgorithm::SHA1), sha1);
+            assert_eq!(
+                generate_code(SEED_SHA256, step, 8, TOTPAlgorithm::SHA256),
+                sha256
+            );
+            assert_eq!(
+                generate_code(SEED_SHA512, step, 8, TOTPAlgorithm::SHA512),
+                sha512
+            );
+        }
+    }
+
+    #[test]
+    fn test_verify_tolerates_one_step_of_drift() {
+        let secret = BASE32_NOPAD.encode(SEED_SHA1);
+        let date = Utc.timestamp_opt(1111111109, 0).unwrap();
+        let step = get_step(date);
+
+        let code = |step: u64| generate_code(SEED_SHA1, step, DIGITS, TOTPAlgorithm::SHA1);
+
+        assert_eq!(verify(&secret, &code(step), date), Some(step));
+        assert_eq!(verify(&secret, &code(step - 1), date), Some(step - 1));
+        assert_eq!(verify(&secret, &code(step + 1), date), Some(step + 1));
+        assert_eq!(verify(&secret, &code(step + 2), date), None);
+        assert_eq!(verify(&secret, "12345", date), None);
+    }
+
+    #[test]
+    fn test_generated_secret_round_trip() {
+        let secret = generate_secret();
+
+        assert_eq!(decode_secret(&secret).unwrap().len(), SECRET_SIZE);
+        assert_eq!(
+            get_otpauth_uri("my api", "john@doe.com", &secret),
+            format!("otpauth://totp/my%20api:john%40doe.com?secret={secret}&issuer=my%20api&algorithm=SHA1&digits=6&period=30")
+        );
+    }
+}
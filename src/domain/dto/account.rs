@@ -1,9 +1,12 @@
 use chrono::{DateTime, Utc};
-use serde::Serialize;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
 
+use crate::core::guards::list_query::{FieldKind, ListFields};
 use crate::domain::model::account::Account;
 
-#[derive(Serialize, Default)]
+#[derive(Serialize, Default, JsonSchema)]
 #[serde(crate = "rocket::serde")]
 pub struct AccountListItemDTO {
     pub id: i32,
@@ -21,7 +24,16 @@ impl From<&Account> for AccountListItemDTO {
     }
 }
 
-#[derive(Serialize, Default)]
+impl ListFields for AccountListItemDTO {
+    const FILTERABLE: &'static [(&'static str, FieldKind)] = &[
+        ("id", FieldKind::Integer),
+        ("name", FieldKind::Text),
+        ("created_date", FieldKind::Date),
+    ];
+    const SORTABLE: &'static [&'static str] = &["id", "name", "created_date"];
+}
+
+#[derive(Serialize, Default, JsonSchema)]
 #[serde(crate = "rocket::serde")]
 pub struct AccountDetailsDTO {
     pub id: i32,
@@ -38,3 +50,17 @@ impl From<&Account> for AccountDetailsDTO {
         }
     }
 }
+
+#[derive(Serialize, Deserialize, Validate, JsonSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct NewAccountInputDTO {
+    #[validate(length(min = 1, max = 255))]
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize, Validate, JsonSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct UpdateAccountInputDTO {
+    #[validate(length(min = 1, max = 255))]
+    pub name: String,
+}
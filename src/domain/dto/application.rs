@@ -1,9 +1,12 @@
 use chrono::{DateTime, Utc};
-use serde::Serialize;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
 
+use crate::core::guards::list_query::{FieldKind, ListFields};
 use crate::domain::model::application::Application;
 
-#[derive(Serialize, Default)]
+#[derive(Serialize, Default, JsonSchema)]
 #[serde(crate = "rocket::serde")]
 pub struct ApplicationListItemDTO {
     pub id: i32,
@@ -21,7 +24,16 @@ impl From<&Application> for ApplicationListItemDTO {
     }
 }
 
-#[derive(Serialize, Default)]
+impl ListFields for ApplicationListItemDTO {
+    const FILTERABLE: &'static [(&'static str, FieldKind)] = &[
+        ("id", FieldKind::Integer),
+        ("name", FieldKind::Text),
+        ("created_date", FieldKind::Date),
+    ];
+    const SORTABLE: &'static [&'static str] = &["id", "name", "created_date"];
+}
+
+#[derive(Serialize, Default, JsonSchema)]
 #[serde(crate = "rocket::serde")]
 pub struct ApplicationDetailsDTO {
     pub id: i32,
@@ -44,3 +56,22 @@ impl From<&Application> for ApplicationDetailsDTO {
         }
     }
 }
+
+#[derive(Serialize, Deserialize, Validate, JsonSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct NewApplicationInputDTO {
+    #[validate(length(min = 1, max = 255))]
+    pub name: String,
+    #[validate(email, length(min = 1, max = 255))]
+    pub contact_email: String,
+    pub account_id: i32,
+}
+
+#[derive(Serialize, Deserialize, Validate, JsonSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct UpdateApplicationInputDTO {
+    #[validate(length(min = 1, max = 255))]
+    pub name: String,
+    #[validate(email, length(min = 1, max = 255))]
+    pub contact_email: String,
+}
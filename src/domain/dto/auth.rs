@@ -1,14 +1,52 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use validator::Validate;
 
-#[derive(Serialize, Default)]
+use super::user::validate_password;
+
+#[derive(Serialize, Default, JsonSchema)]
 #[serde(crate = "rocket::serde")]
 pub struct JWTTokenOutputDTO {
     pub token: String,
     pub refresh_token: String,
 }
 
-#[derive(Serialize, Deserialize, Validate)]
+/// Output of an impersonation: a short-lived token, without refresh token.
+#[derive(Serialize, JsonSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct ImpersonationOutputDTO {
+    pub token: String,
+    pub expiration_date: DateTime<Utc>,
+    pub impersonator_id: i32,
+}
+
+#[derive(Serialize, Deserialize, Validate, JsonSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct ImpersonationInputDTO {
+    /// why the user is impersonated (e.g. a support ticket), recorded in the impersonation log.
+    #[serde(default)]
+    #[validate(length(min = 1, max = 255))]
+    pub reason: Option<String>,
+}
+
+#[derive(Serialize, JsonSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct MFAChallengeOutputDTO {
+    pub mfa_required: bool,
+    pub challenge: String,
+    pub expiration_date: DateTime<Utc>,
+}
+
+/// Output of a login: either tokens, or a challenge when the user enabled two-factor authentication.
+#[derive(Serialize, JsonSchema)]
+#[serde(crate = "rocket::serde", untagged)]
+pub enum LoginOutputDTO {
+    Token(JWTTokenOutputDTO),
+    MFAChallenge(MFAChallengeOutputDTO),
+}
+
+#[derive(Serialize, Deserialize, Validate, JsonSchema)]
 #[serde(crate = "rocket::serde")]
 pub struct LoginInputDTO {
     #[validate(length(min = 1))]
@@ -17,9 +55,52 @@ pub struct LoginInputDTO {
     pub password: String,
 }
 
-#[derive(Serialize, Deserialize, Validate)]
+#[derive(Serialize, Deserialize, Validate, JsonSchema)]
 #[serde(crate = "rocket::serde")]
 pub struct RefreshTokenInputDTO {
     #[validate(length(equal = 128))]
     pub refresh_token: String,
 }
+
+#[derive(Serialize, Deserialize, Validate, JsonSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct DelegationInputDTO {
+    /// the OAuth client allowed to act on behalf of the user.
+    #[validate(length(equal = 26))]
+    pub client_id: String,
+}
+
+/// Output of a delegation: a refresh token only accepted from the client, through the `refresh_token` grant.
+#[derive(Serialize, JsonSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct DelegationOutputDTO {
+    pub client_id: String,
+    pub refresh_token: String,
+}
+
+#[derive(Serialize, Deserialize, Validate, JsonSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct PasswordResetRequestInputDTO {
+    /// the login or the email of the user.
+    #[validate(length(min = 1, max = 180))]
+    pub login: String,
+}
+
+#[derive(Serialize, Deserialize, Validate, JsonSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct PasswordResetConfirmInputDTO {
+    #[validate(length(equal = 64))]
+    pub token: String,
+    #[validate(length(min = 1, max = 255), custom = "validate_password")]
+    pub password: String,
+}
+
+#[derive(Serialize, Deserialize, Validate, JsonSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct MFALoginInputDTO {
+    #[validate(length(equal = 64))]
+    pub challenge: String,
+    /// a TOTP code or a recovery code.
+    #[validate(length(min = 1, max = 64))]
+    pub code: String,
+}
@@ -2,4 +2,8 @@ pub mod account;
 pub mod application;
 pub mod auth;
 pub mod cron_log;
+pub mod oauth;
+pub mod role;
+pub mod totp;
 pub mod user;
+pub mod well_known;
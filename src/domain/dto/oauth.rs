@@ -0,0 +1,74 @@
+// `FromForm` derived code allows a lint removed by recent toolchains.
+#![allow(renamed_and_removed_lints)]
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+/// OAuth2 token request (RFC 6749, sections 4.4 and 6), sent as `application/x-www-form-urlencoded`.
+#[derive(FromForm, JsonSchema)]
+pub struct OAuthTokenInputDTO {
+    pub grant_type: Option<String>,
+    /// space separated list of requested scopes.
+    pub scope: Option<String>,
+    /// the refresh token, for the `refresh_token` grant.
+    pub refresh_token: Option<String>,
+    /// the client ID, when not sent with HTTP Basic authentication.
+    pub client_id: Option<String>,
+    /// the client secret, when not sent with HTTP Basic authentication.
+    pub client_secret: Option<String>,
+}
+
+/// OAuth2 introspection (RFC 7662) and revocation (RFC 7009) request, sent as `application/x-www-form-urlencoded`.
+#[derive(FromForm, JsonSchema)]
+pub struct OAuthTokenActionInputDTO {
+    pub token: Option<String>,
+    pub token_type_hint: Option<String>,
+    /// the client ID, when not sent with HTTP Basic authentication.
+    pub client_id: Option<String>,
+    /// the client secret, when not sent with HTTP Basic authentication.
+    pub client_secret: Option<String>,
+}
+
+#[derive(Serialize, Default, JsonSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct OAuthTokenOutputDTO {
+    pub access_token: String,
+    pub token_type: String,
+    pub expires_in: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>,
+    pub scope: String,
+}
+
+#[derive(Serialize, Default, JsonSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct OAuthIntrospectionOutputDTO {
+    pub active: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exp: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub iat: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sub: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub iss: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jti: Option<String>,
+}
+
+/// OAuth2 error response (RFC 6749, section 5.2).
+#[derive(Serialize, Default, JsonSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct OAuthErrorOutputDTO {
+    pub error: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_description: Option<String>,
+}
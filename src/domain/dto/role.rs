@@ -0,0 +1,61 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use validator::{Validate, ValidationError};
+
+use crate::domain::model::role::Role;
+
+#[derive(Serialize, Default, JsonSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct RoleDTO {
+    pub name: String,
+    pub implied_roles: Vec<String>,
+    pub description: Option<String>,
+    pub is_builtin: bool,
+}
+
+impl From<Role> for RoleDTO {
+    fn from(value: Role) -> Self {
+        Self {
+            name: value.name,
+            implied_roles: value.implied_roles,
+            description: value.description,
+            is_builtin: false,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Validate, JsonSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct NewRoleInputDTO {
+    #[validate(length(min = 6, max = 100), custom = "validate_role_name")]
+    pub name: String,
+    #[serde(default)]
+    pub implied_roles: Vec<String>,
+    #[validate(length(max = 255))]
+    pub description: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Validate, JsonSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct RoleAssignmentInputDTO {
+    #[validate(length(min = 6, max = 100), custom = "validate_role_name")]
+    pub role: String,
+}
+
+/// role names are upper-cased and prefixed, like `ROLE_SUPPORT`.
+pub fn validate_role_name(name: &str) -> Result<(), ValidationError> {
+    let is_valid = name.strip_prefix("ROLE_").is_some_and(|suffix| {
+        !suffix.is_empty()
+            && suffix
+                .chars()
+                .all(|char| char.is_ascii_uppercase() || char.is_ascii_digit() || char == '_')
+    });
+
+    if !is_valid {
+        return Err(ValidationError::new(
+            "Role name must start with ROLE_ and only contain capital letters, numbers and underscores.",
+        ));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,23 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+#[derive(Serialize, Default, JsonSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct TOTPEnrolmentOutputDTO {
+    pub secret: String,
+    pub otpauth_uri: String,
+}
+
+#[derive(Serialize, Default, JsonSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct RecoveryCodesOutputDTO {
+    pub recovery_codes: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Validate, JsonSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct TOTPCodeInputDTO {
+    #[validate(length(min = 1, max = 64))]
+    pub code: String,
+}
@@ -1,10 +1,20 @@
 use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use validator::{Validate, ValidationError};
 
-use crate::{core::password, domain::model::user::User};
+use crate::{
+    core::{
+        guards::list_query::{FieldKind, ListFields},
+        password,
+    },
+    domain::{
+        dto::{account::AccountDetailsDTO, application::ApplicationDetailsDTO},
+        model::user::User,
+    },
+};
 
-#[derive(Serialize, Default)]
+#[derive(Serialize, Default, JsonSchema)]
 #[serde(crate = "rocket::serde")]
 pub struct UserListItemDTO {
     pub id: i32,
@@ -26,7 +36,17 @@ impl From<&User> for UserListItemDTO {
     }
 }
 
-#[derive(Serialize, Default)]
+impl ListFields for UserListItemDTO {
+    const FILTERABLE: &'static [(&'static str, FieldKind)] = &[
+        ("id", FieldKind::Integer),
+        ("login", FieldKind::Text),
+        ("email", FieldKind::Text),
+        ("created_date", FieldKind::Date),
+    ];
+    const SORTABLE: &'static [&'static str] = &["id", "login", "email", "created_date"];
+}
+
+#[derive(Serialize, Default, JsonSchema)]
 #[serde(crate = "rocket::serde")]
 pub struct UserDetailsDTO {
     pub id: i32,
@@ -54,7 +74,22 @@ impl From<&User> for UserDetailsDTO {
     }
 }
 
-#[derive(Serialize, Deserialize, Validate)]
+/// The connected user, with its effective roles, its organization and what it is granted.
+#[derive(Serialize, JsonSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct CurrentUserDTO {
+    #[serde(flatten)]
+    pub user: UserDetailsDTO,
+    pub application: ApplicationDetailsDTO,
+    pub account: AccountDetailsDTO,
+    /// rights granted regardless of any resource, as `subject:right`.
+    pub permissions: Vec<String>,
+    /// the administrator impersonating the user, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub impersonator_id: Option<i32>,
+}
+
+#[derive(Serialize, Deserialize, Validate, JsonSchema)]
 #[serde(crate = "rocket::serde")]
 pub struct NewUserInputDTO {
     #[validate(length(min = 1, max = 200))]
@@ -69,7 +104,7 @@ pub struct NewUserInputDTO {
     pub password: String,
 }
 
-#[derive(Serialize, Deserialize, Validate)]
+#[derive(Serialize, Deserialize, Validate, JsonSchema)]
 #[serde(crate = "rocket::serde")]
 pub struct UpdateUserInputDTO {
     #[validate(length(min = 1, max = 200))]
@@ -80,7 +115,28 @@ pub struct UpdateUserInputDTO {
     pub email: String,
 }
 
-fn validate_password(password: &str) -> Result<(), ValidationError> {
+/// Self-service profile edits: same rules as `UpdateUserInputDTO`, every field being optional.
+#[derive(Serialize, Deserialize, Validate, JsonSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct UpdateProfileInputDTO {
+    #[validate(length(min = 1, max = 200))]
+    pub first_name: Option<String>,
+    #[validate(length(min = 1, max = 200))]
+    pub last_name: Option<String>,
+    #[validate(email, length(min = 1, max = 180))]
+    pub email: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Validate, JsonSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct UpdatePasswordInputDTO {
+    #[validate(length(min = 1, max = 255))]
+    pub current_password: String,
+    #[validate(length(min = 1, max = 255), custom = "validate_password")]
+    pub password: String,
+}
+
+pub fn validate_password(password: &str) -> Result<(), ValidationError> {
     if !password::is_strong(password.into()) {
         return Err(ValidationError::new("Password is not strong enough, it must contain at least 8 char, a capital letter, a number and a special symbol."));
     }
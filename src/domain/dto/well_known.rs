@@ -0,0 +1,25 @@
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::core::jwt::JWK;
+
+#[derive(Serialize, Default, JsonSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct JWKSetOutputDTO {
+    pub keys: Vec<JWK>,
+}
+
+#[derive(Serialize, Default, JsonSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct OpenIDConfigurationOutputDTO {
+    pub issuer: String,
+    pub jwks_uri: String,
+    pub token_endpoint: String,
+    pub introspection_endpoint: String,
+    pub revocation_endpoint: String,
+    pub grant_types_supported: Vec<String>,
+    pub token_endpoint_auth_methods_supported: Vec<String>,
+    pub response_types_supported: Vec<String>,
+    pub subject_types_supported: Vec<String>,
+    pub id_token_signing_alg_values_supported: Vec<String>,
+}
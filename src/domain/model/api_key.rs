@@ -0,0 +1,42 @@
+use crate::core::security;
+use crate::domain::model::application::Application;
+use crate::domain::schema::*;
+use chrono::{DateTime, Utc};
+use diesel::{Associations, Identifiable, Insertable, Queryable};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Queryable, Identifiable, Associations, Serialize, Deserialize, Clone)]
+#[diesel(table_name = api_key)]
+#[diesel(belongs_to(Application))]
+pub struct ApiKey {
+    pub id: i32,
+    pub application_id: i32,
+    pub name: String,
+    pub prefix: String,
+    pub key_hash: String,
+    pub scopes: Vec<String>,
+    pub expiration_date: Option<DateTime<Utc>>,
+    pub last_used_date: Option<DateTime<Utc>>,
+    pub is_revoked: bool,
+    pub revoked_date: Option<DateTime<Utc>>,
+    pub created_date: DateTime<Utc>,
+}
+
+impl ApiKey {
+    /// checks if the key is granted a right on a subject, with either `subject:right`, `subject:*` or `*` scopes.
+    pub fn has_scope(&self, subject: &str, right: &str) -> bool {
+        security::is_scope_granted(&self.scopes, &format!("{subject}:{right}"))
+    }
+}
+
+#[derive(Debug, Deserialize, Insertable)]
+#[diesel(table_name = api_key)]
+pub struct NewApiKey<'a> {
+    pub application_id: i32,
+    pub name: &'a str,
+    pub prefix: &'a str,
+    pub key_hash: &'a str,
+    pub scopes: Vec<&'a str>,
+    pub expiration_date: Option<DateTime<Utc>>,
+    pub created_date: DateTime<Utc>,
+}
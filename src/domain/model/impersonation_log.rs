@@ -0,0 +1,30 @@
+use crate::domain::schema::*;
+use chrono::{DateTime, Utc};
+use diesel::{Identifiable, Insertable, Queryable};
+use serde::{Deserialize, Serialize};
+
+/// A recorded impersonation, e.g. a token issued to an administrator to act as another user.
+#[derive(Debug, Queryable, Identifiable, Serialize, Deserialize, Clone)]
+#[diesel(table_name = impersonation_log)]
+pub struct ImpersonationLog {
+    pub id: i32,
+    pub impersonator_id: i32,
+    pub impersonated_id: i32,
+    pub application_id: i32,
+    pub jti: String,
+    pub reason: Option<String>,
+    pub expiration_date: DateTime<Utc>,
+    pub created_date: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Insertable)]
+#[diesel(table_name = impersonation_log)]
+pub struct NewImpersonationLog<'a> {
+    pub impersonator_id: i32,
+    pub impersonated_id: i32,
+    pub application_id: i32,
+    pub jti: &'a str,
+    pub reason: Option<&'a str>,
+    pub expiration_date: DateTime<Utc>,
+    pub created_date: DateTime<Utc>,
+}
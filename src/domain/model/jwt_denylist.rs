@@ -0,0 +1,25 @@
+use crate::domain::model::user::User;
+use crate::domain::schema::*;
+use chrono::{DateTime, Utc};
+use diesel::{Associations, Identifiable, Insertable, Queryable};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Queryable, Identifiable, Associations, Serialize, Deserialize, Clone)]
+#[diesel(table_name = jwt_denylist)]
+#[diesel(belongs_to(User))]
+pub struct JWTDenylistEntry {
+    pub id: i32,
+    pub jti: String,
+    pub user_id: Option<i32>,
+    pub expiration_date: DateTime<Utc>,
+    pub created_date: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Insertable)]
+#[diesel(table_name = jwt_denylist)]
+pub struct NewJWTDenylistEntry<'a> {
+    pub jti: &'a str,
+    pub user_id: Option<i32>,
+    pub expiration_date: DateTime<Utc>,
+    pub created_date: DateTime<Utc>,
+}
@@ -0,0 +1,29 @@
+use crate::domain::schema::*;
+use chrono::{DateTime, Utc};
+use diesel::{AsChangeset, Identifiable, Insertable, Queryable};
+use serde::{Deserialize, Serialize};
+
+/// scope of throttle entries tracking a login.
+pub const SCOPE_LOGIN: &str = "login";
+/// scope of throttle entries tracking a client IP.
+pub const SCOPE_IP: &str = "ip";
+
+#[derive(Debug, Queryable, Identifiable, Serialize, Deserialize, Clone, AsChangeset)]
+#[diesel(table_name = login_throttle)]
+pub struct LoginThrottle {
+    pub id: i32,
+    pub scope: String,
+    pub identifier: String,
+    pub failure_count: i32,
+    pub last_failure_date: DateTime<Utc>,
+    pub locked_until: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize, Insertable)]
+#[diesel(table_name = login_throttle)]
+pub struct NewLoginThrottle<'a> {
+    pub scope: &'a str,
+    pub identifier: &'a str,
+    pub failure_count: i32,
+    pub last_failure_date: DateTime<Utc>,
+}
@@ -0,0 +1,27 @@
+use crate::domain::model::user::User;
+use crate::domain::schema::*;
+use chrono::{DateTime, Utc};
+use diesel::{Associations, Identifiable, Insertable, Queryable};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Queryable, Identifiable, Associations, Serialize, Deserialize, Clone)]
+#[diesel(table_name = mfa_challenge)]
+#[diesel(belongs_to(User))]
+pub struct MFAChallenge {
+    pub id: i32,
+    pub token_hash: String,
+    pub user_id: i32,
+    pub expiration_date: DateTime<Utc>,
+    pub attempts: i32,
+    pub is_used: bool,
+    pub created_date: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Insertable)]
+#[diesel(table_name = mfa_challenge)]
+pub struct NewMFAChallenge<'a> {
+    pub token_hash: &'a str,
+    pub user_id: i32,
+    pub expiration_date: DateTime<Utc>,
+    pub created_date: DateTime<Utc>,
+}
@@ -1,5 +1,16 @@
 pub mod account;
+pub mod api_key;
 pub mod application;
 pub mod cron_log;
+pub mod impersonation_log;
+pub mod jwt_denylist;
+pub mod login_throttle;
+pub mod mfa_challenge;
+pub mod oauth_client;
+pub mod password_reset_token;
+pub mod recovery_code;
 pub mod refresh_token;
+pub mod role;
+pub mod security_audit_log;
 pub mod user;
+pub mod user_totp;
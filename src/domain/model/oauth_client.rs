@@ -0,0 +1,31 @@
+use crate::domain::model::application::Application;
+use crate::domain::schema::*;
+use chrono::{DateTime, Utc};
+use diesel::{Associations, Identifiable, Insertable, Queryable};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Queryable, Identifiable, Associations, Serialize, Deserialize, Clone)]
+#[diesel(table_name = oauth_client)]
+#[diesel(belongs_to(Application))]
+pub struct OAuthClient {
+    pub id: i32,
+    pub application_id: i32,
+    pub name: String,
+    pub client_id: String,
+    pub client_secret_hash: String,
+    pub scopes: Vec<String>,
+    pub is_revoked: bool,
+    pub revoked_date: Option<DateTime<Utc>>,
+    pub created_date: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Insertable)]
+#[diesel(table_name = oauth_client)]
+pub struct NewOAuthClient<'a> {
+    pub application_id: i32,
+    pub name: &'a str,
+    pub client_id: &'a str,
+    pub client_secret_hash: &'a str,
+    pub scopes: Vec<&'a str>,
+    pub created_date: DateTime<Utc>,
+}
@@ -0,0 +1,25 @@
+use crate::domain::model::user::User;
+use crate::domain::schema::*;
+use chrono::{DateTime, Utc};
+use diesel::{Associations, Identifiable, Insertable, Queryable};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Queryable, Identifiable, Associations, Serialize, Deserialize, Clone)]
+#[diesel(table_name = recovery_code)]
+#[diesel(belongs_to(User))]
+pub struct RecoveryCode {
+    pub id: i32,
+    pub user_id: i32,
+    pub code_hash: String,
+    pub is_used: bool,
+    pub used_date: Option<DateTime<Utc>>,
+    pub created_date: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Insertable)]
+#[diesel(table_name = recovery_code)]
+pub struct NewRecoveryCode {
+    pub user_id: i32,
+    pub code_hash: String,
+    pub created_date: DateTime<Utc>,
+}
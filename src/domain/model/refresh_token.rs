@@ -13,6 +13,13 @@ pub struct RefreshToken {
     pub token: String,
     pub user_id: i32,
     pub validity_date: DateTime<Utc>,
+    pub family: String,
+    pub is_used: bool,
+    pub used_date: Option<DateTime<Utc>>,
+    pub replaced_by: Option<i32>,
+    pub is_revoked: bool,
+    pub revoked_date: Option<DateTime<Utc>>,
+    pub client_id: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Insertable)]
@@ -21,4 +28,6 @@ pub struct NewRefreshToken<'a> {
     pub token: &'a str,
     pub user_id: i32,
     pub validity_date: DateTime<Utc>,
+    pub family: &'a str,
+    pub client_id: Option<&'a str>,
 }
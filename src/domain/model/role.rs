@@ -0,0 +1,28 @@
+use crate::domain::model::application::Application;
+use crate::domain::schema::*;
+use chrono::{DateTime, Utc};
+use diesel::{Associations, Identifiable, Insertable, Queryable};
+use serde::{Deserialize, Serialize};
+
+/// A custom role, defined by an application in addition to built-in roles.
+#[derive(Debug, Queryable, Identifiable, Associations, Serialize, Deserialize, Clone, Default)]
+#[diesel(table_name = role)]
+#[diesel(belongs_to(Application))]
+pub struct Role {
+    pub id: i32,
+    pub application_id: i32,
+    pub name: String,
+    pub implied_roles: Vec<String>,
+    pub description: Option<String>,
+    pub created_date: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Insertable)]
+#[diesel(table_name = role)]
+pub struct NewRole<'a> {
+    pub application_id: i32,
+    pub name: &'a str,
+    pub implied_roles: Vec<&'a str>,
+    pub description: Option<&'a str>,
+    pub created_date: DateTime<Utc>,
+}
@@ -0,0 +1,36 @@
+use crate::domain::schema::*;
+use chrono::{DateTime, Utc};
+use diesel::{Identifiable, Insertable, Queryable};
+use serde::{Deserialize, Serialize};
+
+/// A recorded security decision (e.g. an access denied by a voter).
+#[derive(Debug, Queryable, Identifiable, Serialize, Deserialize, Clone)]
+#[diesel(table_name = security_audit_log)]
+pub struct SecurityAuditLog {
+    pub id: i32,
+    pub user_id: Option<i32>,
+    pub api_key_id: Option<i32>,
+    pub application_id: i32,
+    pub subject: String,
+    pub access_right: String,
+    pub context: serde_json::Value,
+    pub voter: Option<String>,
+    pub outcome: String,
+    pub reason: Option<String>,
+    pub created_date: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Insertable)]
+#[diesel(table_name = security_audit_log)]
+pub struct NewSecurityAuditLog<'a> {
+    pub user_id: Option<i32>,
+    pub api_key_id: Option<i32>,
+    pub application_id: i32,
+    pub subject: &'a str,
+    pub access_right: &'a str,
+    pub context: serde_json::Value,
+    pub voter: Option<&'a str>,
+    pub outcome: &'a str,
+    pub reason: Option<&'a str>,
+    pub created_date: DateTime<Utc>,
+}
@@ -7,6 +7,7 @@ use super::application::Application;
 
 pub const ROLE_USER: &str = "ROLE_USER";
 pub const ROLE_USER_ADMIN: &str = "ROLE_USER_ADMIN";
+pub const ROLE_SUPER_ADMIN: &str = "ROLE_SUPER_ADMIN";
 
 #[derive(
     Debug,
@@ -27,6 +28,8 @@ pub struct User {
     pub first_name: Option<String>,
     pub last_name: Option<String>,
     pub login: String,
+    /// roles are only written through role assignment (see `UserRepository::update_roles`).
+    #[diesel(skip_update)]
     pub roles: Vec<String>,
     pub password: String,
     pub salt: Option<String>,
@@ -36,6 +39,9 @@ pub struct User {
     pub deleted_date: Option<DateTime<Utc>>,
     pub deleted_by: Option<i32>,
     pub is_deleted: bool,
+    /// JWTs issued until this date are refused, it is only written by `UserRepository::revoke_tokens`.
+    #[diesel(skip_update)]
+    pub tokens_revoked_date: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Deserialize, Insertable, Clone)]
@@ -0,0 +1,27 @@
+use crate::domain::model::user::User;
+use crate::domain::schema::*;
+use chrono::{DateTime, Utc};
+use diesel::{Associations, Identifiable, Insertable, Queryable};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Queryable, Identifiable, Associations, Serialize, Deserialize, Clone)]
+#[diesel(table_name = user_totp)]
+#[diesel(belongs_to(User))]
+pub struct UserTOTP {
+    pub id: i32,
+    pub user_id: i32,
+    /// the shared secret, encrypted with `SecretCipher`.
+    pub secret: String,
+    pub is_enabled: bool,
+    pub enabled_date: Option<DateTime<Utc>>,
+    pub last_used_step: Option<i64>,
+    pub created_date: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Insertable)]
+#[diesel(table_name = user_totp)]
+pub struct NewUserTOTP<'a> {
+    pub user_id: i32,
+    pub secret: &'a str,
+    pub created_date: DateTime<Utc>,
+}
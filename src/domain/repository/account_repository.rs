@@ -1,17 +1,27 @@
-use diesel::{prelude::*, sql_query, sql_types::Integer};
+use chrono::{DateTime, Utc};
+use diesel::{pg::Pg, prelude::*, sql_query, sql_types::Integer};
 
 use crate::{
-    core::database::{DbPoolState, DB},
+    core::{
+        database::{DbPoolState, DB},
+        guards::list_query::ListQuery,
+        security::is_super_admin,
+    },
     domain::{
+        dto::account::AccountListItemDTO,
         model::{
             account::{Account, NewAccount},
             user::User,
         },
-        schema::account::{self, id, is_deleted, name},
+        schema::{
+            account::{self, created_date, id, is_deleted, name},
+            application,
+        },
     },
+    filter_by, sort_by,
 };
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 
 #[derive(Clone)]
 pub struct AccountRepository {
@@ -45,6 +55,24 @@ impl AccountRepository {
         Ok(account)
     }
 
+    /// finds an account by its ID, even if it is deleted (e.g. to restore it).
+    pub fn find_one_by_id_including_deleted(&self, account_id: i32) -> Result<Option<Account>> {
+        let account = account::table
+            .filter(id.eq(account_id))
+            .get_result::<Account>(&mut self.get_db())
+            .optional()?;
+
+        Ok(account)
+    }
+
+    pub fn update(&self, updated_account: &Account) -> Result<Account> {
+        let updated = diesel::update(updated_account)
+            .set(updated_account)
+            .get_result(&mut self.get_db())?;
+
+        Ok(updated)
+    }
+
     pub fn find_one_by_name(&self, account_name: &str) -> Result<Option<Account>> {
         let account = account::table
             .filter(name.eq(account_name))
@@ -55,29 +83,43 @@ impl AccountRepository {
         Ok(account)
     }
 
-    pub fn find_all_for_user(&self, user: &User, page: i32, per_page: i32) -> Result<Vec<Account>> {
+    pub fn find_all_for_user(
+        &self,
+        user: &User,
+        list_query: &ListQuery<AccountListItemDTO>,
+        page: i32,
+        per_page: i32,
+    ) -> Result<Vec<Account>> {
         let offset = (page - 1) * per_page;
 
-        let accounts = sql_query(
-            "
-            SELECT *
-            FROM account ac
-            INNER JOIN application app ON app.account_id = ac.id AND app.id = $1 AND app.is_deleted = false
-            WHERE ac.is_deleted = false
-            ORDER BY ac.created_date ASC
-            LIMIT $2
-            OFFSET $3
-        ",
-        )
-        .bind::<Integer, _>(user.id)
-        .bind::<Integer, _>(per_page)
-        .bind::<Integer, _>(offset)
-        .load(&mut self.get_db())?;
+        let accounts = Self::sort_listing(Self::get_listing_query(user, list_query)?, list_query)?
+            .then_order_by((created_date.asc(), id.asc()))
+            .limit(per_page.into())
+            .offset(offset.into())
+            .get_results::<Account>(&mut self.get_db())?;
 
         Ok(accounts)
     }
 
+    /// counts the accounts listed by `find_all_for_user`.
+    pub fn count_all_for_user(
+        &self,
+        user: &User,
+        list_query: &ListQuery<AccountListItemDTO>,
+    ) -> Result<i64> {
+        let count = Self::get_listing_query(user, list_query)?
+            .count()
+            .get_result(&mut self.get_db())?;
+
+        Ok(count)
+    }
+
+    /// finds an account by its ID, if it is the account of the user's application, or any account for super admins.
     pub fn find_for_user(&self, account_id: i32, user: &User) -> Result<Option<Account>> {
+        if is_super_admin(user) {
+            return self.find_one_by_id(account_id);
+        }
+
         let account = sql_query(
             "
             SELECT *
@@ -87,10 +129,59 @@ impl AccountRepository {
             AND ac.id = $2
         ",
         )
-        .bind::<Integer, _>(user.id)
+        .bind::<Integer, _>(user.application_id)
         .bind::<Integer, _>(account_id)
         .get_result(&mut self.get_db()).optional()?;
 
         Ok(account)
     }
+
+    /// PRIVATE - gets the query of the accounts of a user, filtered by a list query.
+    ///
+    /// Super admins manage every account, so every account is listed to them.
+    fn get_listing_query<'a>(
+        user: &User,
+        list_query: &ListQuery<AccountListItemDTO>,
+    ) -> Result<account::BoxedQuery<'a, Pg>> {
+        let mut query = account::table.filter(is_deleted.eq(false)).into_boxed();
+
+        if !is_super_admin(user) {
+            query = query.filter(
+                id.eq_any(
+                    application::table
+                        .filter(application::id.eq(user.application_id))
+                        .filter(application::is_deleted.eq(false))
+                        .select(application::account_id),
+                ),
+            );
+        }
+
+        for filter in &list_query.filters {
+            query = match filter.field.as_str() {
+                "id" => filter_by!(query, id, filter, i32),
+                "name" => filter_by!(query, name, filter),
+                "created_date" => filter_by!(query, created_date, filter, DateTime<Utc>),
+                _ => bail!("Unknown filter field {}", filter.field),
+            };
+        }
+
+        Ok(query)
+    }
+
+    /// PRIVATE - sorts a listing query by the sorts of a list query.
+    fn sort_listing<'a>(
+        mut query: account::BoxedQuery<'a, Pg>,
+        list_query: &ListQuery<AccountListItemDTO>,
+    ) -> Result<account::BoxedQuery<'a, Pg>> {
+        for sort in &list_query.sorts {
+            query = match sort.field.as_str() {
+                "id" => sort_by!(query, id, sort),
+                "name" => sort_by!(query, name, sort),
+                "created_date" => sort_by!(query, created_date, sort),
+                _ => bail!("Unknown sort field {}", sort.field),
+            };
+        }
+
+        Ok(query)
+    }
 }
@@ -0,0 +1,79 @@
+use anyhow::Result;
+use chrono::Utc;
+use diesel::prelude::*;
+
+use crate::{
+    core::database::{DbPoolState, DB},
+    domain::{
+        model::api_key::{ApiKey, NewApiKey},
+        schema::{
+            api_key::{application_id, id, is_revoked, last_used_date, prefix, revoked_date},
+            *,
+        },
+    },
+};
+
+#[derive(Clone)]
+pub struct ApiKeyRepository {
+    db_conn: DbPoolState,
+}
+
+impl ApiKeyRepository {
+    pub fn new(db_pool: DbPoolState) -> Self {
+        Self { db_conn: db_pool }
+    }
+
+    fn get_db(&self) -> DB {
+        self.db_conn.db_pool.get().unwrap()
+    }
+
+    pub fn insert(&self, new_api_key: NewApiKey) -> Result<ApiKey> {
+        let api_key = diesel::insert_into(api_key::table)
+            .values(&new_api_key)
+            .get_result(&mut self.get_db())?;
+
+        Ok(api_key)
+    }
+
+    pub fn find_one_by_prefix(&self, key_prefix: &str) -> Result<Option<ApiKey>> {
+        let api_key = api_key::table
+            .filter(prefix.eq(key_prefix))
+            .get_result::<ApiKey>(&mut self.get_db())
+            .optional()?;
+
+        Ok(api_key)
+    }
+
+    /// finds every key, or only those of an application when `key_application_id` is given.
+    pub fn find_all(&self, key_application_id: Option<i32>) -> Result<Vec<ApiKey>> {
+        let mut query = api_key::table.order(id.asc()).into_boxed();
+
+        if let Some(key_application_id) = key_application_id {
+            query = query.filter(application_id.eq(key_application_id));
+        }
+
+        let api_keys = query.load::<ApiKey>(&mut self.get_db())?;
+
+        Ok(api_keys)
+    }
+
+    /// revokes a key, returning `false` if it was already revoked or does not exist.
+    pub fn revoke(&self, key_id: i32) -> Result<bool> {
+        let revoked = diesel::update(api_key::table)
+            .filter(id.eq(key_id))
+            .filter(is_revoked.eq(false))
+            .set((is_revoked.eq(true), revoked_date.eq(Some(Utc::now()))))
+            .execute(&mut self.get_db())?;
+
+        Ok(revoked == 1)
+    }
+
+    pub fn touch_last_used(&self, key_id: i32) -> Result<usize> {
+        let updated = diesel::update(api_key::table)
+            .filter(id.eq(key_id))
+            .set(last_used_date.eq(Some(Utc::now())))
+            .execute(&mut self.get_db())?;
+
+        Ok(updated)
+    }
+}
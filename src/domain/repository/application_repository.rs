@@ -1,20 +1,28 @@
-use diesel::{prelude::*, sql_query, sql_types::Integer};
+use chrono::{DateTime, Utc};
+use diesel::{pg::Pg, prelude::*, sql_query, sql_types::Integer};
 
 use crate::{
-    core::database::{DbPoolState, DB},
+    core::{
+        cursor::Cursor,
+        database::{DbPoolState, DB},
+        guards::list_query::ListQuery,
+        security::is_super_admin,
+    },
     domain::{
+        dto::application::ApplicationListItemDTO,
         model::{
             application::{Application, NewApplication},
             user::User,
         },
         schema::{
-            application::{id, is_deleted},
+            application::{account_id, created_date, id, is_deleted, name},
             *,
         },
     },
+    filter_by, sort_by,
 };
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 
 #[derive(Clone)]
 pub struct ApplicationRepository {
@@ -48,36 +56,102 @@ impl ApplicationRepository {
         Ok(application)
     }
 
+    /// finds an application by its ID, even if it is deleted (e.g. to restore it).
+    pub fn find_one_by_id_including_deleted(
+        &self,
+        application_id: i32,
+    ) -> Result<Option<Application>> {
+        let application = application::table
+            .filter(id.eq(application_id))
+            .get_result::<Application>(&mut self.get_db())
+            .optional()?;
+
+        Ok(application)
+    }
+
+    /// counts the applications of an account which are not deleted.
+    pub fn count_for_account(&self, searched_account_id: i32) -> Result<i64> {
+        let count = application::table
+            .filter(account_id.eq(searched_account_id))
+            .filter(is_deleted.eq(false))
+            .count()
+            .get_result(&mut self.get_db())?;
+
+        Ok(count)
+    }
+
+    pub fn update(&self, updated_application: &Application) -> Result<Application> {
+        let updated = diesel::update(updated_application)
+            .set(updated_application)
+            .get_result(&mut self.get_db())?;
+
+        Ok(updated)
+    }
+
     pub fn find_all_for_user(
         &self,
         user: &User,
+        list_query: &ListQuery<ApplicationListItemDTO>,
         page: i32,
         per_page: i32,
     ) -> Result<Vec<Application>> {
         let offset = (page - 1) * per_page;
 
-        let applications = sql_query(
-            "
-            SELECT *
-            FROM application app
-            INNER JOIN users u on u.application_id = app.id AND u.is_deleted = false and u.id = $1
-            WHERE app.is_deleted = false
-            AND app.id = $2
-            ORDER BY app.created_date ASC
-            LIMIT $3
-            OFFSET $4
-        ",
-        )
-        .bind::<Integer, _>(user.id)
-        .bind::<Integer, _>(user.application_id)
-        .bind::<Integer, _>(per_page)
-        .bind::<Integer, _>(offset)
-        .load(&mut self.get_db())?;
+        let applications =
+            Self::sort_listing(Self::get_listing_query(user, list_query)?, list_query)?
+                .then_order_by((created_date.asc(), id.asc()))
+                .limit(per_page.into())
+                .offset(offset.into())
+                .get_results::<Application>(&mut self.get_db())?;
 
         Ok(applications)
     }
 
+    /// finds the applications of a user after a cursor, ordered by `(created_date, id)` (e.g. keyset pagination).
+    pub fn find_all_for_user_after(
+        &self,
+        user: &User,
+        list_query: &ListQuery<ApplicationListItemDTO>,
+        cursor: Option<&Cursor>,
+        limit: i32,
+    ) -> Result<Vec<Application>> {
+        let mut query = Self::get_listing_query(user, list_query)?;
+
+        if let Some(cursor) = cursor {
+            query = query.filter(
+                created_date
+                    .gt(cursor.created_date)
+                    .or(created_date.eq(cursor.created_date).and(id.gt(cursor.id))),
+            );
+        }
+
+        let applications = query
+            .order((created_date.asc(), id.asc()))
+            .limit(limit.into())
+            .get_results::<Application>(&mut self.get_db())?;
+
+        Ok(applications)
+    }
+
+    /// counts the applications listed by `find_all_for_user`.
+    pub fn count_all_for_user(
+        &self,
+        user: &User,
+        list_query: &ListQuery<ApplicationListItemDTO>,
+    ) -> Result<i64> {
+        let count = Self::get_listing_query(user, list_query)?
+            .count()
+            .get_result(&mut self.get_db())?;
+
+        Ok(count)
+    }
+
+    /// finds an application by its ID, if it is the user's application, or any application for super admins.
     pub fn find_for_user(&self, application_id: i32, user: &User) -> Result<Option<Application>> {
+        if is_super_admin(user) {
+            return self.find_one_by_id(application_id);
+        }
+
         let application = sql_query(
             "
             SELECT *
@@ -94,4 +168,53 @@ impl ApplicationRepository {
 
         Ok(application)
     }
+
+    /// PRIVATE - gets the query of the applications of a user, filtered by a list query.
+    ///
+    /// Super admins manage every application, so every application is listed to them.
+    fn get_listing_query<'a>(
+        user: &User,
+        list_query: &ListQuery<ApplicationListItemDTO>,
+    ) -> Result<application::BoxedQuery<'a, Pg>> {
+        let mut query = application::table.filter(is_deleted.eq(false)).into_boxed();
+
+        if !is_super_admin(user) {
+            query = query.filter(
+                id.eq_any(
+                    users::table
+                        .filter(users::id.eq(user.id))
+                        .filter(users::is_deleted.eq(false))
+                        .select(users::application_id),
+                ),
+            );
+        }
+
+        for filter in &list_query.filters {
+            query = match filter.field.as_str() {
+                "id" => filter_by!(query, id, filter, i32),
+                "name" => filter_by!(query, name, filter),
+                "created_date" => filter_by!(query, created_date, filter, DateTime<Utc>),
+                _ => bail!("Unknown filter field {}", filter.field),
+            };
+        }
+
+        Ok(query)
+    }
+
+    /// PRIVATE - sorts a listing query by the sorts of a list query.
+    fn sort_listing<'a>(
+        mut query: application::BoxedQuery<'a, Pg>,
+        list_query: &ListQuery<ApplicationListItemDTO>,
+    ) -> Result<application::BoxedQuery<'a, Pg>> {
+        for sort in &list_query.sorts {
+            query = match sort.field.as_str() {
+                "id" => sort_by!(query, id, sort),
+                "name" => sort_by!(query, name, sort),
+                "created_date" => sort_by!(query, created_date, sort),
+                _ => bail!("Unknown sort field {}", sort.field),
+            };
+        }
+
+        Ok(query)
+    }
 }
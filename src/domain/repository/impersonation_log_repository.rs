@@ -0,0 +1,33 @@
+use anyhow::Result;
+use diesel::prelude::*;
+
+use crate::{
+    core::database::{DbPoolState, DB},
+    domain::{
+        model::impersonation_log::{ImpersonationLog, NewImpersonationLog},
+        schema::*,
+    },
+};
+
+#[derive(Clone)]
+pub struct ImpersonationLogRepository {
+    db_conn: DbPoolState,
+}
+
+impl ImpersonationLogRepository {
+    pub fn new(db_pool: DbPoolState) -> Self {
+        Self { db_conn: db_pool }
+    }
+
+    fn get_db(&self) -> DB {
+        self.db_conn.db_pool.get().unwrap()
+    }
+
+    pub fn insert(&self, new_log: NewImpersonationLog) -> Result<ImpersonationLog> {
+        let log = diesel::insert_into(impersonation_log::table)
+            .values(&new_log)
+            .get_result(&mut self.get_db())?;
+
+        Ok(log)
+    }
+}
@@ -0,0 +1,56 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+
+use crate::{
+    core::database::{DbPoolState, DB},
+    domain::{
+        model::jwt_denylist::{JWTDenylistEntry, NewJWTDenylistEntry},
+        schema::{
+            jwt_denylist::{expiration_date, jti},
+            *,
+        },
+    },
+};
+
+#[derive(Clone)]
+pub struct JWTDenylistRepository {
+    db_conn: DbPoolState,
+}
+
+impl JWTDenylistRepository {
+    pub fn new(db_pool: DbPoolState) -> Self {
+        Self { db_conn: db_pool }
+    }
+
+    fn get_db(&self) -> DB {
+        self.db_conn.db_pool.get().unwrap()
+    }
+
+    pub fn insert(&self, new_entry: NewJWTDenylistEntry) -> Result<usize> {
+        let inserted = diesel::insert_into(jwt_denylist::table)
+            .values(&new_entry)
+            .on_conflict(jti)
+            .do_nothing()
+            .execute(&mut self.get_db())?;
+
+        Ok(inserted)
+    }
+
+    pub fn find_one_by_jti(&self, token_id: &str) -> Result<Option<JWTDenylistEntry>> {
+        let entry = jwt_denylist::table
+            .filter(jti.eq(token_id))
+            .get_result::<JWTDenylistEntry>(&mut self.get_db())
+            .optional()?;
+
+        Ok(entry)
+    }
+
+    pub fn delete_expired_before(&self, date: DateTime<Utc>) -> Result<usize> {
+        let deleted = diesel::delete(jwt_denylist::table)
+            .filter(expiration_date.lt(date))
+            .execute(&mut self.get_db())?;
+
+        Ok(deleted)
+    }
+}
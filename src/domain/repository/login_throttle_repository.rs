@@ -0,0 +1,101 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+
+use crate::{
+    core::database::{DbPoolState, DB},
+    domain::{
+        model::login_throttle::{LoginThrottle, NewLoginThrottle},
+        schema::{
+            login_throttle::{identifier, locked_until, scope},
+            *,
+        },
+    },
+};
+
+#[derive(Clone)]
+pub struct LoginThrottleRepository {
+    db_conn: DbPoolState,
+}
+
+impl LoginThrottleRepository {
+    pub fn new(db_pool: DbPoolState) -> Self {
+        Self { db_conn: db_pool }
+    }
+
+    fn get_db(&self) -> DB {
+        self.db_conn.db_pool.get().unwrap()
+    }
+
+    pub fn find_one(
+        &self,
+        throttle_scope: &str,
+        throttle_identifier: &str,
+    ) -> Result<Option<LoginThrottle>> {
+        let throttle = login_throttle::table
+            .filter(scope.eq(throttle_scope))
+            .filter(identifier.eq(throttle_identifier))
+            .get_result::<LoginThrottle>(&mut self.get_db())
+            .optional()?;
+
+        Ok(throttle)
+    }
+
+    pub fn find_all_locked_after(&self, date: DateTime<Utc>) -> Result<Vec<LoginThrottle>> {
+        let throttles = login_throttle::table
+            .filter(locked_until.gt(date))
+            .order(locked_until.desc())
+            .get_results::<LoginThrottle>(&mut self.get_db())?;
+
+        Ok(throttles)
+    }
+
+    /// records a failure for the given entry, in a single transaction locking the entry.
+    ///
+    /// The `apply_failure` closure receives the current entry and updates it (e.g. counters and lock).
+    pub fn record_failure<F>(
+        &self,
+        new_throttle: NewLoginThrottle,
+        apply_failure: F,
+    ) -> Result<LoginThrottle>
+    where
+        F: FnOnce(&mut LoginThrottle),
+    {
+        let throttle = self.get_db().transaction(|conn| {
+            diesel::insert_into(login_throttle::table)
+                .values(&new_throttle)
+                .on_conflict((scope, identifier))
+                .do_nothing()
+                .execute(conn)?;
+
+            let mut throttle = login_throttle::table
+                .filter(scope.eq(new_throttle.scope))
+                .filter(identifier.eq(new_throttle.identifier))
+                .for_update()
+                .get_result::<LoginThrottle>(conn)?;
+
+            apply_failure(&mut throttle);
+
+            diesel::update(&throttle)
+                .set(&throttle)
+                .get_result::<LoginThrottle>(conn)
+        })?;
+
+        Ok(throttle)
+    }
+
+    pub fn delete(&self, throttle_scope: &str, throttle_identifier: &str) -> Result<usize> {
+        let deleted = diesel::delete(login_throttle::table)
+            .filter(scope.eq(throttle_scope))
+            .filter(identifier.eq(throttle_identifier))
+            .execute(&mut self.get_db())?;
+
+        Ok(deleted)
+    }
+
+    pub fn delete_all(&self) -> Result<usize> {
+        let deleted = diesel::delete(login_throttle::table).execute(&mut self.get_db())?;
+
+        Ok(deleted)
+    }
+}
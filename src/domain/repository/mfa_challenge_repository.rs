@@ -0,0 +1,67 @@
+use anyhow::Result;
+use diesel::prelude::*;
+
+use crate::{
+    core::database::{DbPoolState, DB},
+    domain::{
+        model::mfa_challenge::{MFAChallenge, NewMFAChallenge},
+        schema::{
+            mfa_challenge::{attempts, id, is_used, token_hash},
+            *,
+        },
+    },
+};
+
+#[derive(Clone)]
+pub struct MFAChallengeRepository {
+    db_conn: DbPoolState,
+}
+
+impl MFAChallengeRepository {
+    pub fn new(db_pool: DbPoolState) -> Self {
+        Self { db_conn: db_pool }
+    }
+
+    fn get_db(&self) -> DB {
+        self.db_conn.db_pool.get().unwrap()
+    }
+
+    pub fn insert(&self, new_challenge: NewMFAChallenge) -> Result<MFAChallenge> {
+        let challenge = diesel::insert_into(mfa_challenge::table)
+            .values(&new_challenge)
+            .get_result(&mut self.get_db())?;
+
+        Ok(challenge)
+    }
+
+    pub fn find_one_by_token_hash(&self, hash: &str) -> Result<Option<MFAChallenge>> {
+        let challenge = mfa_challenge::table
+            .filter(token_hash.eq(hash))
+            .get_result::<MFAChallenge>(&mut self.get_db())
+            .optional()?;
+
+        Ok(challenge)
+    }
+
+    pub fn increment_attempts(&self, challenge_id: i32) -> Result<usize> {
+        let updated = diesel::update(mfa_challenge::table)
+            .filter(id.eq(challenge_id))
+            .set(attempts.eq(attempts + 1))
+            .execute(&mut self.get_db())?;
+
+        Ok(updated)
+    }
+
+    /// marks the challenge as used, only if it has not been used in the meantime.
+    ///
+    /// Returns `false` if the challenge was already used.
+    pub fn consume(&self, challenge_id: i32) -> Result<bool> {
+        let consumed = diesel::update(mfa_challenge::table)
+            .filter(id.eq(challenge_id))
+            .filter(is_used.eq(false))
+            .set(is_used.eq(true))
+            .execute(&mut self.get_db())?;
+
+        Ok(consumed == 1)
+    }
+}
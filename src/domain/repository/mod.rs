@@ -1,5 +1,16 @@
 pub mod account_repository;
+pub mod api_key_repository;
 pub mod application_repository;
 pub mod cron_log_repository;
+pub mod impersonation_log_repository;
+pub mod jwt_denylist_repository;
+pub mod login_throttle_repository;
+pub mod mfa_challenge_repository;
+pub mod oauth_client_repository;
+pub mod password_reset_token_repository;
+pub mod recovery_code_repository;
 pub mod refresh_token_repository;
+pub mod role_repository;
+pub mod security_audit_log_repository;
 pub mod user_repository;
+pub mod user_totp_repository;
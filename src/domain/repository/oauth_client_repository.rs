@@ -0,0 +1,70 @@
+use anyhow::Result;
+use chrono::Utc;
+use diesel::prelude::*;
+
+use crate::{
+    core::database::{DbPoolState, DB},
+    domain::{
+        model::oauth_client::{NewOAuthClient, OAuthClient},
+        schema::{
+            oauth_client::{application_id, client_id, id, is_revoked, revoked_date},
+            *,
+        },
+    },
+};
+
+#[derive(Clone)]
+pub struct OAuthClientRepository {
+    db_conn: DbPoolState,
+}
+
+impl OAuthClientRepository {
+    pub fn new(db_pool: DbPoolState) -> Self {
+        Self { db_conn: db_pool }
+    }
+
+    fn get_db(&self) -> DB {
+        self.db_conn.db_pool.get().unwrap()
+    }
+
+    pub fn insert(&self, new_client: NewOAuthClient) -> Result<OAuthClient> {
+        let client = diesel::insert_into(oauth_client::table)
+            .values(&new_client)
+            .get_result(&mut self.get_db())?;
+
+        Ok(client)
+    }
+
+    pub fn find_one_by_client_id(&self, searched_client_id: &str) -> Result<Option<OAuthClient>> {
+        let client = oauth_client::table
+            .filter(client_id.eq(searched_client_id))
+            .get_result::<OAuthClient>(&mut self.get_db())
+            .optional()?;
+
+        Ok(client)
+    }
+
+    /// finds every client, or only those of an application when `client_application_id` is given.
+    pub fn find_all(&self, client_application_id: Option<i32>) -> Result<Vec<OAuthClient>> {
+        let mut query = oauth_client::table.order(id.asc()).into_boxed();
+
+        if let Some(client_application_id) = client_application_id {
+            query = query.filter(application_id.eq(client_application_id));
+        }
+
+        let clients = query.load::<OAuthClient>(&mut self.get_db())?;
+
+        Ok(clients)
+    }
+
+    /// revokes a client, returning `false` if it was already revoked or does not exist.
+    pub fn revoke(&self, revoked_client_id: &str) -> Result<bool> {
+        let revoked = diesel::update(oauth_client::table)
+            .filter(client_id.eq(revoked_client_id))
+            .filter(is_revoked.eq(false))
+            .set((is_revoked.eq(true), revoked_date.eq(Some(Utc::now()))))
+            .execute(&mut self.get_db())?;
+
+        Ok(revoked == 1)
+    }
+}
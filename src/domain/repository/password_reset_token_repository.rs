@@ -0,0 +1,70 @@
+use anyhow::Result;
+use chrono::Utc;
+use diesel::prelude::*;
+
+use crate::{
+    core::database::{DbPoolState, DB},
+    domain::{
+        model::password_reset_token::{NewPasswordResetToken, PasswordResetToken},
+        schema::{
+            password_reset_token::{id, is_used, token_hash, used_date, user_id},
+            *,
+        },
+    },
+};
+
+#[derive(Clone)]
+pub struct PasswordResetTokenRepository {
+    db_conn: DbPoolState,
+}
+
+impl PasswordResetTokenRepository {
+    pub fn new(db_pool: DbPoolState) -> Self {
+        Self { db_conn: db_pool }
+    }
+
+    fn get_db(&self) -> DB {
+        self.db_conn.db_pool.get().unwrap()
+    }
+
+    pub fn insert(&self, new_token: NewPasswordResetToken) -> Result<PasswordResetToken> {
+        let token = diesel::insert_into(password_reset_token::table)
+            .values(&new_token)
+            .get_result(&mut self.get_db())?;
+
+        Ok(token)
+    }
+
+    pub fn find_one_by_token_hash(&self, hash: &str) -> Result<Option<PasswordResetToken>> {
+        let token = password_reset_token::table
+            .filter(token_hash.eq(hash))
+            .get_result::<PasswordResetToken>(&mut self.get_db())
+            .optional()?;
+
+        Ok(token)
+    }
+
+    /// marks the token as used, only if it has not been used in the meantime.
+    ///
+    /// Returns `false` if the token was already used.
+    pub fn consume(&self, token_id: i32) -> Result<bool> {
+        let consumed = diesel::update(password_reset_token::table)
+            .filter(id.eq(token_id))
+            .filter(is_used.eq(false))
+            .set((is_used.eq(true), used_date.eq(Some(Utc::now()))))
+            .execute(&mut self.get_db())?;
+
+        Ok(consumed == 1)
+    }
+
+    /// marks every pending token of a user as used, so only the last requested one is valid.
+    pub fn invalidate_all_for_user(&self, token_user_id: i32) -> Result<usize> {
+        let invalidated = diesel::update(password_reset_token::table)
+            .filter(user_id.eq(token_user_id))
+            .filter(is_used.eq(false))
+            .set((is_used.eq(true), used_date.eq(Some(Utc::now()))))
+            .execute(&mut self.get_db())?;
+
+        Ok(invalidated)
+    }
+}
@@ -0,0 +1,62 @@
+use anyhow::Result;
+use chrono::Utc;
+use diesel::prelude::*;
+
+use crate::{
+    core::database::{DbPoolState, DB},
+    domain::{
+        model::recovery_code::NewRecoveryCode,
+        schema::{
+            recovery_code::{code_hash, is_used, used_date, user_id},
+            *,
+        },
+    },
+};
+
+#[derive(Clone)]
+pub struct RecoveryCodeRepository {
+    db_conn: DbPoolState,
+}
+
+impl RecoveryCodeRepository {
+    pub fn new(db_pool: DbPoolState) -> Self {
+        Self { db_conn: db_pool }
+    }
+
+    fn get_db(&self) -> DB {
+        self.db_conn.db_pool.get().unwrap()
+    }
+
+    /// replaces every recovery code of a user by the given ones, in a single transaction.
+    pub fn replace_all_for_user(
+        &self,
+        code_user_id: i32,
+        new_codes: Vec<NewRecoveryCode>,
+    ) -> Result<usize> {
+        let inserted = self.get_db().transaction(|conn| {
+            diesel::delete(recovery_code::table)
+                .filter(user_id.eq(code_user_id))
+                .execute(conn)?;
+
+            diesel::insert_into(recovery_code::table)
+                .values(&new_codes)
+                .execute(conn)
+        })?;
+
+        Ok(inserted)
+    }
+
+    /// marks an unused recovery code of a user as used.
+    ///
+    /// Returns `false` if no unused code matches.
+    pub fn consume(&self, code_user_id: i32, hash: &str) -> Result<bool> {
+        let consumed = diesel::update(recovery_code::table)
+            .filter(user_id.eq(code_user_id))
+            .filter(code_hash.eq(hash))
+            .filter(is_used.eq(false))
+            .set((is_used.eq(true), used_date.eq(Some(Utc::now()))))
+            .execute(&mut self.get_db())?;
+
+        Ok(consumed > 0)
+    }
+}
@@ -2,9 +2,16 @@ use crate::{
     core::database::{DbPoolState, DB},
     domain::{
         model::refresh_token::{NewRefreshToken, RefreshToken},
-        schema::{refresh_token::token, *},
+        schema::{
+            refresh_token::{
+                family, id, is_revoked, is_used, replaced_by, revoked_date, token, used_date,
+                user_id,
+            },
+            *,
+        },
     },
 };
+use chrono::Utc;
 use diesel::prelude::*;
 
 use anyhow::Result;
@@ -38,4 +45,55 @@ impl RefreshTokenRepository {
 
         Ok(refresh_token)
     }
+
+    /// marks the token `used_id` as used and inserts its successor, in a single transaction.
+    ///
+    /// Returns `None` if the token has already been used or revoked in the meantime.
+    pub fn rotate(&self, used_id: i32, successor: NewRefreshToken) -> Result<Option<RefreshToken>> {
+        let rotated = self.get_db().transaction(|conn| {
+            let consumed = diesel::update(refresh_token::table)
+                .filter(id.eq(used_id))
+                .filter(is_used.eq(false))
+                .filter(is_revoked.eq(false))
+                .set((is_used.eq(true), used_date.eq(Some(Utc::now()))))
+                .execute(conn)?;
+
+            if consumed == 0 {
+                return Ok(None);
+            }
+
+            let created = diesel::insert_into(refresh_token::table)
+                .values(&successor)
+                .get_result::<RefreshToken>(conn)?;
+
+            diesel::update(refresh_token::table)
+                .filter(id.eq(used_id))
+                .set(replaced_by.eq(Some(created.id)))
+                .execute(conn)?;
+
+            Ok::<Option<RefreshToken>, diesel::result::Error>(Some(created))
+        })?;
+
+        Ok(rotated)
+    }
+
+    pub fn revoke_family(&self, token_family: &str) -> Result<usize> {
+        let revoked = diesel::update(refresh_token::table)
+            .filter(family.eq(token_family))
+            .filter(is_revoked.eq(false))
+            .set((is_revoked.eq(true), revoked_date.eq(Some(Utc::now()))))
+            .execute(&mut self.get_db())?;
+
+        Ok(revoked)
+    }
+
+    pub fn revoke_all_for_user(&self, token_user_id: i32) -> Result<usize> {
+        let revoked = diesel::update(refresh_token::table)
+            .filter(user_id.eq(token_user_id))
+            .filter(is_revoked.eq(false))
+            .set((is_revoked.eq(true), revoked_date.eq(Some(Utc::now()))))
+            .execute(&mut self.get_db())?;
+
+        Ok(revoked)
+    }
 }
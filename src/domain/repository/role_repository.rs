@@ -0,0 +1,67 @@
+use anyhow::Result;
+use diesel::prelude::*;
+
+use crate::{
+    core::database::{DbPoolState, DB},
+    domain::{
+        model::role::{NewRole, Role},
+        schema::{
+            role::{application_id, id, name},
+            *,
+        },
+    },
+};
+
+#[derive(Clone)]
+pub struct RoleRepository {
+    db_conn: DbPoolState,
+}
+
+impl RoleRepository {
+    pub fn new(db_pool: DbPoolState) -> Self {
+        Self { db_conn: db_pool }
+    }
+
+    fn get_db(&self) -> DB {
+        self.db_conn.db_pool.get().unwrap()
+    }
+
+    pub fn insert(&self, new_role: NewRole) -> Result<Role> {
+        let role = diesel::insert_into(role::table)
+            .values(&new_role)
+            .get_result(&mut self.get_db())?;
+
+        Ok(role)
+    }
+
+    pub fn find_all_for_application(&self, role_application_id: i32) -> Result<Vec<Role>> {
+        let roles = role::table
+            .filter(application_id.eq(role_application_id))
+            .order(name.asc())
+            .load::<Role>(&mut self.get_db())?;
+
+        Ok(roles)
+    }
+
+    pub fn find_one_for_application(
+        &self,
+        role_application_id: i32,
+        role_name: &str,
+    ) -> Result<Option<Role>> {
+        let role = role::table
+            .filter(application_id.eq(role_application_id))
+            .filter(name.eq(role_name))
+            .get_result::<Role>(&mut self.get_db())
+            .optional()?;
+
+        Ok(role)
+    }
+
+    pub fn delete(&self, role_id: i32) -> Result<usize> {
+        let deleted = diesel::delete(role::table)
+            .filter(id.eq(role_id))
+            .execute(&mut self.get_db())?;
+
+        Ok(deleted)
+    }
+}
@@ -0,0 +1,33 @@
+use anyhow::Result;
+use diesel::prelude::*;
+
+use crate::{
+    core::database::{DbPoolState, DB},
+    domain::{
+        model::security_audit_log::{NewSecurityAuditLog, SecurityAuditLog},
+        schema::*,
+    },
+};
+
+#[derive(Clone)]
+pub struct SecurityAuditLogRepository {
+    db_conn: DbPoolState,
+}
+
+impl SecurityAuditLogRepository {
+    pub fn new(db_pool: DbPoolState) -> Self {
+        Self { db_conn: db_pool }
+    }
+
+    fn get_db(&self) -> DB {
+        self.db_conn.db_pool.get().unwrap()
+    }
+
+    pub fn insert(&self, new_log: NewSecurityAuditLog) -> Result<SecurityAuditLog> {
+        let log = diesel::insert_into(security_audit_log::table)
+            .values(&new_log)
+            .get_result(&mut self.get_db())?;
+
+        Ok(log)
+    }
+}
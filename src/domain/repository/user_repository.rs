@@ -1,17 +1,27 @@
-use diesel::prelude::*;
+use chrono::{DateTime, Utc};
+use diesel::{pg::Pg, prelude::*};
 
 use crate::{
-    core::database::{DbPoolState, DB},
+    core::{
+        cursor::Cursor,
+        database::{DbPoolState, DB},
+        guards::list_query::ListQuery,
+    },
     domain::{
+        dto::user::UserListItemDTO,
         model::user::{NewUser, User},
         schema::{
-            users::{application_id, created_date, id, is_deleted, login},
+            users::{
+                application_id, created_date, email, id, is_deleted, login, roles,
+                tokens_revoked_date,
+            },
             *,
         },
     },
+    filter_by, sort_by,
 };
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 
 #[derive(Clone)]
 pub struct UserRepository {
@@ -27,11 +37,12 @@ impl UserRepository {
         self.db_conn.db_pool.get().unwrap()
     }
 
-    pub fn load_user_by_login(&self, user_login: &str) -> Result<User> {
+    pub fn load_user_by_login(&self, user_login: &str) -> Result<Option<User>> {
         let user = users::table
             .filter(login.eq(user_login))
             .filter(is_deleted.eq(false))
-            .get_result::<User>(&mut self.get_db())?;
+            .get_result::<User>(&mut self.get_db())
+            .optional()?;
 
         Ok(user)
     }
@@ -56,25 +67,75 @@ impl UserRepository {
         Ok(user)
     }
 
+    pub fn find_all_by_email(&self, user_email: &str) -> Result<Vec<User>> {
+        let users = users::table
+            .filter(email.eq(user_email))
+            .filter(is_deleted.eq(false))
+            .get_results::<User>(&mut self.get_db())?;
+
+        Ok(users)
+    }
+
     pub fn find_all_for_application_id(
         &self,
         user_application_id: i32,
+        list_query: &ListQuery<UserListItemDTO>,
         page: i32,
         per_page: i32,
     ) -> Result<Vec<User>> {
         let offset = (page - 1) * per_page;
 
-        let users = users::table
-            .filter(application_id.eq(user_application_id))
-            .filter(is_deleted.eq(false))
-            .limit(per_page.into())
-            .offset(offset.into())
-            .order(created_date.asc())
+        let users = Self::sort_listing(
+            Self::get_listing_query(user_application_id, list_query)?,
+            list_query,
+        )?
+        .then_order_by((created_date.asc(), id.asc()))
+        .limit(per_page.into())
+        .offset(offset.into())
+        .get_results::<User>(&mut self.get_db())?;
+
+        Ok(users)
+    }
+
+    /// finds the users of an application after a cursor, ordered by `(created_date, id)` (e.g. keyset pagination).
+    pub fn find_all_for_application_id_after(
+        &self,
+        user_application_id: i32,
+        list_query: &ListQuery<UserListItemDTO>,
+        cursor: Option<&Cursor>,
+        limit: i32,
+    ) -> Result<Vec<User>> {
+        let mut query = Self::get_listing_query(user_application_id, list_query)?;
+
+        if let Some(cursor) = cursor {
+            query = query.filter(
+                created_date
+                    .gt(cursor.created_date)
+                    .or(created_date.eq(cursor.created_date).and(id.gt(cursor.id))),
+            );
+        }
+
+        let users = query
+            .order((created_date.asc(), id.asc()))
+            .limit(limit.into())
             .get_results::<User>(&mut self.get_db())?;
 
         Ok(users)
     }
 
+    /// counts the users listed by `find_all_for_application_id`.
+    pub fn count_all_for_application_id(
+        &self,
+        user_application_id: i32,
+        list_query: &ListQuery<UserListItemDTO>,
+    ) -> Result<i64> {
+        let count = Self::get_listing_query(user_application_id, list_query)?
+            .count()
+            .get_result(&mut self.get_db())?;
+
+        Ok(count)
+    }
+
     pub fn find_one_for_user_and_application(
         &self,
         user_id: i32,
@@ -105,4 +166,80 @@ impl UserRepository {
 
         Ok(updated)
     }
+
+    /// updates the stored roles of a user, as they are never written by `update`.
+    pub fn update_roles(&self, user_id: i32, user_roles: Vec<String>) -> Result<User> {
+        let updated = diesel::update(users::table)
+            .filter(id.eq(user_id))
+            .set(roles.eq(user_roles))
+            .get_result(&mut self.get_db())?;
+
+        Ok(updated)
+    }
+
+    /// refuses every JWT of a user issued until the given date.
+    pub fn revoke_tokens(&self, user_id: i32, revoked_date: DateTime<Utc>) -> Result<User> {
+        let updated = diesel::update(users::table)
+            .filter(id.eq(user_id))
+            .set(tokens_revoked_date.eq(Some(revoked_date)))
+            .get_result(&mut self.get_db())?;
+
+        Ok(updated)
+    }
+
+    pub fn count_for_application_and_role(
+        &self,
+        user_application_id: i32,
+        role_name: &str,
+    ) -> Result<i64> {
+        let count = users::table
+            .filter(application_id.eq(user_application_id))
+            .filter(is_deleted.eq(false))
+            .filter(roles.contains(vec![role_name]))
+            .count()
+            .get_result(&mut self.get_db())?;
+
+        Ok(count)
+    }
+
+    /// PRIVATE - gets the query of the users of an application, filtered by a list query.
+    fn get_listing_query<'a>(
+        user_application_id: i32,
+        list_query: &ListQuery<UserListItemDTO>,
+    ) -> Result<users::BoxedQuery<'a, Pg>> {
+        let mut query = users::table
+            .filter(application_id.eq(user_application_id))
+            .filter(is_deleted.eq(false))
+            .into_boxed();
+
+        for filter in &list_query.filters {
+            query = match filter.field.as_str() {
+                "id" => filter_by!(query, id, filter, i32),
+                "login" => filter_by!(query, login, filter),
+                "email" => filter_by!(query, email, filter),
+                "created_date" => filter_by!(query, created_date, filter, DateTime<Utc>),
+                _ => bail!("Unknown filter field {}", filter.field),
+            };
+        }
+
+        Ok(query)
+    }
+
+    /// PRIVATE - sorts a listing query by the sorts of a list query.
+    fn sort_listing<'a>(
+        mut query: users::BoxedQuery<'a, Pg>,
+        list_query: &ListQuery<UserListItemDTO>,
+    ) -> Result<users::BoxedQuery<'a, Pg>> {
+        for sort in &list_query.sorts {
+            query = match sort.field.as_str() {
+                "id" => sort_by!(query, id, sort),
+                "login" => sort_by!(query, login, sort),
+                "email" => sort_by!(query, email, sort),
+                "created_date" => sort_by!(query, created_date, sort),
+                _ => bail!("Unknown sort field {}", sort.field),
+            };
+        }
+
+        Ok(query)
+    }
 }
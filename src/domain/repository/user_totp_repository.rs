@@ -0,0 +1,84 @@
+use anyhow::Result;
+use chrono::Utc;
+use diesel::prelude::*;
+
+use crate::{
+    core::database::{DbPoolState, DB},
+    domain::{
+        model::user_totp::{NewUserTOTP, UserTOTP},
+        schema::{
+            user_totp::{
+                created_date, enabled_date, id, is_enabled, last_used_step, secret, user_id,
+            },
+            *,
+        },
+    },
+};
+
+#[derive(Clone)]
+pub struct UserTOTPRepository {
+    db_conn: DbPoolState,
+}
+
+impl UserTOTPRepository {
+    pub fn new(db_pool: DbPoolState) -> Self {
+        Self { db_conn: db_pool }
+    }
+
+    fn get_db(&self) -> DB {
+        self.db_conn.db_pool.get().unwrap()
+    }
+
+    pub fn find_one_by_user_id(&self, totp_user_id: i32) -> Result<Option<UserTOTP>> {
+        let totp = user_totp::table
+            .filter(user_id.eq(totp_user_id))
+            .get_result::<UserTOTP>(&mut self.get_db())
+            .optional()?;
+
+        Ok(totp)
+    }
+
+    /// registers a pending (e.g. not enabled yet) secret for a user, replacing any previous pending one.
+    pub fn upsert_pending(&self, new_totp: NewUserTOTP) -> Result<UserTOTP> {
+        let totp = diesel::insert_into(user_totp::table)
+            .values(&new_totp)
+            .on_conflict(user_id)
+            .do_update()
+            .set((
+                secret.eq(new_totp.secret),
+                is_enabled.eq(false),
+                enabled_date.eq(None::<chrono::DateTime<Utc>>),
+                last_used_step.eq(None::<i64>),
+                created_date.eq(new_totp.created_date),
+            ))
+            .get_result(&mut self.get_db())?;
+
+        Ok(totp)
+    }
+
+    pub fn enable(&self, totp_id: i32, step: i64) -> Result<usize> {
+        let updated = diesel::update(user_totp::table)
+            .filter(id.eq(totp_id))
+            .set((
+                is_enabled.eq(true),
+                enabled_date.eq(Some(Utc::now())),
+                last_used_step.eq(Some(step)),
+            ))
+            .execute(&mut self.get_db())?;
+
+        Ok(updated)
+    }
+
+    /// records the time step of an accepted code, only if it is newer than the last accepted one.
+    ///
+    /// Returns `false` if the step has already been used (e.g. the code is replayed).
+    pub fn use_step(&self, totp_id: i32, step: i64) -> Result<bool> {
+        let updated = diesel::update(user_totp::table)
+            .filter(id.eq(totp_id))
+            .filter(last_used_step.is_null().or(last_used_step.lt(step)))
+            .set(last_used_step.eq(Some(step)))
+            .execute(&mut self.get_db())?;
+
+        Ok(updated == 1)
+    }
+}
@@ -10,6 +10,22 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    api_key (id) {
+        id -> Int4,
+        application_id -> Int4,
+        name -> Varchar,
+        prefix -> Varchar,
+        key_hash -> Varchar,
+        scopes -> Array<Text>,
+        expiration_date -> Nullable<Timestamptz>,
+        last_used_date -> Nullable<Timestamptz>,
+        is_revoked -> Bool,
+        revoked_date -> Nullable<Timestamptz>,
+        created_date -> Timestamptz,
+    }
+}
+
 diesel::table! {
     application (id) {
         id -> Int4,
@@ -35,12 +51,141 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    impersonation_log (id) {
+        id -> Int4,
+        impersonator_id -> Int4,
+        impersonated_id -> Int4,
+        application_id -> Int4,
+        jti -> Varchar,
+        reason -> Nullable<Varchar>,
+        expiration_date -> Timestamptz,
+        created_date -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    jwt_denylist (id) {
+        id -> Int4,
+        jti -> Varchar,
+        user_id -> Nullable<Int4>,
+        expiration_date -> Timestamptz,
+        created_date -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    login_throttle (id) {
+        id -> Int4,
+        scope -> Varchar,
+        identifier -> Varchar,
+        failure_count -> Int4,
+        last_failure_date -> Timestamptz,
+        locked_until -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    mfa_challenge (id) {
+        id -> Int4,
+        token_hash -> Varchar,
+        user_id -> Int4,
+        expiration_date -> Timestamptz,
+        attempts -> Int4,
+        is_used -> Bool,
+        created_date -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    oauth_client (id) {
+        id -> Int4,
+        application_id -> Int4,
+        name -> Varchar,
+        client_id -> Varchar,
+        client_secret_hash -> Varchar,
+        scopes -> Array<Text>,
+        is_revoked -> Bool,
+        revoked_date -> Nullable<Timestamptz>,
+        created_date -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    password_reset_token (id) {
+        id -> Int4,
+        token_hash -> Varchar,
+        user_id -> Int4,
+        expiration_date -> Timestamptz,
+        is_used -> Bool,
+        used_date -> Nullable<Timestamptz>,
+        created_date -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    recovery_code (id) {
+        id -> Int4,
+        user_id -> Int4,
+        code_hash -> Varchar,
+        is_used -> Bool,
+        used_date -> Nullable<Timestamptz>,
+        created_date -> Timestamptz,
+    }
+}
+
 diesel::table! {
     refresh_token (id) {
         id -> Int4,
         token -> Varchar,
         user_id -> Int4,
         validity_date -> Timestamptz,
+        family -> Varchar,
+        is_used -> Bool,
+        used_date -> Nullable<Timestamptz>,
+        replaced_by -> Nullable<Int4>,
+        is_revoked -> Bool,
+        revoked_date -> Nullable<Timestamptz>,
+        client_id -> Nullable<Varchar>,
+    }
+}
+
+diesel::table! {
+    role (id) {
+        id -> Int4,
+        application_id -> Int4,
+        name -> Varchar,
+        implied_roles -> Array<Text>,
+        description -> Nullable<Varchar>,
+        created_date -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    security_audit_log (id) {
+        id -> Int4,
+        user_id -> Nullable<Int4>,
+        api_key_id -> Nullable<Int4>,
+        application_id -> Int4,
+        subject -> Varchar,
+        access_right -> Varchar,
+        context -> Jsonb,
+        voter -> Nullable<Varchar>,
+        outcome -> Varchar,
+        reason -> Nullable<Text>,
+        created_date -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    user_totp (id) {
+        id -> Int4,
+        user_id -> Int4,
+        secret -> Varchar,
+        is_enabled -> Bool,
+        enabled_date -> Nullable<Timestamptz>,
+        last_used_step -> Nullable<Int8>,
+        created_date -> Timestamptz,
     }
 }
 
@@ -60,17 +205,38 @@ diesel::table! {
         deleted_date -> Nullable<Timestamptz>,
         deleted_by -> Nullable<Int4>,
         is_deleted -> Bool,
+        tokens_revoked_date -> Nullable<Timestamptz>,
     }
 }
 
+diesel::joinable!(api_key -> application (application_id));
 diesel::joinable!(application -> account (account_id));
+diesel::joinable!(impersonation_log -> application (application_id));
+diesel::joinable!(jwt_denylist -> users (user_id));
+diesel::joinable!(mfa_challenge -> users (user_id));
+diesel::joinable!(oauth_client -> application (application_id));
+diesel::joinable!(password_reset_token -> users (user_id));
+diesel::joinable!(recovery_code -> users (user_id));
 diesel::joinable!(refresh_token -> users (user_id));
+diesel::joinable!(role -> application (application_id));
+diesel::joinable!(user_totp -> users (user_id));
 diesel::joinable!(users -> application (application_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
     account,
+    api_key,
     application,
     cron_logs,
+    impersonation_log,
+    jwt_denylist,
+    login_throttle,
+    mfa_challenge,
+    oauth_client,
+    password_reset_token,
+    recovery_code,
     refresh_token,
+    role,
+    security_audit_log,
+    user_totp,
     users,
 );
@@ -1,8 +1,9 @@
 use rocket::http::Status;
+use schemars::JsonSchema;
 use serde::Serialize;
 
 /// a struct representing an HTTP exception
-#[derive(Serialize, Default)]
+#[derive(Serialize, Default, JsonSchema)]
 #[serde(crate = "rocket::serde")]
 pub struct HttpException {
     /// code of the exception, or HTTP status.
@@ -1,24 +1,58 @@
+use chrono::Utc;
+use thiserror::Error;
+
+use crate::core::guards::list_query::ListQuery;
 use crate::domain::{
-    dto::account::{AccountDetailsDTO, AccountListItemDTO},
+    dto::account::{
+        AccountDetailsDTO, AccountListItemDTO, NewAccountInputDTO, UpdateAccountInputDTO,
+    },
     model::{
         account::{Account, NewAccount},
         user::User,
     },
-    repository::account_repository::AccountRepository,
+    repository::{
+        account_repository::AccountRepository, application_repository::ApplicationRepository,
+    },
 };
 
 use anyhow::Result;
 
+/// Error states for account management.
+#[derive(Debug, Error)]
+pub enum AccountError {
+    #[error("An account named {} already exists", _0)]
+    AlreadyExists(String),
+    #[error("Account still has {} application(s)", _0)]
+    NotEmpty(i64),
+    #[error("Account is not deleted")]
+    NotDeleted,
+    #[error("Internal error : {} ", _0)]
+    Internal(String),
+}
+
+impl From<anyhow::Error> for AccountError {
+    fn from(error: anyhow::Error) -> Self {
+        AccountError::Internal(error.to_string())
+    }
+}
+
 /// Account model middleware.
 #[derive(Clone)]
 pub struct AccountMiddleware {
     repository: AccountRepository,
+    application_repository: ApplicationRepository,
 }
 
 impl AccountMiddleware {
     /// constructor.
-    pub fn new(repository: AccountRepository) -> Self {
-        Self { repository }
+    pub fn new(
+        repository: AccountRepository,
+        application_repository: ApplicationRepository,
+    ) -> Self {
+        Self {
+            repository,
+            application_repository,
+        }
     }
 
     /// creates (e.g. inserts) an account into the database.
@@ -36,33 +70,112 @@ impl AccountMiddleware {
     }
 
     /// finds an account by its unique identifier.
-    pub fn find_one_by_id(&self, id: &str) -> Result<Option<Account>> {
-        let real_id = id.parse::<i32>()?;
-
-        let account = self.repository.find_one_by_id(real_id)?;
+    pub fn find_one_by_id(&self, id: i32) -> Result<Option<Account>> {
+        let account = self.repository.find_one_by_id(id)?;
 
         Ok(account)
     }
 
     /// finds all accounts for the given user.
     /// this function uses pagination.
-    pub fn find_for_user(&self, user: &User, page: u16, per_page: u16) -> Result<Vec<Account>> {
-        let accounts = self
-            .repository
-            .find_all_for_user(user, page.into(), per_page.into())?;
+    pub fn find_for_user(
+        &self,
+        user: &User,
+        list_query: &ListQuery<AccountListItemDTO>,
+        page: u16,
+        per_page: u16,
+    ) -> Result<Vec<Account>> {
+        let accounts =
+            self.repository
+                .find_all_for_user(user, list_query, page.into(), per_page.into())?;
 
         Ok(accounts)
     }
 
+    /// counts all accounts for the given user, e.g. the total of `find_for_user` pages.
+    pub fn count_for_user(
+        &self,
+        user: &User,
+        list_query: &ListQuery<AccountListItemDTO>,
+    ) -> Result<i64> {
+        let count = self.repository.count_all_for_user(user, list_query)?;
+
+        Ok(count)
+    }
+
     /// finds a given account by its ID, for the given user.
-    pub fn find_one_for_user(&self, id: &str, user: &User) -> Result<Option<Account>> {
-        let real_id = id.parse::<i32>()?;
+    pub fn find_one_for_user(&self, id: i32, user: &User) -> Result<Option<Account>> {
+        let account = self.repository.find_for_user(id, user)?;
 
-        let account = self.repository.find_for_user(real_id, user)?;
+        Ok(account)
+    }
+
+    /// finds an account by its ID, even if it is deleted.
+    pub fn find_one_by_id_including_deleted(&self, id: i32) -> Result<Option<Account>> {
+        let account = self.repository.find_one_by_id_including_deleted(id)?;
 
         Ok(account)
     }
 
+    /// creates an account from NewAccountInputDTO and register it to the database.
+    pub fn create_from_user_input(&self, dto: NewAccountInputDTO) -> Result<Account, AccountError> {
+        if self.find_one_by_name(&dto.name)?.is_some() {
+            return Err(AccountError::AlreadyExists(dto.name));
+        }
+
+        Ok(self.create(NewAccount::new(&dto.name))?)
+    }
+
+    /// updates an account given a UpdateAccountInputDTO, and register it to the database.
+    pub fn update_from_user_input(
+        &self,
+        to_update: &Account,
+        dto: UpdateAccountInputDTO,
+    ) -> Result<Account, AccountError> {
+        if let Some(homonym) = self.find_one_by_name(&dto.name)? {
+            if homonym.id != to_update.id {
+                return Err(AccountError::AlreadyExists(dto.name));
+            }
+        }
+
+        let mut account = to_update.clone();
+        account.name = dto.name;
+
+        Ok(self.repository.update(&account)?)
+    }
+
+    /// apply deletion logic to an Account and register it as deleted in the database.
+    ///
+    /// An account can only be deleted once all its applications are.
+    pub fn delete(&self, account_to_delete: &Account) -> Result<Account, AccountError> {
+        let applications_count = self
+            .application_repository
+            .count_for_account(account_to_delete.id)?;
+
+        if applications_count > 0 {
+            return Err(AccountError::NotEmpty(applications_count));
+        }
+
+        let mut account = account_to_delete.clone();
+        account.deleted_date = Some(Utc::now());
+        account.is_deleted = true;
+
+        Ok(self.repository.update(&account)?)
+    }
+
+    /// restores a deleted Account.
+    pub fn restore(&self, account_to_restore: &Account) -> Result<Account, AccountError> {
+        if !account_to_restore.is_deleted {
+            return Err(AccountError::NotDeleted);
+        }
+
+        let mut account = account_to_restore.clone();
+        account.deleted_date = None;
+        account.is_deleted = false;
+
+        Ok(self.repository.update(&account)?)
+    }
+
     /// transforms a list of Account into a list of AccountListItemDTO
     pub fn to_list_dto(&self, accounts: Vec<Account>) -> Vec<AccountListItemDTO> {
         let mut list_dto = Vec::<AccountListItemDTO>::new();
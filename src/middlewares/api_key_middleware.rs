@@ -0,0 +1,132 @@
+use chrono::{DateTime, Utc};
+use thiserror::Error;
+
+use crate::{
+    core::password,
+    domain::{
+        model::{
+            api_key::{ApiKey, NewApiKey},
+            application::Application,
+        },
+        repository::{
+            api_key_repository::ApiKeyRepository, application_repository::ApplicationRepository,
+        },
+    },
+};
+
+/// leading part of every API key, making them easy to spot (e.g. in secret scanners).
+pub const API_KEY_MARKER: &str = "ak";
+
+/// Error states for API key authentication.
+#[derive(Debug, Error)]
+pub enum ApiKeyAuthenticationError {
+    #[error("Invalid API key")]
+    InvalidKey,
+    #[error("Revoked API key")]
+    RevokedKey,
+    #[error("API key expired since : {} ", _0)]
+    ExpiredKey(String),
+    #[error("Application {} not found.", _0)]
+    ApplicationNotFound(i32),
+}
+
+/// API key middleware.
+#[derive(Clone)]
+pub struct ApiKeyMiddleware {
+    repository: ApiKeyRepository,
+    application_repository: ApplicationRepository,
+}
+
+impl ApiKeyMiddleware {
+    /// constructor.
+    pub fn new(
+        repository: ApiKeyRepository,
+        application_repository: ApplicationRepository,
+    ) -> Self {
+        Self {
+            repository,
+            application_repository,
+        }
+    }
+
+    /// creates an API key for an application, returning it along with the clear key.
+    ///
+    /// The key is formatted as `ak_<prefix>_<secret>` : the prefix identifies the key, and only the hash of the whole key is stored.
+    /// The clear key cannot be retrieved afterwards.
+    pub fn create(
+        &self,
+        application: &Application,
+        name: &str,
+        scopes: Vec<&str>,
+        expiration_date: Option<DateTime<Utc>>,
+    ) -> anyhow::Result<(ApiKey, String)> {
+        let prefix = password::generate_simple_sized(8);
+        let clear_key = format!(
+            "{API_KEY_MARKER}_{prefix}_{}",
+            password::generate_simple_sized(40)
+        );
+        let key_hash = password::hash_token(&clear_key);
+
+        let api_key = self.repository.insert(NewApiKey {
+            application_id: application.id,
+            name,
+            prefix: &prefix,
+            key_hash: &key_hash,
+            scopes,
+            expiration_date,
+            created_date: Utc::now(),
+        })?;
+
+        Ok((api_key, clear_key))
+    }
+
+    /// authenticates a clear API key, returning its key and the application owning it.
+    pub fn authenticate(&self, clear_key: &str) -> anyhow::Result<(Application, ApiKey)> {
+        let parts = clear_key.split('_').collect::<Vec<&str>>();
+
+        if parts.len() != 3 || parts[0] != API_KEY_MARKER {
+            return Err(ApiKeyAuthenticationError::InvalidKey.into());
+        }
+
+        let api_key = self
+            .repository
+            .find_one_by_prefix(parts[1])?
+            .filter(|api_key| api_key.key_hash == password::hash_token(clear_key))
+            .ok_or(ApiKeyAuthenticationError::InvalidKey)?;
+
+        if api_key.is_revoked {
+            return Err(ApiKeyAuthenticationError::RevokedKey.into());
+        }
+
+        if let Some(expiration_date) = api_key.expiration_date {
+            if Utc::now() > expiration_date {
+                return Err(
+                    ApiKeyAuthenticationError::ExpiredKey(expiration_date.to_rfc3339()).into(),
+                );
+            }
+        }
+
+        let application = self
+            .application_repository
+            .find_one_by_id(api_key.application_id)?
+            .ok_or(ApiKeyAuthenticationError::ApplicationNotFound(
+                api_key.application_id,
+            ))?;
+
+        self.repository.touch_last_used(api_key.id)?;
+
+        Ok((application, api_key))
+    }
+
+    /// finds every API key, or only those of a given application.
+    pub fn find_all(&self, application_id: Option<i32>) -> anyhow::Result<Vec<ApiKey>> {
+        self.repository.find_all(application_id)
+    }
+
+    /// revokes an API key by its ID, returning `false` if it was already revoked or does not exist.
+    pub fn revoke(&self, id: &str) -> anyhow::Result<bool> {
+        let real_id = id.parse::<i32>()?;
+
+        self.repository.revoke(real_id)
+    }
+}
@@ -1,24 +1,57 @@
+use chrono::Utc;
+use thiserror::Error;
+use ulid::Ulid;
+
+use crate::core::{cursor::Cursor, guards::list_query::ListQuery};
 use crate::domain::{
-    dto::application::{ApplicationDetailsDTO, ApplicationListItemDTO},
+    dto::application::{
+        ApplicationDetailsDTO, ApplicationListItemDTO, NewApplicationInputDTO,
+        UpdateApplicationInputDTO,
+    },
     model::{
         application::{Application, NewApplication},
         user::User,
     },
-    repository::application_repository::ApplicationRepository,
+    repository::{
+        account_repository::AccountRepository, application_repository::ApplicationRepository,
+    },
 };
 
 use anyhow::Result;
 
+/// Error states for application management.
+#[derive(Debug, Error)]
+pub enum ApplicationError {
+    #[error("Account {} not found", _0)]
+    AccountNotFound(i32),
+    #[error("You cannot delete your own application")]
+    OwnApplication,
+    #[error("Application is not deleted")]
+    NotDeleted,
+    #[error("Internal error : {} ", _0)]
+    Internal(String),
+}
+
+impl From<anyhow::Error> for ApplicationError {
+    fn from(error: anyhow::Error) -> Self {
+        ApplicationError::Internal(error.to_string())
+    }
+}
+
 /// Application model middleware.
 #[derive(Clone)]
 pub struct ApplicationMiddleware {
     repository: ApplicationRepository,
+    account_repository: AccountRepository,
 }
 
 impl ApplicationMiddleware {
     /// constructor
-    pub fn new(repository: ApplicationRepository) -> Self {
-        Self { repository }
+    pub fn new(repository: ApplicationRepository, account_repository: AccountRepository) -> Self {
+        Self {
+            repository,
+            account_repository,
+        }
     }
 
     /// creates (e.g. inserts) an Application into the database.
@@ -29,33 +62,150 @@ impl ApplicationMiddleware {
     }
 
     /// finds an application by its ID
-    pub fn find_one_by_id(&self, id: &str) -> Result<Option<Application>> {
-        let real_id = id.parse::<i32>()?;
-
-        let account = self.repository.find_one_by_id(real_id)?;
+    pub fn find_one_by_id(&self, id: i32) -> Result<Option<Application>> {
+        let account = self.repository.find_one_by_id(id)?;
 
         Ok(account)
     }
 
     /// finds all applications for a given user.
     /// this function uses pagination.
-    pub fn find_for_user(&self, user: &User, page: u16, per_page: u16) -> Result<Vec<Application>> {
-        let applications = self
-            .repository
-            .find_all_for_user(user, page.into(), per_page.into())?;
+    pub fn find_for_user(
+        &self,
+        user: &User,
+        list_query: &ListQuery<ApplicationListItemDTO>,
+        page: u16,
+        per_page: u16,
+    ) -> Result<Vec<Application>> {
+        let applications =
+            self.repository
+                .find_all_for_user(user, list_query, page.into(), per_page.into())?;
 
         Ok(applications)
     }
 
+    /// finds the applications for a given user after a cursor.
+    /// this function uses keyset pagination.
+    pub fn find_for_user_after(
+        &self,
+        user: &User,
+        list_query: &ListQuery<ApplicationListItemDTO>,
+        cursor: Option<&Cursor>,
+        limit: u16,
+    ) -> Result<Vec<Application>> {
+        let applications =
+            self.repository
+                .find_all_for_user_after(user, list_query, cursor, limit.into())?;
+
+        Ok(applications)
+    }
+
+    /// counts all applications for a given user, e.g. the total of `find_for_user` pages.
+    pub fn count_for_user(
+        &self,
+        user: &User,
+        list_query: &ListQuery<ApplicationListItemDTO>,
+    ) -> Result<i64> {
+        let count = self.repository.count_all_for_user(user, list_query)?;
+
+        Ok(count)
+    }
+
     /// find a given application by its ID, for a given user.
-    pub fn find_one_for_user(&self, id: &str, user: &User) -> Result<Option<Application>> {
-        let real_id = id.parse::<i32>()?;
+    pub fn find_one_for_user(&self, id: i32, user: &User) -> Result<Option<Application>> {
+        let application = self.repository.find_for_user(id, user)?;
+
+        Ok(application)
+    }
 
-        let application = self.repository.find_for_user(real_id, user)?;
+    /// finds an application by its ID, even if it is deleted.
+    pub fn find_one_by_id_including_deleted(&self, id: i32) -> Result<Option<Application>> {
+        let application = self.repository.find_one_by_id_including_deleted(id)?;
 
         Ok(application)
     }
 
+    /// creates an application from NewApplicationInputDTO and register it to the database.
+    pub fn create_from_user_input(
+        &self,
+        dto: NewApplicationInputDTO,
+    ) -> Result<Application, ApplicationError> {
+        if self
+            .account_repository
+            .find_one_by_id(dto.account_id)?
+            .is_none()
+        {
+            return Err(ApplicationError::AccountNotFound(dto.account_id));
+        }
+
+        let ulid = Ulid::new().to_string();
+
+        Ok(self.create(NewApplication::new(
+            &ulid,
+            &dto.name,
+            &dto.contact_email,
+            dto.account_id,
+        ))?)
+    }
+
+    /// updates an application given a UpdateApplicationInputDTO, and register it to the database.
+    pub fn update_from_user_input(
+        &self,
+        to_update: &Application,
+        dto: UpdateApplicationInputDTO,
+    ) -> Result<Application, ApplicationError> {
+        let mut application = to_update.clone();
+        application.name = dto.name;
+        application.contact_email = dto.contact_email;
+
+        Ok(self.repository.update(&application)?)
+    }
+
+    /// apply deletion logic to an Application and register it as deleted in the database.
+    ///
+    /// The application of the deleter cannot be deleted, so it cannot lock itself out.
+    pub fn delete(
+        &self,
+        application_to_delete: &Application,
+        deleter: &User,
+    ) -> Result<Application, ApplicationError> {
+        if application_to_delete.id == deleter.application_id {
+            return Err(ApplicationError::OwnApplication);
+        }
+
+        let mut application = application_to_delete.clone();
+        application.deleted_date = Some(Utc::now());
+        application.is_deleted = true;
+
+        Ok(self.repository.update(&application)?)
+    }
+
+    /// restores a deleted Application, as long as its account is not deleted.
+    pub fn restore(
+        &self,
+        application_to_restore: &Application,
+    ) -> Result<Application, ApplicationError> {
+        if !application_to_restore.is_deleted {
+            return Err(ApplicationError::NotDeleted);
+        }
+
+        if self
+            .account_repository
+            .find_one_by_id(application_to_restore.account_id)?
+            .is_none()
+        {
+            return Err(ApplicationError::AccountNotFound(
+                application_to_restore.account_id,
+            ));
+        }
+
+        let mut application = application_to_restore.clone();
+        application.deleted_date = None;
+        application.is_deleted = false;
+
+        Ok(self.repository.update(&application)?)
+    }
+
     /// transforms a list of Application into a list of ApplicationListItemDTO
     pub fn to_list_dto(&self, applications: Vec<Application>) -> Vec<ApplicationListItemDTO> {
         let mut list = Vec::<ApplicationListItemDTO>::new();
@@ -72,3 +222,51 @@ impl ApplicationMiddleware {
         ApplicationDetailsDTO::from(application)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{
+        guards::list_query::FilterOperator,
+        testing::{create_test_application, create_test_user, get_test_db_state},
+    };
+
+    #[test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    fn test_super_admins_find_every_application() {
+        let db_state = get_test_db_state();
+        let application = create_test_application(&db_state);
+        let other_application = create_test_application(&db_state);
+
+        let application_middleware = ApplicationMiddleware::new(
+            ApplicationRepository::new(db_state.clone()),
+            AccountRepository::new(db_state.clone()),
+        );
+
+        let admin = create_test_user(&db_state, &application, &["ROLE_USER_ADMIN"]);
+        let super_admin = create_test_user(&db_state, &application, &["ROLE_SUPER_ADMIN"]);
+
+        let list_query = ListQuery::<ApplicationListItemDTO>::default().with_filter(
+            "id",
+            FilterOperator::Eq,
+            &other_application.id.to_string(),
+        );
+
+        let count = |user: &User| {
+            application_middleware
+                .count_for_user(user, &list_query)
+                .unwrap()
+        };
+
+        assert_eq!(count(&admin), 0);
+        assert_eq!(count(&super_admin), 1);
+        assert!(application_middleware
+            .find_one_for_user(other_application.id, &admin)
+            .unwrap()
+            .is_none());
+        assert!(application_middleware
+            .find_one_for_user(other_application.id, &super_admin)
+            .unwrap()
+            .is_some());
+    }
+}
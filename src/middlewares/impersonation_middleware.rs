@@ -0,0 +1,129 @@
+use chrono::{DateTime, Utc};
+use thiserror::Error;
+
+use crate::{
+    core::{
+        configuration::ConfigState,
+        jwt::{self, APIClaim},
+        jwt_keys::JWTKeyStore,
+    },
+    domain::{
+        model::{impersonation_log::NewImpersonationLog, user::User},
+        repository::{
+            impersonation_log_repository::ImpersonationLogRepository,
+            user_repository::UserRepository,
+        },
+    },
+};
+
+/// Error states for impersonation.
+#[derive(Debug, Error)]
+pub enum ImpersonationError {
+    #[error("User {} not found", _0)]
+    UserNotFound(i32),
+    #[error("You cannot impersonate yourself")]
+    SelfImpersonation,
+    #[error("You cannot impersonate a user having role {}", _0)]
+    NotAllowed(String),
+    #[error("Internal error : {} ", _0)]
+    Internal(String),
+}
+
+impl From<anyhow::Error> for ImpersonationError {
+    fn from(error: anyhow::Error) -> Self {
+        ImpersonationError::Internal(error.to_string())
+    }
+}
+
+/// Impersonation middleware, issuing short-lived tokens for administrators to act as another user.
+#[derive(Clone)]
+pub struct ImpersonationMiddleware {
+    repository: ImpersonationLogRepository,
+    user_repository: UserRepository,
+    jwt_key_store: JWTKeyStore,
+    configuration: ConfigState,
+}
+
+impl ImpersonationMiddleware {
+    /// constructor.
+    pub fn new(
+        repository: ImpersonationLogRepository,
+        user_repository: UserRepository,
+        jwt_key_store: JWTKeyStore,
+        configuration: ConfigState,
+    ) -> Self {
+        Self {
+            repository,
+            user_repository,
+            jwt_key_store,
+            configuration,
+        }
+    }
+
+    /// issues a token for an administrator to act as another user of its application, and records it.
+    ///
+    /// The administrator must hold every role of the impersonated user (e.g. an admin cannot become a super admin).
+    /// The token lasts `impersonation_ttl` seconds (defaults to 15 minutes), and no refresh token is issued.
+    pub fn impersonate(
+        &self,
+        impersonator: &User,
+        impersonated_id: i32,
+        reason: Option<&str>,
+    ) -> Result<(String, DateTime<Utc>), ImpersonationError> {
+        if impersonator.id == impersonated_id {
+            return Err(ImpersonationError::SelfImpersonation);
+        }
+
+        let impersonated = self
+            .user_repository
+            .find_one_for_user_and_application(impersonated_id, impersonator.application_id)?
+            .ok_or(ImpersonationError::UserNotFound(impersonated_id))?;
+
+        if let Some(role) = impersonated
+            .roles
+            .iter()
+            .find(|role| !impersonator.roles.contains(role))
+        {
+            return Err(ImpersonationError::NotAllowed(role.clone()));
+        }
+
+        let claim = APIClaim {
+            user_id: Some(impersonated.id),
+            roles: impersonated.roles.clone(),
+            username: impersonated.login.clone(),
+            client_id: None,
+            scopes: Vec::new(),
+            impersonator_id: Some(impersonator.id),
+        };
+
+        let ttl = self
+            .configuration
+            .get_int_or_default("impersonation_ttl", 900);
+        let issuer = self.configuration.get_string("package.name")?;
+
+        let token = jwt::encode(&self.jwt_key_store, claim, ttl, &issuer)?;
+
+        // the token is decoded back to record its ID and expiration as signed.
+        let claims = jwt::decode(&self.jwt_key_store, &token, &issuer)?;
+
+        let jti = claims
+            .jwt_id
+            .clone()
+            .ok_or_else(|| ImpersonationError::Internal("Token has no ID".into()))?;
+
+        let expiration_date = jwt::get_expiration_date(&claims)
+            .ok_or_else(|| ImpersonationError::Internal("Token has no expiration".into()))?;
+
+        self.repository.insert(NewImpersonationLog {
+            impersonator_id: impersonator.id,
+            impersonated_id: impersonated.id,
+            application_id: impersonator.application_id,
+            jti: &jti,
+            reason,
+            expiration_date,
+            created_date: Utc::now(),
+        })?;
+
+        Ok((token, expiration_date))
+    }
+}
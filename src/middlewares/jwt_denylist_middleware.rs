@@ -0,0 +1,51 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+
+use crate::domain::{
+    model::jwt_denylist::NewJWTDenylistEntry,
+    repository::jwt_denylist_repository::JWTDenylistRepository,
+};
+
+/// JWT denylist middleware.
+#[derive(Clone)]
+pub struct JWTDenylistMiddleware {
+    repository: JWTDenylistRepository,
+}
+
+impl JWTDenylistMiddleware {
+    /// constructor.
+    pub fn new(repository: JWTDenylistRepository) -> Self {
+        Self { repository }
+    }
+
+    /// denies a JWT by its ID (e.g. `jti` claim) until its expiration date, along with the user it was issued to, if any.
+    pub fn deny(
+        &self,
+        jti: &str,
+        user_id: Option<i32>,
+        expiration_date: DateTime<Utc>,
+    ) -> Result<()> {
+        self.repository.insert(NewJWTDenylistEntry {
+            jti,
+            user_id,
+            expiration_date,
+            created_date: Utc::now(),
+        })?;
+
+        Ok(())
+    }
+
+    /// checks if a JWT ID (e.g. `jti` claim) is denied.
+    pub fn is_denied(&self, jti: &str) -> Result<bool> {
+        let entry = self.repository.find_one_by_jti(jti)?;
+
+        Ok(entry.is_some())
+    }
+
+    /// removes every entry whose JWT is expired, as those tokens are rejected anyway.
+    pub fn purge_expired(&self) -> Result<usize> {
+        let purged = self.repository.delete_expired_before(Utc::now())?;
+
+        Ok(purged)
+    }
+}
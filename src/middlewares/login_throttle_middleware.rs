@@ -0,0 +1,276 @@
+use chrono::{DateTime, Duration, Utc};
+
+use crate::{
+    core::configuration::ConfigState,
+    domain::{
+        model::login_throttle::{LoginThrottle, NewLoginThrottle, SCOPE_IP, SCOPE_LOGIN},
+        repository::login_throttle_repository::LoginThrottleRepository,
+    },
+};
+
+/// Maximum length of a tracked identifier (e.g. a login given by a client).
+const MAX_IDENTIFIER_LENGTH: usize = 255;
+
+/// Login throttle (e.g. brute-force protection) middleware.
+///
+/// Failed logins are tracked per login and per client IP. Each failure delays the next attempt
+/// exponentially, until a temporary lockout once the maximum number of failures is reached.
+#[derive(Clone)]
+pub struct LoginThrottleMiddleware {
+    repository: LoginThrottleRepository,
+    configuration: ConfigState,
+}
+
+impl LoginThrottleMiddleware {
+    /// constructor.
+    pub fn new(repository: LoginThrottleRepository, configuration: ConfigState) -> Self {
+        Self {
+            repository,
+            configuration,
+        }
+    }
+
+    /// gets the number of seconds to wait before a login may be attempted, if it is throttled.
+    pub fn get_retry_after(
+        &self,
+        login: &str,
+        client_ip: Option<&str>,
+    ) -> anyhow::Result<Option<i64>> {
+        let mut throttles = vec![self.repository.find_one(SCOPE_LOGIN, &normalize(login))?];
+
+        if let Some(client_ip) = client_ip {
+            throttles.push(self.repository.find_one(SCOPE_IP, client_ip)?);
+        }
+
+        let now = Utc::now();
+
+        let retry_after = throttles
+            .into_iter()
+            .flatten()
+            .filter_map(|throttle| throttle.locked_until)
+            .filter(|locked_until| *locked_until > now)
+            .map(|locked_until| (locked_until - now).num_seconds() + 1)
+            .max();
+
+        Ok(retry_after)
+    }
+
+    /// registers a failed login for the given login and client IP.
+    pub fn register_failure(&self, login: &str, client_ip: Option<&str>) -> anyhow::Result<()> {
+        let max_attempts = self
+            .configuration
+            .get_int_or_default("login_max_attempts", 5);
+
+        self.record_failure(SCOPE_LOGIN, &normalize(login), max_attempts)?;
+
+        if let Some(client_ip) = client_ip {
+            let max_ip_attempts = self
+                .configuration
+                .get_int_or_default("login_ip_max_attempts", 50);
+
+            self.record_failure(SCOPE_IP, client_ip, max_ip_attempts)?;
+        }
+
+        Ok(())
+    }
+
+    /// registers a successful login, clearing failures of the login.
+    ///
+    /// Failures of the client IP are kept, so a single valid account cannot be used to reset them.
+    pub fn register_success(&self, login: &str) -> anyhow::Result<()> {
+        self.repository.delete(SCOPE_LOGIN, &normalize(login))?;
+
+        Ok(())
+    }
+
+    /// finds every login or client IP currently locked.
+    pub fn find_all_locked(&self) -> anyhow::Result<Vec<LoginThrottle>> {
+        self.repository.find_all_locked_after(Utc::now())
+    }
+
+    /// clears failures and lockout of a login.
+    pub fn clear_login(&self, login: &str) -> anyhow::Result<usize> {
+        self.repository.delete(SCOPE_LOGIN, &normalize(login))
+    }
+
+    /// clears failures and lockout of a client IP.
+    pub fn clear_ip(&self, client_ip: &str) -> anyhow::Result<usize> {
+        self.repository.delete(SCOPE_IP, client_ip)
+    }
+
+    /// clears every failure and lockout.
+    pub fn clear_all(&self) -> anyhow::Result<usize> {
+        self.repository.delete_all()
+    }
+
+    /// PRIVATE - records a failure for an entry, computing its backoff or lockout.
+    fn record_failure(
+        &self,
+        scope: &str,
+        identifier: &str,
+        max_attempts: i64,
+    ) -> anyhow::Result<LoginThrottle> {
+        let backoff_base = self
+            .configuration
+            .get_int_or_default("login_backoff_base", 1);
+        let lockout_duration = self
+            .configuration
+            .get_int_or_default("login_lockout_duration", 900);
+
+        let now = Utc::now();
+
+        let throttle = self.repository.record_failure(
+            NewLoginThrottle {
+                scope,
+                identifier,
+                failure_count: 0,
+                last_failure_date: now,
+            },
+            |throttle| {
+                throttle.locked_until = Some(apply_failure(
+                    throttle,
+                    now,
+                    max_attempts,
+                    backoff_base,
+                    lockout_duration,
+                ));
+            },
+        )?;
+
+        Ok(throttle)
+    }
+}
+
+/// PRIVATE - counts a failure on an entry and computes the date until which it is locked.
+///
+/// Failures older than the lockout duration are forgotten.
+fn apply_failure(
+    throttle: &mut LoginThrottle,
+    now: DateTime<Utc>,
+    max_attempts: i64,
+    backoff_base: i64,
+    lockout_duration: i64,
+) -> DateTime<Utc> {
+    if now - throttle.last_failure_date > Duration::seconds(lockout_duration) {
+        throttle.failure_count = 0;
+    }
+
+    throttle.failure_count += 1;
+    throttle.last_failure_date = now;
+
+    if i64::from(throttle.failure_count) >= max_attempts {
+        return now + Duration::seconds(lockout_duration);
+    }
+
+    let exponent = (throttle.failure_count - 1).clamp(0, 30) as u32;
+    let backoff = backoff_base
+        .saturating_mul(2i64.pow(exponent))
+        .min(lockout_duration);
+
+    now + Duration::seconds(backoff)
+}
+
+/// PRIVATE - normalizes a login before tracking it.
+fn normalize(login: &str) -> String {
+    login.trim().chars().take(MAX_IDENTIFIER_LENGTH).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::testing::get_test_db_state;
+
+    const MAX_ATTEMPTS: i64 = 5;
+    const BACKOFF_BASE: i64 = 2;
+    const LOCKOUT_DURATION: i64 = 900;
+
+    fn get_throttle(failure_count: i32, last_failure_date: DateTime<Utc>) -> LoginThrottle {
+        LoginThrottle {
+            id: 1,
+            scope: SCOPE_LOGIN.into(),
+            identifier: "login".into(),
+            failure_count,
+            last_failure_date,
+            locked_until: None,
+        }
+    }
+
+    /// applies a failure, giving the number of seconds the entry is locked for.
+    fn fail(throttle: &mut LoginThrottle, now: DateTime<Utc>, backoff_base: i64) -> i64 {
+        let locked_until =
+            apply_failure(throttle, now, MAX_ATTEMPTS, backoff_base, LOCKOUT_DURATION);
+
+        (locked_until - now).num_seconds()
+    }
+
+    #[test]
+    fn test_backoff_doubles_until_the_lockout() {
+        let now = Utc::now();
+        let mut throttle = get_throttle(0, now);
+
+        let delays = (0..MAX_ATTEMPTS)
+            .map(|_| fail(&mut throttle, now, BACKOFF_BASE))
+            .collect::<Vec<i64>>();
+
+        assert_eq!(delays, vec![2, 4, 8, 16, LOCKOUT_DURATION]);
+        assert_eq!(throttle.failure_count, 5);
+        assert_eq!(throttle.last_failure_date, now);
+    }
+
+    #[test]
+    fn test_backoff_is_capped_by_the_lockout_duration() {
+        let now = Utc::now();
+        let mut throttle = get_throttle(0, now);
+
+        assert_eq!(fail(&mut throttle, now, 1000), LOCKOUT_DURATION);
+        // failure counts past the limit of the exponent do not overflow.
+        let mut throttle = get_throttle(i32::MAX - 1, now);
+        assert_eq!(fail(&mut throttle, now, BACKOFF_BASE), LOCKOUT_DURATION);
+    }
+
+    #[test]
+    fn test_failures_older_than_the_lockout_duration_are_forgotten() {
+        let now = Utc::now();
+
+        let mut recent = get_throttle(3, now - Duration::seconds(LOCKOUT_DURATION));
+        assert_eq!(fail(&mut recent, now, BACKOFF_BASE), 16);
+        assert_eq!(recent.failure_count, 4);
+
+        let mut outdated = get_throttle(3, now - Duration::seconds(LOCKOUT_DURATION + 1));
+        assert_eq!(fail(&mut outdated, now, BACKOFF_BASE), 2);
+        assert_eq!(outdated.failure_count, 1);
+    }
+
+    #[test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    fn test_success_clears_failures_of_the_login_only() {
+        let login_throttle_middleware = LoginThrottleMiddleware::new(
+            LoginThrottleRepository::new(get_test_db_state()),
+            ConfigState::default(),
+        );
+        let login = ulid::Ulid::new().to_string();
+        let client_ip = format!("test-{login}");
+
+        login_throttle_middleware
+            .register_failure(&login, Some(&client_ip))
+            .unwrap();
+
+        assert!(login_throttle_middleware
+            .get_retry_after(&login, None)
+            .unwrap()
+            .is_some());
+
+        login_throttle_middleware.register_success(&login).unwrap();
+
+        assert!(login_throttle_middleware
+            .get_retry_after(&login, None)
+            .unwrap()
+            .is_none());
+        assert!(login_throttle_middleware
+            .get_retry_after(&login, Some(&client_ip))
+            .unwrap()
+            .is_some());
+
+        login_throttle_middleware.clear_ip(&client_ip).unwrap();
+    }
+}
@@ -1,5 +1,13 @@
 pub mod account_middleware;
+pub mod api_key_middleware;
 pub mod application_middleware;
 pub mod cron_log_middleware;
+pub mod impersonation_middleware;
+pub mod jwt_denylist_middleware;
+pub mod login_throttle_middleware;
+pub mod oauth_middleware;
+pub mod password_reset_middleware;
 pub mod refresh_token_middleware;
+pub mod role_middleware;
+pub mod totp_middleware;
 pub mod user_middleware;
@@ -0,0 +1,345 @@
+use chrono::Utc;
+use jwt_simple::prelude::JWTClaims;
+use thiserror::Error;
+use ulid::Ulid;
+
+use crate::{
+    core::{
+        configuration::ConfigState,
+        jwt::{self, APIClaim},
+        jwt_keys::JWTKeyStore,
+        password, security,
+    },
+    domain::{
+        model::{
+            application::Application,
+            oauth_client::{NewOAuthClient, OAuthClient},
+            user::User,
+        },
+        repository::{
+            application_repository::ApplicationRepository,
+            oauth_client_repository::OAuthClientRepository,
+            refresh_token_repository::RefreshTokenRepository, user_repository::UserRepository,
+        },
+    },
+    middlewares::jwt_denylist_middleware::JWTDenylistMiddleware,
+};
+
+/// Error states of the OAuth2 endpoints, as defined by RFC 6749 (section 5.2).
+#[derive(Debug, Error)]
+pub enum OAuthError {
+    #[error("{}", _0)]
+    InvalidRequest(String),
+    #[error("Client authentication failed")]
+    InvalidClient,
+    #[error("{}", _0)]
+    InvalidGrant(String),
+    #[error("Grant type {} is not supported", _0)]
+    UnsupportedGrantType(String),
+    #[error("Scope {} is not granted to the client", _0)]
+    InvalidScope(String),
+    #[error("Internal error : {} ", _0)]
+    Internal(String),
+}
+
+impl OAuthError {
+    /// gets the RFC 6749 error code (e.g. `invalid_client`).
+    pub fn get_code(&self) -> &'static str {
+        match self {
+            OAuthError::InvalidRequest(_) => "invalid_request",
+            OAuthError::InvalidClient => "invalid_client",
+            OAuthError::InvalidGrant(_) => "invalid_grant",
+            OAuthError::UnsupportedGrantType(_) => "unsupported_grant_type",
+            OAuthError::InvalidScope(_) => "invalid_scope",
+            OAuthError::Internal(_) => "server_error",
+        }
+    }
+}
+
+impl From<anyhow::Error> for OAuthError {
+    fn from(error: anyhow::Error) -> Self {
+        OAuthError::Internal(error.to_string())
+    }
+}
+
+/// An OAuth client authenticated with a token issued to it on its own, along with its application.
+#[derive(Debug, Clone)]
+pub struct AuthenticatedClient {
+    pub application: Application,
+    pub client: OAuthClient,
+    pub claims: JWTClaims<APIClaim>,
+}
+
+/// OAuth2 middleware, handling clients registered per application and the tokens issued to them.
+#[derive(Clone)]
+pub struct OAuthMiddleware {
+    repository: OAuthClientRepository,
+    user_repository: UserRepository,
+    application_repository: ApplicationRepository,
+    refresh_token_repository: RefreshTokenRepository,
+    jwt_denylist_middleware: JWTDenylistMiddleware,
+    jwt_key_store: JWTKeyStore,
+    configuration: ConfigState,
+}
+
+impl OAuthMiddleware {
+    /// constructor.
+    pub fn new(
+        repository: OAuthClientRepository,
+        user_repository: UserRepository,
+        application_repository: ApplicationRepository,
+        refresh_token_repository: RefreshTokenRepository,
+        jwt_denylist_middleware: JWTDenylistMiddleware,
+        jwt_key_store: JWTKeyStore,
+        configuration: ConfigState,
+    ) -> Self {
+        Self {
+            repository,
+            user_repository,
+            application_repository,
+            refresh_token_repository,
+            jwt_denylist_middleware,
+            jwt_key_store,
+            configuration,
+        }
+    }
+
+    /// registers a client for an application, returning it along with its clear secret.
+    ///
+    /// Only the hash of the secret is stored, it cannot be retrieved afterwards.
+    pub fn create_client(
+        &self,
+        application: &Application,
+        name: &str,
+        scopes: Vec<&str>,
+    ) -> anyhow::Result<(OAuthClient, String)> {
+        let client_id = Ulid::new().to_string();
+        let client_secret = password::generate_simple_sized(64);
+
+        let client = self.repository.insert(NewOAuthClient {
+            application_id: application.id,
+            name,
+            client_id: &client_id,
+            client_secret_hash: &password::hash_token(&client_secret),
+            scopes,
+            created_date: Utc::now(),
+        })?;
+
+        Ok((client, client_secret))
+    }
+
+    /// authenticates a client with its ID and secret.
+    pub fn authenticate_client(
+        &self,
+        client_id: &str,
+        client_secret: &str,
+    ) -> Result<OAuthClient, OAuthError> {
+        self.repository
+            .find_one_by_client_id(client_id)?
+            .filter(|client| !client.is_revoked)
+            .filter(|client| client.client_secret_hash == password::hash_token(client_secret))
+            .ok_or(OAuthError::InvalidClient)
+    }
+
+    /// computes the scopes granted to a client for a space separated list of requested scopes.
+    ///
+    /// Every scope of the client is granted when none is requested.
+    pub fn grant_scopes(
+        &self,
+        client: &OAuthClient,
+        requested_scopes: Option<&str>,
+    ) -> Result<Vec<String>, OAuthError> {
+        let requested_scopes = match requested_scopes.map(|scopes| scopes.trim()) {
+            None | Some("") => return Ok(client.scopes.clone()),
+            Some(scopes) => scopes.split_whitespace().collect::<Vec<&str>>(),
+        };
+
+        if let Some(scope) = requested_scopes
+            .iter()
+            .find(|scope| !security::is_scope_granted(&client.scopes, scope))
+        {
+            return Err(OAuthError::InvalidScope(scope.to_string()));
+        }
+
+        Ok(requested_scopes
+            .iter()
+            .map(|scope| scope.to_string())
+            .collect())
+    }
+
+    /// creates a JWT for a client, acting on its own or on behalf of one of its application's users.
+    pub fn create_jwt(
+        &self,
+        client: &OAuthClient,
+        user: Option<&User>,
+        scopes: &[String],
+    ) -> anyhow::Result<String> {
+        let claim = APIClaim {
+            user_id: user.map(|user| user.id),
+            roles: user.map(|user| user.roles.clone()).unwrap_or_default(),
+            username: user
+                .map(|user| user.login.clone())
+                .unwrap_or_else(|| client.client_id.clone()),
+            client_id: Some(client.client_id.clone()),
+            scopes: scopes.to_vec(),
+            impersonator_id: None,
+        };
+
+        let issuer = self.configuration.get_string("package.name")?;
+
+        jwt::encode(&self.jwt_key_store, claim, self.get_token_ttl(), &issuer)
+    }
+
+    /// gets the lifetime of access tokens, in seconds.
+    pub fn get_token_ttl(&self) -> i64 {
+        self.configuration.get_int_or_default("jwt_ttl", 3600)
+    }
+
+    /// decodes an access token for a client, if the token is active and belongs to the client's application.
+    pub fn introspect(
+        &self,
+        client: &OAuthClient,
+        token: &str,
+    ) -> anyhow::Result<Option<JWTClaims<APIClaim>>> {
+        let issuer = self.configuration.get_string("package.name")?;
+
+        let claims = match jwt::decode(&self.jwt_key_store, token, &issuer) {
+            Ok(claims) => claims,
+            Err(_) => return Ok(None),
+        };
+
+        if let Some(jti) = &claims.jwt_id {
+            if self.jwt_denylist_middleware.is_denied(jti)? {
+                return Ok(None);
+            }
+        }
+
+        if !self.is_claim_for_application(&claims.custom, client.application_id)? {
+            return Ok(None);
+        }
+
+        Ok(Some(claims))
+    }
+
+    /// authenticates an access token issued to a client on its own (e.g. `client_credentials` grant), giving the client and its application.
+    ///
+    /// Tokens issued on behalf of a user, invalid or revoked tokens, and tokens of revoked clients give `None`.
+    pub fn authenticate_client_token(
+        &self,
+        token: &str,
+    ) -> anyhow::Result<Option<AuthenticatedClient>> {
+        let issuer = self.configuration.get_string("package.name")?;
+
+        let claims = match jwt::decode(&self.jwt_key_store, token, &issuer) {
+            Ok(claims) => claims,
+            Err(_) => return Ok(None),
+        };
+
+        let client_id = match (&claims.custom.client_id, claims.custom.user_id) {
+            (Some(client_id), None) => client_id.clone(),
+            _ => return Ok(None),
+        };
+
+        if let Some(jti) = &claims.jwt_id {
+            if self.jwt_denylist_middleware.is_denied(jti)? {
+                return Ok(None);
+            }
+        }
+
+        let client = match self
+            .repository
+            .find_one_by_client_id(&client_id)?
+            .filter(|client| !client.is_revoked)
+        {
+            Some(client) => client,
+            None => return Ok(None),
+        };
+
+        let application = self
+            .application_repository
+            .find_one_by_id(client.application_id)?;
+
+        Ok(application.map(|application| AuthenticatedClient {
+            application,
+            client,
+            claims,
+        }))
+    }
+
+    /// revokes an access token belonging to the client's application, or a refresh token (with its whole family) issued to the client.
+    ///
+    /// Both token types are looked up whatever the `token_type_hint`, and unknown tokens are ignored, as allowed by RFC 7009.
+    pub fn revoke_token(&self, client: &OAuthClient, token: &str) -> anyhow::Result<()> {
+        if let Some(claims) = self.introspect(client, token)? {
+            if let (Some(jti), Some(expiration_date)) =
+                (&claims.jwt_id, jwt::get_expiration_date(&claims))
+            {
+                self.jwt_denylist_middleware
+                    .deny(jti, claims.custom.user_id, expiration_date)?;
+            }
+
+            return Ok(());
+        }
+
+        if let Ok(refresh_token) = self.refresh_token_repository.find_by_token(token) {
+            if refresh_token.client_id.as_deref() == Some(client.client_id.as_str()) {
+                self.refresh_token_repository
+                    .revoke_family(&refresh_token.family)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// finds a client of an application by its client ID, if it has not been revoked.
+    pub fn find_client(
+        &self,
+        client_id: &str,
+        application_id: i32,
+    ) -> anyhow::Result<Option<OAuthClient>> {
+        let client = self
+            .repository
+            .find_one_by_client_id(client_id)?
+            .filter(|client| !client.is_revoked && client.application_id == application_id);
+
+        Ok(client)
+    }
+
+    /// finds every client, or only those of a given application.
+    pub fn find_all_clients(
+        &self,
+        application_id: Option<i32>,
+    ) -> anyhow::Result<Vec<OAuthClient>> {
+        self.repository.find_all(application_id)
+    }
+
+    /// revokes a client by its client ID, returning `false` if it was already revoked or does not exist.
+    ///
+    /// Tokens already issued to the client on its own are refused from then on. Those issued on behalf of users are reported as inactive by introspection, but remain valid for this API until they expire.
+    pub fn revoke_client(&self, client_id: &str) -> anyhow::Result<bool> {
+        self.repository.revoke(client_id)
+    }
+
+    /// PRIVATE - checks if claims were issued to a client or a user of the given application.
+    fn is_claim_for_application(
+        &self,
+        claim: &APIClaim,
+        application_id: i32,
+    ) -> anyhow::Result<bool> {
+        if let Some(client_id) = &claim.client_id {
+            let client = self.repository.find_one_by_client_id(client_id)?;
+
+            return Ok(client.is_some_and(|client| {
+                !client.is_revoked && client.application_id == application_id
+            }));
+        }
+
+        if let Some(user_id) = claim.user_id {
+            let user = self.user_repository.find_one_by_id(user_id)?;
+
+            return Ok(user.is_some_and(|user| user.application_id == application_id));
+        }
+
+        Ok(false)
+    }
+}
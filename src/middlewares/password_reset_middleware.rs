@@ -0,0 +1,130 @@
+use std::sync::Arc;
+
+use chrono::{Duration, Utc};
+use thiserror::Error;
+
+use crate::{
+    core::{configuration::ConfigState, notifier::Notifier, password},
+    domain::{
+        model::{
+            password_reset_token::{NewPasswordResetToken, PasswordResetToken},
+            user::User,
+        },
+        repository::{
+            password_reset_token_repository::PasswordResetTokenRepository,
+            user_repository::UserRepository,
+        },
+    },
+};
+
+/// Error states for password reset token validation.
+#[derive(Debug, Error)]
+pub enum PasswordResetError {
+    #[error("Password reset token not found")]
+    NotFound,
+    #[error("Password reset token expired since : {} ", _0)]
+    Expired(String),
+    #[error("Password reset token already used")]
+    AlreadyUsed,
+}
+
+/// Password reset middleware.
+#[derive(Clone)]
+pub struct PasswordResetMiddleware {
+    repository: PasswordResetTokenRepository,
+    user_repository: UserRepository,
+    notifier: Arc<dyn Notifier>,
+    configuration: ConfigState,
+}
+
+impl PasswordResetMiddleware {
+    /// constructor.
+    pub fn new(
+        repository: PasswordResetTokenRepository,
+        user_repository: UserRepository,
+        notifier: Arc<dyn Notifier>,
+        configuration: ConfigState,
+    ) -> Self {
+        Self {
+            repository,
+            user_repository,
+            notifier,
+            configuration,
+        }
+    }
+
+    /// issues a password reset token for every user matching the given login or email, and notifies them.
+    ///
+    /// Only the hash of the token is stored. Previous pending tokens of those users are invalidated.
+    /// Returns the number of notified users, which must never be disclosed to the requester.
+    pub fn request(&self, login_or_email: &str) -> anyhow::Result<usize> {
+        let users = match self.user_repository.find_one_by_login(login_or_email)? {
+            Some(user) => vec![user],
+            None => self.user_repository.find_all_by_email(login_or_email)?,
+        };
+
+        for user in users.iter() {
+            self.issue_for_user(user)?;
+        }
+
+        Ok(users.len())
+    }
+
+    /// validates a password reset token and consumes it, returning the user whose password may be reset.
+    pub fn consume(&self, token: &str) -> Result<User, PasswordResetError> {
+        let reset_token = self
+            .repository
+            .find_one_by_token_hash(&password::hash_token(token))
+            .ok()
+            .flatten()
+            .ok_or(PasswordResetError::NotFound)?;
+
+        if reset_token.is_used {
+            return Err(PasswordResetError::AlreadyUsed);
+        }
+
+        if Utc::now() > reset_token.expiration_date {
+            return Err(PasswordResetError::Expired(
+                reset_token.expiration_date.to_rfc3339(),
+            ));
+        }
+
+        let user = self
+            .user_repository
+            .find_one_by_id(reset_token.user_id)
+            .ok()
+            .flatten()
+            .ok_or(PasswordResetError::NotFound)?;
+
+        // the token could have been consumed concurrently.
+        match self.repository.consume(reset_token.id) {
+            Ok(true) => Ok(user),
+            Ok(false) => Err(PasswordResetError::AlreadyUsed),
+            Err(_) => Err(PasswordResetError::NotFound),
+        }
+    }
+
+    /// PRIVATE - creates a new token for a user and sends it through the notifier.
+    fn issue_for_user(&self, user: &User) -> anyhow::Result<PasswordResetToken> {
+        let token = password::generate_simple_sized(64);
+        let token_hash = password::hash_token(&token);
+
+        let reset_ttl = self
+            .configuration
+            .get_int_or_default("password_reset_ttl", 3600);
+
+        self.repository.invalidate_all_for_user(user.id)?;
+
+        let reset_token = self.repository.insert(NewPasswordResetToken {
+            token_hash: &token_hash,
+            user_id: user.id,
+            expiration_date: Utc::now() + Duration::seconds(reset_ttl),
+            created_date: Utc::now(),
+        })?;
+
+        self.notifier
+            .notify_password_reset(user, &token, reset_token.expiration_date)?;
+
+        Ok(reset_token)
+    }
+}
@@ -1,10 +1,13 @@
+use anyhow::bail;
 use chrono::{Duration, Utc};
 use thiserror::Error;
+use ulid::Ulid;
 
 use crate::{
     core::{configuration::ConfigState, password},
     domain::{
         model::{
+            oauth_client::OAuthClient,
             refresh_token::{NewRefreshToken, RefreshToken},
             user::User,
         },
@@ -19,6 +22,10 @@ pub enum JWTRefreshTokenValidationError {
     NotFound(String),
     #[error("Token expired since : {} ", _0)]
     Expired(String),
+    #[error("Token revoked : {} ", _0)]
+    Revoked(String),
+    #[error("Token already used, token family {} revoked", _0)]
+    Reused(String),
 }
 
 /// RefreshToken middleware.
@@ -35,30 +42,39 @@ impl RefreshTokenMiddleware {
     }
 
     /// Generates a valid refresh token for the given user, registering it into the database.
+    ///
+    /// The token starts a new token family, which will be shared by all of its successors.
     pub fn generate_for_user(&self, user: &User) -> anyhow::Result<RefreshToken> {
-        let token = password::generate_simple_sized(128);
-        let refresh_ttl = self.config.get_int_or_default("jwt_refresh_ttl", 86400);
-        let validity_date = Utc::now() + Duration::seconds(refresh_ttl);
-
-        let new_refresh_token = NewRefreshToken {
-            token: token.as_str(),
-            user_id: user.id,
-            validity_date,
-        };
-
-        let refresh_token = self.repository.insert(new_refresh_token)?;
+        self.generate(user, None)
+    }
 
-        Ok(refresh_token)
+    /// Generates a refresh token for an OAuth client acting on behalf of the given user.
+    ///
+    /// The token starts its own token family, and is only accepted from that client.
+    pub fn generate_for_client(
+        &self,
+        user: &User,
+        client: &OAuthClient,
+    ) -> anyhow::Result<RefreshToken> {
+        self.generate(user, Some(&client.client_id))
     }
 
-    /// checks if a given refresh token is a valid one.
+    /// checks if a given refresh token is a valid one, for the given OAuth client (`None` for session tokens).
+    ///
+    /// Presenting a token that has already been used revokes its whole token family.
+    /// Tokens issued to another client (or to none) are unknown, so they cannot trigger a revocation.
     pub fn is_valid(
         &self,
         refresh_token: &str,
+        client_id: Option<&str>,
     ) -> Result<RefreshToken, JWTRefreshTokenValidationError> {
-        let token = self.repository.find_by_token(refresh_token);
+        let token = self
+            .repository
+            .find_by_token(refresh_token)
+            .ok()
+            .filter(|token| token.client_id.as_deref() == client_id);
 
-        if token.is_err() {
+        if token.is_none() {
             return Err(JWTRefreshTokenValidationError::NotFound(
                 refresh_token.into(),
             ));
@@ -66,6 +82,16 @@ impl RefreshTokenMiddleware {
 
         let token = token.unwrap();
 
+        if token.is_revoked {
+            return Err(JWTRefreshTokenValidationError::Revoked(
+                token.revoked_date.unwrap_or_else(Utc::now).to_rfc3339(),
+            ));
+        }
+
+        if token.is_used {
+            return Err(self.detect_reuse(&token));
+        }
+
         let now_utc = Utc::now();
 
         if now_utc > token.validity_date {
@@ -76,4 +102,191 @@ impl RefreshTokenMiddleware {
 
         Ok(token)
     }
+
+    /// consumes a valid refresh token, and issues its successor in the same token family.
+    ///
+    /// If the token has been consumed concurrently, this is considered as a reuse and the family is revoked.
+    pub fn rotate(
+        &self,
+        used_token: &RefreshToken,
+    ) -> Result<RefreshToken, JWTRefreshTokenValidationError> {
+        let token = password::generate_simple_sized(128);
+
+        let successor = NewRefreshToken {
+            token: token.as_str(),
+            user_id: used_token.user_id,
+            validity_date: self.get_validity_date(),
+            family: used_token.family.as_str(),
+            client_id: used_token.client_id.as_deref(),
+        };
+
+        let rotated = self.repository.rotate(used_token.id, successor);
+
+        match rotated {
+            Ok(Some(refresh_token)) => Ok(refresh_token),
+            Ok(None) => Err(self.detect_reuse(used_token)),
+            Err(_) => Err(JWTRefreshTokenValidationError::NotFound(
+                used_token.token.clone(),
+            )),
+        }
+    }
+
+    /// revokes every token of the family of the given refresh token.
+    pub fn revoke_family(&self, refresh_token: &RefreshToken) -> anyhow::Result<usize> {
+        let revoked = self.repository.revoke_family(&refresh_token.family)?;
+
+        Ok(revoked)
+    }
+
+    /// revokes the session (e.g. the token family) of a refresh token presented by its owner.
+    pub fn revoke_for_user(&self, refresh_token: &str, user: &User) -> anyhow::Result<usize> {
+        let token = self.repository.find_by_token(refresh_token)?;
+
+        if token.user_id != user.id {
+            bail!("Refresh token does not belong to user {}", user.id);
+        }
+
+        self.revoke_family(&token)
+    }
+
+    /// revokes every refresh token of the given user, closing all of its sessions.
+    pub fn revoke_all_for_user(&self, user: &User) -> anyhow::Result<usize> {
+        let revoked = self.repository.revoke_all_for_user(user.id)?;
+
+        Ok(revoked)
+    }
+
+    /// PRIVATE - registers a refresh token starting a new token family.
+    fn generate(&self, user: &User, client_id: Option<&str>) -> anyhow::Result<RefreshToken> {
+        let token = password::generate_simple_sized(128);
+        let token_family = Ulid::new().to_string();
+
+        let new_refresh_token = NewRefreshToken {
+            token: token.as_str(),
+            user_id: user.id,
+            validity_date: self.get_validity_date(),
+            family: token_family.as_str(),
+            client_id,
+        };
+
+        let refresh_token = self.repository.insert(new_refresh_token)?;
+
+        Ok(refresh_token)
+    }
+
+    /// PRIVATE - revokes the family of a reused token and builds the matching error.
+    fn detect_reuse(&self, reused_token: &RefreshToken) -> JWTRefreshTokenValidationError {
+        // a failure here must not let the reused token through, the error is returned anyway.
+        self.revoke_family(reused_token).ok();
+
+        JWTRefreshTokenValidationError::Reused(reused_token.family.clone())
+    }
+
+    /// PRIVATE - computes the validity date of a newly issued refresh token.
+    fn get_validity_date(&self) -> chrono::DateTime<Utc> {
+        let refresh_ttl = self.config.get_int_or_default("jwt_refresh_ttl", 86400);
+
+        Utc::now() + Duration::seconds(refresh_ttl)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Barrier, thread};
+
+    use super::*;
+    use crate::core::testing::{create_test_application, create_test_user, get_test_db_state};
+
+    fn get_middleware() -> RefreshTokenMiddleware {
+        RefreshTokenMiddleware::new(
+            RefreshTokenRepository::new(get_test_db_state()),
+            ConfigState::default(),
+        )
+    }
+
+    fn generate_token(refresh_token_middleware: &RefreshTokenMiddleware) -> RefreshToken {
+        let db_state = get_test_db_state();
+        let user = create_test_user(&db_state, &create_test_application(&db_state), &[]);
+
+        refresh_token_middleware.generate_for_user(&user).unwrap()
+    }
+
+    #[test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    fn test_rotation_issues_a_successor_and_invalidates_the_used_token() {
+        let refresh_token_middleware = get_middleware();
+        let token = generate_token(&refresh_token_middleware);
+
+        let used = refresh_token_middleware
+            .is_valid(&token.token, None)
+            .unwrap();
+        let successor = refresh_token_middleware.rotate(&used).unwrap();
+
+        assert_ne!(successor.token, token.token);
+        assert_eq!(successor.family, token.family);
+        assert_eq!(successor.user_id, token.user_id);
+        assert!(refresh_token_middleware
+            .is_valid(&successor.token, None)
+            .is_ok());
+        assert!(matches!(
+            refresh_token_middleware.rotate(&used),
+            Err(JWTRefreshTokenValidationError::Reused(_))
+        ));
+    }
+
+    #[test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    fn test_reusing_a_rotated_token_revokes_its_family() {
+        let refresh_token_middleware = get_middleware();
+        let token = generate_token(&refresh_token_middleware);
+
+        let used = refresh_token_middleware
+            .is_valid(&token.token, None)
+            .unwrap();
+        let successor = refresh_token_middleware.rotate(&used).unwrap();
+
+        assert!(matches!(
+            refresh_token_middleware.is_valid(&token.token, None),
+            Err(JWTRefreshTokenValidationError::Reused(family)) if family == token.family
+        ));
+        assert!(matches!(
+            refresh_token_middleware.is_valid(&successor.token, None),
+            Err(JWTRefreshTokenValidationError::Revoked(_))
+        ));
+    }
+
+    #[test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    fn test_concurrent_rotations_of_a_token_yield_a_single_successor() {
+        let refresh_token_middleware = get_middleware();
+        let token = generate_token(&refresh_token_middleware);
+        let used = refresh_token_middleware
+            .is_valid(&token.token, None)
+            .unwrap();
+        let barrier = Barrier::new(2);
+
+        let rotations = thread::scope(|scope| {
+            let handles = (0..2)
+                .map(|_| {
+                    scope.spawn(|| {
+                        barrier.wait();
+                        refresh_token_middleware.rotate(&used)
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .collect::<Vec<_>>()
+        });
+
+        assert_eq!(
+            rotations.iter().filter(|rotation| rotation.is_ok()).count(),
+            1
+        );
+        assert!(rotations
+            .iter()
+            .any(|rotation| matches!(rotation, Err(JWTRefreshTokenValidationError::Reused(_)))));
+    }
 }
@@ -0,0 +1,288 @@
+use chrono::Utc;
+use thiserror::Error;
+
+use crate::{
+    core::{role_hierarchy::RoleHierarchy, security::is_a},
+    domain::{
+        dto::role::{NewRoleInputDTO, RoleDTO},
+        model::{
+            role::{NewRole, Role},
+            user::{User, ROLE_SUPER_ADMIN},
+        },
+        repository::{role_repository::RoleRepository, user_repository::UserRepository},
+    },
+};
+
+/// Error states for role management.
+#[derive(Debug, Error)]
+pub enum RoleError {
+    #[error("Role {} is not defined", _0)]
+    UnknownRole(String),
+    #[error("Role {} already exists", _0)]
+    AlreadyExists(String),
+    #[error("Role {} is still granted to {} user(s)", _0, _1)]
+    InUse(String, i64),
+    #[error("Role {} is a built-in role", _0)]
+    BuiltIn(String),
+    #[error("Role {} is not held by the granter", _0)]
+    NotHeld(String),
+    #[error("Role {} cannot be implied by an application role", _0)]
+    NotImpliable(String),
+    #[error("Internal error : {} ", _0)]
+    Internal(String),
+}
+
+impl From<anyhow::Error> for RoleError {
+    fn from(error: anyhow::Error) -> Self {
+        RoleError::Internal(error.to_string())
+    }
+}
+
+/// Role middleware, resolving role hierarchies and managing applications' custom roles.
+#[derive(Clone)]
+pub struct RoleMiddleware {
+    repository: RoleRepository,
+    user_repository: UserRepository,
+    hierarchy: RoleHierarchy,
+}
+
+impl RoleMiddleware {
+    /// constructor.
+    pub fn new(
+        repository: RoleRepository,
+        user_repository: UserRepository,
+        hierarchy: RoleHierarchy,
+    ) -> Self {
+        Self {
+            repository,
+            user_repository,
+            hierarchy,
+        }
+    }
+
+    /// resolves the effective roles of a user, e.g. its roles and every role they imply.
+    pub fn resolve_roles(&self, user: &User) -> anyhow::Result<Vec<String>> {
+        let custom_roles = self
+            .repository
+            .find_all_for_application(user.application_id)?;
+
+        Ok(self
+            .hierarchy
+            .with_roles(&custom_roles)
+            .resolve(&user.roles))
+    }
+
+    /// finds every custom role of an application.
+    pub fn find_all_for_application(&self, application_id: i32) -> anyhow::Result<Vec<Role>> {
+        self.repository.find_all_for_application(application_id)
+    }
+
+    /// checks if a role is either a built-in role or a custom role of an application.
+    pub fn is_defined(&self, application_id: i32, role_name: &str) -> anyhow::Result<bool> {
+        if self.is_builtin(role_name) {
+            return Ok(true);
+        }
+
+        Ok(self
+            .repository
+            .find_one_for_application(application_id, role_name)?
+            .is_some())
+    }
+
+    /// checks if a user can grant a role : the role must be held by the granter, and defined for its application.
+    ///
+    /// Built-in roles are defined for every application, so administrators could otherwise hand out more than they hold (e.g. `ROLE_SUPER_ADMIN`).
+    pub fn check_grantable(&self, granter: &User, role_name: &str) -> Result<(), RoleError> {
+        // the roles of connected users are their effective roles, including implied ones.
+        if !is_a(role_name, granter) {
+            return Err(RoleError::NotHeld(role_name.into()));
+        }
+
+        if !self.is_defined(granter.application_id, role_name)? {
+            return Err(RoleError::UnknownRole(role_name.into()));
+        }
+
+        Ok(())
+    }
+
+    /// creates a custom role for the application of its creator.
+    ///
+    /// Implied roles, and the roles they imply in turn, must be held by the creator : `ROLE_SUPER_ADMIN` can never be implied, as it manages every application.
+    pub fn create(&self, creator: &User, dto: NewRoleInputDTO) -> Result<Role, RoleError> {
+        let application_id = creator.application_id;
+
+        if self.is_builtin(&dto.name) {
+            return Err(RoleError::BuiltIn(dto.name));
+        }
+
+        if self
+            .repository
+            .find_one_for_application(application_id, &dto.name)?
+            .is_some()
+        {
+            return Err(RoleError::AlreadyExists(dto.name));
+        }
+
+        for implied_role in dto.implied_roles.iter() {
+            if !self.is_defined(application_id, implied_role)? {
+                return Err(RoleError::UnknownRole(implied_role.clone()));
+            }
+        }
+
+        let custom_roles = self.repository.find_all_for_application(application_id)?;
+
+        for implied_role in self
+            .hierarchy
+            .with_roles(&custom_roles)
+            .resolve(&dto.implied_roles)
+        {
+            if implied_role == ROLE_SUPER_ADMIN {
+                return Err(RoleError::NotImpliable(implied_role));
+            }
+
+            // the roles of connected users are their effective roles, including implied ones.
+            if !is_a(&implied_role, creator) {
+                return Err(RoleError::NotHeld(implied_role));
+            }
+        }
+
+        let role = self.repository.insert(NewRole {
+            application_id,
+            name: &dto.name,
+            implied_roles: dto.implied_roles.iter().map(|role| role.as_str()).collect(),
+            description: dto.description.as_deref(),
+            created_date: Utc::now(),
+        })?;
+
+        Ok(role)
+    }
+
+    /// deletes a custom role of an application, as long as no user is granted it.
+    pub fn delete(&self, application_id: i32, role_name: &str) -> Result<(), RoleError> {
+        if self.is_builtin(role_name) {
+            return Err(RoleError::BuiltIn(role_name.into()));
+        }
+
+        let role = self
+            .repository
+            .find_one_for_application(application_id, role_name)?
+            .ok_or_else(|| RoleError::UnknownRole(role_name.into()))?;
+
+        let users_count = self
+            .user_repository
+            .count_for_application_and_role(application_id, role_name)?;
+
+        if users_count > 0 {
+            return Err(RoleError::InUse(role_name.into(), users_count));
+        }
+
+        self.repository.delete(role.id)?;
+
+        Ok(())
+    }
+
+    /// converts custom roles to a list of DTOs, preceded by the built-in roles.
+    pub fn to_list_dto(&self, custom_roles: Vec<Role>) -> Vec<RoleDTO> {
+        let builtin_roles = self
+            .hierarchy
+            .get_role_names()
+            .into_iter()
+            .map(|role_name| RoleDTO {
+                implied_roles: self.hierarchy.get_implied_roles(&role_name),
+                name: role_name,
+                description: None,
+                is_builtin: true,
+            });
+
+        builtin_roles
+            .chain(custom_roles.into_iter().map(RoleDTO::from))
+            .collect()
+    }
+
+    /// PRIVATE - checks if a role is a built-in role (e.g. known by the API or the configured hierarchy).
+    fn is_builtin(&self, role_name: &str) -> bool {
+        self.hierarchy
+            .get_role_names()
+            .iter()
+            .any(|builtin_role| builtin_role == role_name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{
+        role_hierarchy::DEFAULT_ROLE_HIERARCHY,
+        testing::{create_test_application, create_test_user, get_test_db_state},
+    };
+
+    #[test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    fn test_roles_are_only_grantable_by_their_holders() {
+        let db_state = get_test_db_state();
+        let application = create_test_application(&db_state);
+
+        let role_middleware = RoleMiddleware::new(
+            RoleRepository::new(db_state.clone()),
+            UserRepository::new(db_state.clone()),
+            RoleHierarchy::parse(DEFAULT_ROLE_HIERARCHY).unwrap(),
+        );
+
+        let mut admin = create_test_user(&db_state, &application, &["ROLE_USER_ADMIN"]);
+        admin.roles = role_middleware.resolve_roles(&admin).unwrap();
+
+        assert!(role_middleware.check_grantable(&admin, "ROLE_USER").is_ok());
+        assert!(matches!(
+            role_middleware.check_grantable(&admin, "ROLE_SUPER_ADMIN"),
+            Err(RoleError::NotHeld(_))
+        ));
+        assert!(matches!(
+            role_middleware.check_grantable(&admin, "ROLE_UNKNOWN"),
+            Err(RoleError::NotHeld(_))
+        ));
+    }
+
+    #[test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    fn test_custom_roles_only_imply_roles_held_by_their_creator() {
+        let db_state = get_test_db_state();
+        let application = create_test_application(&db_state);
+
+        let role_middleware = RoleMiddleware::new(
+            RoleRepository::new(db_state.clone()),
+            UserRepository::new(db_state.clone()),
+            RoleHierarchy::parse(DEFAULT_ROLE_HIERARCHY).unwrap(),
+        );
+
+        let mut admin = create_test_user(&db_state, &application, &["ROLE_USER_ADMIN"]);
+        admin.roles = role_middleware.resolve_roles(&admin).unwrap();
+
+        let mut super_admin = create_test_user(&db_state, &application, &["ROLE_SUPER_ADMIN"]);
+        super_admin.roles = role_middleware.resolve_roles(&super_admin).unwrap();
+
+        let new_role = |name: &str, implied_roles: &[&str]| NewRoleInputDTO {
+            name: name.into(),
+            implied_roles: implied_roles.iter().map(|role| role.to_string()).collect(),
+            description: None,
+        };
+
+        assert!(role_middleware
+            .create(&admin, new_role("ROLE_EDITOR", &["ROLE_USER"]))
+            .is_ok());
+        assert!(role_middleware
+            .create(&super_admin, new_role("ROLE_AUDITOR", &[]))
+            .is_ok());
+        assert!(matches!(
+            role_middleware.create(&admin, new_role("ROLE_REVIEWER", &["ROLE_AUDITOR"])),
+            Err(RoleError::NotHeld(_))
+        ));
+        assert!(matches!(
+            role_middleware.create(&admin, new_role("ROLE_ROOT", &["ROLE_SUPER_ADMIN"])),
+            Err(RoleError::NotImpliable(_))
+        ));
+        assert!(matches!(
+            role_middleware.create(&super_admin, new_role("ROLE_ROOT", &["ROLE_SUPER_ADMIN"])),
+            Err(RoleError::NotImpliable(_))
+        ));
+    }
+}
@@ -0,0 +1,236 @@
+use chrono::{DateTime, Duration, Utc};
+use thiserror::Error;
+
+use crate::{
+    core::{configuration::ConfigState, password, secret_cipher::SecretCipher, totp},
+    domain::{
+        model::{
+            mfa_challenge::NewMFAChallenge, recovery_code::NewRecoveryCode, user::User,
+            user_totp::NewUserTOTP,
+        },
+        repository::{
+            mfa_challenge_repository::MFAChallengeRepository,
+            recovery_code_repository::RecoveryCodeRepository, user_repository::UserRepository,
+            user_totp_repository::UserTOTPRepository,
+        },
+    },
+};
+
+/// Number of recovery codes given to a user upon enrolment.
+const RECOVERY_CODES_COUNT: usize = 10;
+
+/// Number of codes that can be tried against a single MFA challenge.
+const MAX_CHALLENGE_ATTEMPTS: i32 = 5;
+
+/// Error states for two-factor authentication.
+#[derive(Debug, Error)]
+pub enum MFAError {
+    #[error("Two-factor authentication is already enabled")]
+    AlreadyEnabled,
+    #[error("Two-factor authentication is not enrolled")]
+    NotEnrolled,
+    #[error("Invalid two-factor authentication code")]
+    InvalidCode,
+    #[error("MFA challenge not found")]
+    ChallengeNotFound,
+    #[error("MFA challenge expired since : {} ", _0)]
+    ChallengeExpired(String),
+    #[error("Too many attempts for this MFA challenge")]
+    TooManyAttempts,
+    #[error("Internal error : {} ", _0)]
+    Internal(String),
+}
+
+impl From<anyhow::Error> for MFAError {
+    fn from(error: anyhow::Error) -> Self {
+        MFAError::Internal(error.to_string())
+    }
+}
+
+/// TOTP (e.g. two-factor authentication) middleware.
+#[derive(Clone)]
+pub struct TOTPMiddleware {
+    repository: UserTOTPRepository,
+    recovery_code_repository: RecoveryCodeRepository,
+    mfa_challenge_repository: MFAChallengeRepository,
+    user_repository: UserRepository,
+    configuration: ConfigState,
+    secret_cipher: SecretCipher,
+}
+
+impl TOTPMiddleware {
+    /// constructor.
+    pub fn new(
+        repository: UserTOTPRepository,
+        recovery_code_repository: RecoveryCodeRepository,
+        mfa_challenge_repository: MFAChallengeRepository,
+        user_repository: UserRepository,
+        configuration: ConfigState,
+        secret_cipher: SecretCipher,
+    ) -> Self {
+        Self {
+            repository,
+            recovery_code_repository,
+            mfa_challenge_repository,
+            user_repository,
+            configuration,
+            secret_cipher,
+        }
+    }
+
+    /// starts the enrolment of a user, returning a new secret and its `otpauth://` URI.
+    ///
+    /// The secret is pending until a code generated from it is verified (see `confirm_enrolment`).
+    pub fn enroll(&self, user: &User) -> Result<(String, String), MFAError> {
+        if self.is_enabled_for_user(user)? {
+            return Err(MFAError::AlreadyEnabled);
+        }
+
+        let secret = totp::generate_secret();
+        // the secret is only stored encrypted, so a database read alone cannot bypass two-factor authentication.
+        let encrypted_secret = self.secret_cipher.encrypt(&secret)?;
+
+        self.repository.upsert_pending(NewUserTOTP {
+            user_id: user.id,
+            secret: &encrypted_secret,
+            created_date: Utc::now(),
+        })?;
+
+        let issuer = self.configuration.get_string("package.name")?;
+        let account = user.email.clone().unwrap_or_else(|| user.login.clone());
+
+        let uri = totp::get_otpauth_uri(&issuer, &account, &secret);
+
+        Ok((secret, uri))
+    }
+
+    /// enables two-factor authentication once the user proved its authenticator app works.
+    ///
+    /// Returns one-time recovery codes, which are only stored hashed and cannot be displayed again.
+    pub fn confirm_enrolment(&self, user: &User, code: &str) -> Result<Vec<String>, MFAError> {
+        let user_totp = self
+            .repository
+            .find_one_by_user_id(user.id)?
+            .ok_or(MFAError::NotEnrolled)?;
+
+        if user_totp.is_enabled {
+            return Err(MFAError::AlreadyEnabled);
+        }
+
+        let secret = self.secret_cipher.decrypt(&user_totp.secret)?;
+        let step = totp::verify(&secret, code, Utc::now()).ok_or(MFAError::InvalidCode)?;
+
+        self.repository.enable(user_totp.id, step as i64)?;
+
+        let recovery_codes = self.generate_recovery_codes(user)?;
+
+        Ok(recovery_codes)
+    }
+
+    /// checks if a user has enabled two-factor authentication.
+    pub fn is_enabled_for_user(&self, user: &User) -> anyhow::Result<bool> {
+        let user_totp = self.repository.find_one_by_user_id(user.id)?;
+
+        Ok(user_totp
+            .map(|user_totp| user_totp.is_enabled)
+            .unwrap_or(false))
+    }
+
+    /// creates a short-lived challenge for a user who passed the first authentication factor.
+    ///
+    /// Returns the challenge token, to be sent back along with a code, and its expiration date.
+    pub fn create_challenge(&self, user: &User) -> anyhow::Result<(String, DateTime<Utc>)> {
+        let token = password::generate_simple_sized(64);
+        let challenge_ttl = self
+            .configuration
+            .get_int_or_default("mfa_challenge_ttl", 300);
+
+        let challenge = self.mfa_challenge_repository.insert(NewMFAChallenge {
+            token_hash: &password::hash_token(&token),
+            user_id: user.id,
+            expiration_date: Utc::now() + Duration::seconds(challenge_ttl),
+            created_date: Utc::now(),
+        })?;
+
+        Ok((token, challenge.expiration_date))
+    }
+
+    /// completes a challenge with a TOTP code or a recovery code, returning the authenticated user.
+    pub fn complete_challenge(&self, challenge_token: &str, code: &str) -> Result<User, MFAError> {
+        let challenge = self
+            .mfa_challenge_repository
+            .find_one_by_token_hash(&password::hash_token(challenge_token))?
+            .filter(|challenge| !challenge.is_used)
+            .ok_or(MFAError::ChallengeNotFound)?;
+
+        if Utc::now() > challenge.expiration_date {
+            return Err(MFAError::ChallengeExpired(
+                challenge.expiration_date.to_rfc3339(),
+            ));
+        }
+
+        if challenge.attempts >= MAX_CHALLENGE_ATTEMPTS {
+            return Err(MFAError::TooManyAttempts);
+        }
+
+        self.mfa_challenge_repository
+            .increment_attempts(challenge.id)?;
+
+        let user_totp = self
+            .repository
+            .find_one_by_user_id(challenge.user_id)?
+            .filter(|user_totp| user_totp.is_enabled)
+            .ok_or(MFAError::NotEnrolled)?;
+
+        let secret = self.secret_cipher.decrypt(&user_totp.secret)?;
+
+        let is_code_valid = match totp::verify(&secret, code, Utc::now()) {
+            // a code can only be used once, even within its validity window.
+            Some(step) => self.repository.use_step(user_totp.id, step as i64)?,
+            None => self
+                .recovery_code_repository
+                .consume(challenge.user_id, &hash_recovery_code(code))?,
+        };
+
+        if !is_code_valid {
+            return Err(MFAError::InvalidCode);
+        }
+
+        if !self.mfa_challenge_repository.consume(challenge.id)? {
+            return Err(MFAError::ChallengeNotFound);
+        }
+
+        let user = self
+            .user_repository
+            .find_one_by_id(challenge.user_id)?
+            .ok_or(MFAError::ChallengeNotFound)?;
+
+        Ok(user)
+    }
+
+    /// PRIVATE - generates new recovery codes for a user, replacing the previous ones.
+    fn generate_recovery_codes(&self, user: &User) -> anyhow::Result<Vec<String>> {
+        let recovery_codes = (0..RECOVERY_CODES_COUNT)
+            .map(|_| password::generate_simple_sized(10).to_lowercase())
+            .collect::<Vec<String>>();
+
+        let new_codes = recovery_codes
+            .iter()
+            .map(|code| NewRecoveryCode {
+                user_id: user.id,
+                code_hash: hash_recovery_code(code),
+                created_date: Utc::now(),
+            })
+            .collect();
+
+        self.recovery_code_repository
+            .replace_all_for_user(user.id, new_codes)?;
+
+        Ok(recovery_codes)
+    }
+}
+
+/// PRIVATE - hashes a recovery code, ignoring case and surrounding spaces.
+fn hash_recovery_code(code: &str) -> String {
+    password::hash_token(&code.trim().to_lowercase())
+}
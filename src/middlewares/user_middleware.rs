@@ -1,23 +1,34 @@
 use anyhow::bail;
-use argon2::password_hash::SaltString;
-use chrono::Utc;
+use argon2::{password_hash::SaltString, Params};
+use chrono::{DateTime, Utc};
+use jwt_simple::prelude::JWTClaims;
 use thiserror::Error;
 
 use crate::{
     core::{
         configuration::ConfigState,
-        jwt,
-        password::{self, generate_salt, hash},
+        cursor::Cursor,
+        guards::{
+            connected_application::ConnectedApplication,
+            list_query::{FilterOperator, ListQuery},
+        },
+        jwt::{self, APIClaim},
+        jwt_keys::JWTKeyStore,
+        password::{self, generate_salt, hash_with_params},
         security::is_admin,
     },
     domain::{
         dto::{
             auth::LoginInputDTO,
-            user::{NewUserInputDTO, UpdateUserInputDTO, UserDetailsDTO, UserListItemDTO},
+            user::{
+                NewUserInputDTO, UpdateProfileInputDTO, UpdateUserInputDTO, UserDetailsDTO,
+                UserListItemDTO,
+            },
         },
-        model::user::{NewUser, User, ROLE_USER, ROLE_USER_ADMIN},
+        model::user::{NewUser, User, ROLE_USER},
         repository::user_repository::UserRepository,
     },
+    middlewares::jwt_denylist_middleware::JWTDenylistMiddleware,
 };
 
 /// Error states for authentication
@@ -34,6 +45,8 @@ pub enum AuthenticationError {
 pub enum JWTAuthenticationError {
     #[error("Invalid token")]
     InvalidToken,
+    #[error("Revoked token")]
+    RevokedToken,
     #[error("User {} not found.", _0)]
     UserNotFound(i32),
 }
@@ -42,26 +55,45 @@ pub enum JWTAuthenticationError {
 #[derive(Clone)]
 pub struct UserMiddleware {
     repository: UserRepository,
+    jwt_denylist_middleware: JWTDenylistMiddleware,
+    jwt_key_store: JWTKeyStore,
     configuration: ConfigState,
 }
 
 impl UserMiddleware {
     /// constructor.
-    pub fn new(repository: UserRepository, configuration: ConfigState) -> Self {
+    pub fn new(
+        repository: UserRepository,
+        jwt_denylist_middleware: JWTDenylistMiddleware,
+        jwt_key_store: JWTKeyStore,
+        configuration: ConfigState,
+    ) -> Self {
         Self {
             repository,
+            jwt_denylist_middleware,
+            jwt_key_store,
             configuration,
         }
     }
 
     /// authenticates a user with a given LoginInputDTO.
+    ///
+    /// A password hashed with outdated argon2 parameters is transparently rehashed with the configured ones.
+    /// Errors other than an `AuthenticationError` mean the authentication could not be processed (e.g. database failure).
     pub fn authenticate_user_from_input(&self, input: &LoginInputDTO) -> anyhow::Result<User> {
-        let user_found = self.repository.load_user_by_login(input.login.as_str());
+        let user_found = self.repository.load_user_by_login(input.login.as_str())?;
 
-        if let Ok(user) = user_found {
+        if let Some(user) = user_found {
             let is_password_valid = password::compare_hashed(&input.password, &user.password);
 
             if is_password_valid {
+                if password::needs_rehash(&user.password, &self.get_hash_params()) {
+                    // a failure to upgrade the hash must not prevent authentication.
+                    if let Ok(rehashed_user) = self.set_password(&user, &input.password) {
+                        return Ok(rehashed_user);
+                    }
+                }
+
                 return Ok(user);
             }
 
@@ -72,10 +104,15 @@ impl UserMiddleware {
     }
 
     /// authenticates a user from a given JWT token. This will also validate the given JWT prior to authentication.
-    pub fn authenticate_user_from_jwt(&self, jwt_token: &str) -> anyhow::Result<User> {
+    ///
+    /// Returns the authenticated user along with the decoded claims of the token.
+    pub fn authenticate_user_from_jwt(
+        &self,
+        jwt_token: &str,
+    ) -> anyhow::Result<(User, JWTClaims<APIClaim>)> {
         let issuer = self.configuration.get_string("package.name").unwrap();
 
-        let jwt_validation_result = jwt::decode(jwt_token, issuer.as_str());
+        let jwt_validation_result = jwt::decode(&self.jwt_key_store, jwt_token, issuer.as_str());
 
         if jwt_validation_result.is_err() {
             return Err(JWTAuthenticationError::InvalidToken.into());
@@ -83,27 +120,71 @@ impl UserMiddleware {
 
         let jwt_claims = jwt_validation_result.unwrap();
 
-        let user_fetch_result = self.repository.find_one_by_id(jwt_claims.custom.user_id)?;
+        if let Some(jti) = &jwt_claims.jwt_id {
+            if self.jwt_denylist_middleware.is_denied(jti)? {
+                return Err(JWTAuthenticationError::RevokedToken.into());
+            }
+        }
+
+        // tokens issued to OAuth clients (e.g. `client_credentials` grant) do not authenticate any user.
+        let user_id = match jwt_claims.custom.user_id {
+            Some(user_id) => user_id,
+            None => return Err(JWTAuthenticationError::InvalidToken.into()),
+        };
+
+        let user_fetch_result = self.repository.find_one_by_id(user_id)?;
 
         if user_fetch_result.is_none() {
-            return Err(JWTAuthenticationError::UserNotFound(jwt_claims.custom.user_id).into());
+            return Err(JWTAuthenticationError::UserNotFound(user_id).into());
+        }
+
+        let user = user_fetch_result.unwrap();
+
+        if Self::is_issued_before(&jwt_claims, user.tokens_revoked_date) {
+            return Err(JWTAuthenticationError::RevokedToken.into());
         }
 
-        Ok(user_fetch_result.unwrap())
+        Ok((user, jwt_claims))
+    }
+
+    /// revokes every JWT issued to a user so far (e.g. on its own, or to OAuth clients on its behalf).
+    pub fn revoke_all_jwt(&self, user: &User) -> anyhow::Result<User> {
+        self.repository.revoke_tokens(user.id, Utc::now())
+    }
+
+    /// finds the administrator impersonating the user of decoded JWT claims, if any.
+    ///
+    /// A token whose impersonator no longer exists is refused, as any other token of a deleted user.
+    pub fn find_impersonator(
+        &self,
+        jwt_claims: &JWTClaims<APIClaim>,
+    ) -> anyhow::Result<Option<User>> {
+        let impersonator_id = match jwt_claims.custom.impersonator_id {
+            Some(impersonator_id) => impersonator_id,
+            None => return Ok(None),
+        };
+
+        match self.repository.find_one_by_id(impersonator_id)? {
+            Some(impersonator) => Ok(Some(impersonator)),
+            None => Err(JWTAuthenticationError::UserNotFound(impersonator_id).into()),
+        }
     }
 
     /// create a valid JWT token for a given user.
     pub fn create_jwt_for_user(&self, user: &User) -> anyhow::Result<String> {
-        let claim = jwt::APIClaim {
-            user_id: user.id,
+        let claim = APIClaim {
+            user_id: Some(user.id),
             roles: user.roles.clone(),
             username: user.login.clone(),
+            client_id: None,
+            scopes: Vec::new(),
+            impersonator_id: None,
         };
 
         let jwt_ttl = self.configuration.get_int_or_default("jwt_ttl", 3600);
         let issuer = self.configuration.get_string("package.name")?;
 
-        let jwt_token = jwt::encode(claim, jwt_ttl, &issuer)?;
+        let jwt_token = jwt::encode(&self.jwt_key_store, claim, jwt_ttl, &issuer)?;
 
         Ok(jwt_token)
     }
@@ -124,25 +205,100 @@ impl UserMiddleware {
         Ok(user)
     }
 
-    /// finds all users for a given user.
+    /// finds all users for a given user, filtered and sorted by a list query.
     /// this function use pagination.
     pub fn find_for_user(
         &self,
         user: &User,
+        list_query: &ListQuery<UserListItemDTO>,
+        page: i32,
+        per_page: i32,
+    ) -> anyhow::Result<Vec<User>> {
+        let users = self.repository.find_all_for_application_id(
+            user.application_id,
+            &Self::restrict_list_query(user, list_query),
+            page,
+            per_page,
+        )?;
+
+        Ok(users)
+    }
+
+    /// finds the users for a given user after a cursor, filtered by a list query.
+    /// this function uses keyset pagination.
+    pub fn find_for_user_after(
+        &self,
+        user: &User,
+        list_query: &ListQuery<UserListItemDTO>,
+        cursor: Option<&Cursor>,
+        limit: i32,
+    ) -> anyhow::Result<Vec<User>> {
+        let users = self.repository.find_all_for_application_id_after(
+            user.application_id,
+            &Self::restrict_list_query(user, list_query),
+            cursor,
+            limit,
+        )?;
+
+        Ok(users)
+    }
+
+    /// counts all users for a given user, e.g. the total of `find_for_user` pages.
+    pub fn count_for_user(
+        &self,
+        user: &User,
+        list_query: &ListQuery<UserListItemDTO>,
+    ) -> anyhow::Result<i64> {
+        let count = self.repository.count_all_for_application_id(
+            user.application_id,
+            &Self::restrict_list_query(user, list_query),
+        )?;
+
+        Ok(count)
+    }
+
+    /// finds the users of an application authenticated with an API key, filtered by a list query.
+    pub fn find_for_application(
+        &self,
+        application: &ConnectedApplication,
+        list_query: &ListQuery<UserListItemDTO>,
         page: i32,
         per_page: i32,
     ) -> anyhow::Result<Vec<User>> {
+        let users = self.repository.find_all_for_application_id(
+            application.application.id,
+            list_query,
+            page,
+            per_page,
+        )?;
+
+        Ok(users)
+    }
+
+    /// counts the users of an application authenticated with an API key, filtered by a list query.
+    pub fn count_for_application(
+        &self,
+        application: &ConnectedApplication,
+        list_query: &ListQuery<UserListItemDTO>,
+    ) -> anyhow::Result<i64> {
+        let count = self
+            .repository
+            .count_all_for_application_id(application.application.id, list_query)?;
+
+        Ok(count)
+    }
+
+    /// PRIVATE - restricts a list query to what a user can see : users only see themselves, unless they are admins.
+    fn restrict_list_query(
+        user: &User,
+        list_query: &ListQuery<UserListItemDTO>,
+    ) -> ListQuery<UserListItemDTO> {
         if is_admin(user) {
-            let users =
-                self.repository
-                    .find_all_for_application_id(user.application_id, page, per_page)?;
-
-            Ok(users)
-        } else if page == 1 {
-            let itself = user.clone();
-            Ok(vec![itself])
+            list_query.clone()
         } else {
-            Ok(vec![])
+            list_query
+                .clone()
+                .with_filter("id", FilterOperator::Eq, &user.id.to_string())
         }
     }
 
@@ -164,36 +320,32 @@ impl UserMiddleware {
         }
     }
 
-    /// promotes a user (e.g. add the ADMIN role to it)
-    pub fn promote(&self, user: &User) -> anyhow::Result<User> {
-        if user.roles.contains(&ROLE_USER_ADMIN.into()) {
-            bail!("User already promoted !");
-        }
+    /// promotes a user (e.g. grants a role to it).
+    ///
+    /// The stored roles of the user are reloaded, so roles implied by the hierarchy are never persisted.
+    pub fn promote(&self, user: &User, role: &str) -> anyhow::Result<User> {
+        let mut roles = self.get_stored_roles(user)?;
 
-        let mut user = user.clone();
-        user.roles.push(ROLE_USER_ADMIN.into());
+        if roles.iter().any(|user_role| user_role == role) {
+            bail!("User already has role {role} !");
+        }
 
-        let user = self.update(&user)?;
+        roles.push(role.into());
 
-        Ok(user)
+        self.repository.update_roles(user.id, roles)
     }
 
-    /// demotes a user (e.g. remove the ADMIN role from it)
-    pub fn demote(&self, user: &User) -> anyhow::Result<User> {
-        if !user.roles.contains(&ROLE_USER_ADMIN.into()) {
-            bail!("User not promoted !");
-        }
-
-        let mut user = user.clone();
+    /// demotes a user (e.g. removes a role from it).
+    pub fn demote(&self, user: &User, role: &str) -> anyhow::Result<User> {
+        let mut roles = self.get_stored_roles(user)?;
 
-        let mut roles = user.roles.clone();
-        roles.retain(|elem| elem != ROLE_USER_ADMIN);
-
-        user.roles = roles;
+        if !roles.iter().any(|user_role| user_role == role) {
+            bail!("User does not have role {role} !");
+        }
 
-        let user = self.update(&user)?;
+        roles.retain(|user_role| user_role != role);
 
-        Ok(user)
+        self.repository.update_roles(user.id, roles)
     }
 
     /// creates a user from NewUserInputDTO and register it to the database.
@@ -243,6 +395,25 @@ impl UserMiddleware {
         self.update(&user)
     }
 
+    /// updates the profile of a user given a UpdateProfileInputDTO, leaving missing fields untouched.
+    pub fn update_profile(&self, user: &User, dto: UpdateProfileInputDTO) -> anyhow::Result<User> {
+        let mut user = user.clone();
+
+        if let Some(first_name) = dto.first_name {
+            user.first_name = Some(first_name);
+        }
+
+        if let Some(last_name) = dto.last_name {
+            user.last_name = Some(last_name);
+        }
+
+        if let Some(email) = dto.email {
+            user.email = Some(email);
+        }
+
+        self.update(&user)
+    }
+
     /// transforms a NewUser into a User, applying creation logic to it, and register it to the database.
     pub fn create(&self, new_user: NewUser) -> anyhow::Result<User> {
         let mut new_user = new_user.clone();
@@ -258,9 +429,10 @@ impl UserMiddleware {
             new_user.salt = Some(new_salt.as_str());
         }
 
-        let hashed_password = hash(
+        let hashed_password = hash_with_params(
             maybe_clear_password,
             SaltString::new(new_user.salt.unwrap()).unwrap(),
+            &self.get_hash_params(),
         );
 
         if !maybe_clear_password.starts_with('$') {
@@ -272,6 +444,31 @@ impl UserMiddleware {
         Ok(user)
     }
 
+    /// sets a new password for the given user, hashed with a new salt, and register it to the database.
+    pub fn set_password(&self, user: &User, clear_password: &str) -> anyhow::Result<User> {
+        let salt = generate_salt();
+
+        let mut user = user.clone();
+        user.password = hash_with_params(clear_password, salt.clone(), &self.get_hash_params());
+        user.salt = Some(salt.as_str().into());
+
+        self.update(&user)
+    }
+
+    /// changes the password of a user, given its current password.
+    pub fn change_password(
+        &self,
+        user: &User,
+        current_password: &str,
+        new_password: &str,
+    ) -> anyhow::Result<User> {
+        if !password::compare_hashed(current_password, &user.password) {
+            return Err(AuthenticationError::WrongPassword(user.id).into());
+        }
+
+        self.set_password(user, new_password)
+    }
+
     /// updates a User into the database.
     pub fn update(&self, user: &User) -> anyhow::Result<User> {
         self.repository.update(user)
@@ -309,4 +506,121 @@ impl UserMiddleware {
     pub fn to_details_dto(&self, application: &User) -> UserDetailsDTO {
         UserDetailsDTO::from(application)
     }
+
+    /// PRIVATE - gets the roles of a user as stored in the database, e.g. without implied roles.
+    fn get_stored_roles(&self, user: &User) -> anyhow::Result<Vec<String>> {
+        match self.repository.find_one_by_id(user.id)? {
+            Some(stored_user) => Ok(stored_user.roles),
+            None => bail!("User {} not found.", user.id),
+        }
+    }
+
+    /// PRIVATE - checks if JWT claims were issued until a revocation date.
+    ///
+    /// `iat` only has a second precision : tokens issued during the second of the revocation are refused as well.
+    fn is_issued_before(
+        jwt_claims: &JWTClaims<APIClaim>,
+        revoked_date: Option<DateTime<Utc>>,
+    ) -> bool {
+        match (jwt_claims.issued_at, revoked_date) {
+            (_, None) => false,
+            (None, Some(_)) => true,
+            (Some(issued_at), Some(revoked_date)) => {
+                issued_at.as_secs() as i64 <= revoked_date.timestamp()
+            }
+        }
+    }
+
+    /// PRIVATE - gets the argon2 parameters to hash passwords with.
+    fn get_hash_params(&self) -> Params {
+        password::get_hash_params(&self.configuration)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        core::testing::{
+            create_test_application, create_test_user, get_test_configuration, get_test_db_state,
+            get_test_key_store,
+        },
+        domain::repository::jwt_denylist_repository::JWTDenylistRepository,
+    };
+
+    const PASSWORD: &str = "Passw0rd!";
+
+    fn get_middleware() -> UserMiddleware {
+        let db_state = get_test_db_state();
+
+        UserMiddleware::new(
+            UserRepository::new(db_state.clone()),
+            JWTDenylistMiddleware::new(JWTDenylistRepository::new(db_state)),
+            get_test_key_store(),
+            get_test_configuration(),
+        )
+    }
+
+    fn log_in(
+        user_middleware: &UserMiddleware,
+        user: &User,
+        password: &str,
+    ) -> anyhow::Result<User> {
+        user_middleware.authenticate_user_from_input(&LoginInputDTO {
+            login: user.login.clone(),
+            password: password.into(),
+        })
+    }
+
+    #[test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    fn test_login_rehashes_outdated_password_hashes() {
+        let db_state = get_test_db_state();
+        let user_middleware = get_middleware();
+        let mut user = create_test_user(&db_state, &create_test_application(&db_state), &[]);
+
+        let outdated_params = Params::new(32, 1, 1, None).unwrap();
+        user.password = hash_with_params(PASSWORD, generate_salt(), &outdated_params);
+        let user = user_middleware.update(&user).unwrap();
+
+        let hash_params = user_middleware.get_hash_params();
+        assert!(password::needs_rehash(&user.password, &hash_params));
+
+        let logged_user = log_in(&user_middleware, &user, PASSWORD).unwrap();
+        let stored_user = user_middleware
+            .find_one_by_id(&user.id.to_string())
+            .unwrap()
+            .unwrap();
+
+        assert_ne!(stored_user.password, user.password);
+        assert_eq!(stored_user.password, logged_user.password);
+        assert!(!password::needs_rehash(&stored_user.password, &hash_params));
+        assert!(log_in(&user_middleware, &user, PASSWORD).is_ok());
+    }
+
+    #[test]
+    #[ignore = "requires TEST_DATABASE_URL"]
+    fn test_change_password_requires_the_current_password() {
+        let db_state = get_test_db_state();
+        let user_middleware = get_middleware();
+        let user = create_test_user(&db_state, &create_test_application(&db_state), &[]);
+        let user = user_middleware.set_password(&user, PASSWORD).unwrap();
+
+        let error = user_middleware
+            .change_password(&user, "wrong-password", "N3w-Passw0rd!")
+            .unwrap_err();
+
+        assert!(matches!(
+            error.downcast_ref::<AuthenticationError>(),
+            Some(AuthenticationError::WrongPassword(user_id)) if *user_id == user.id
+        ));
+        assert!(log_in(&user_middleware, &user, PASSWORD).is_ok());
+
+        let user = user_middleware
+            .change_password(&user, PASSWORD, "N3w-Passw0rd!")
+            .unwrap();
+
+        assert!(log_in(&user_middleware, &user, PASSWORD).is_err());
+        assert!(log_in(&user_middleware, &user, "N3w-Passw0rd!").is_ok());
+    }
 }
@@ -0,0 +1,8 @@
+DROP INDEX IF EXISTS public.refresh_token_family_idx;
+DROP INDEX IF EXISTS public.refresh_token_token_idx;
+ALTER TABLE public."refresh_token" DROP COLUMN IF EXISTS revoked_date;
+ALTER TABLE public."refresh_token" DROP COLUMN IF EXISTS is_revoked;
+ALTER TABLE public."refresh_token" DROP COLUMN IF EXISTS replaced_by;
+ALTER TABLE public."refresh_token" DROP COLUMN IF EXISTS used_date;
+ALTER TABLE public."refresh_token" DROP COLUMN IF EXISTS is_used;
+ALTER TABLE public."refresh_token" DROP COLUMN IF EXISTS family;
@@ -0,0 +1,12 @@
+ALTER TABLE public."refresh_token" ADD COLUMN family character varying(40) DEFAULT NULL;
+UPDATE public."refresh_token" SET family = CONCAT('legacy-', id) WHERE family IS NULL;
+ALTER TABLE public."refresh_token" ALTER COLUMN family SET NOT NULL;
+ALTER TABLE public."refresh_token" ADD COLUMN is_used boolean NOT NULL DEFAULT false;
+ALTER TABLE public."refresh_token" ADD COLUMN used_date timestamp with time zone DEFAULT NULL;
+ALTER TABLE public."refresh_token" ADD COLUMN replaced_by integer references public."refresh_token"(id) DEFAULT NULL;
+ALTER TABLE public."refresh_token" ADD COLUMN is_revoked boolean NOT NULL DEFAULT false;
+ALTER TABLE public."refresh_token" ADD COLUMN revoked_date timestamp with time zone DEFAULT NULL;
+-- tokens shared by several rows cannot tell which session presents them, they are dropped (their holders log in again).
+DELETE FROM public."refresh_token" WHERE token IN (SELECT token FROM public."refresh_token" GROUP BY token HAVING COUNT(*) > 1);
+CREATE UNIQUE INDEX IF NOT EXISTS refresh_token_token_idx ON public."refresh_token" (token);
+CREATE INDEX IF NOT EXISTS refresh_token_family_idx ON public."refresh_token" (family);
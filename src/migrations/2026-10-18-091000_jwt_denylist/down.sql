@@ -0,0 +1,2 @@
+ALTER TABLE public.users DROP COLUMN IF EXISTS tokens_revoked_date;
+DROP TABLE IF EXISTS public.jwt_denylist;
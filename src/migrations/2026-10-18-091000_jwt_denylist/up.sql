@@ -0,0 +1,13 @@
+CREATE TABLE IF NOT EXISTS public.jwt_denylist (
+    id serial NOT NULL,
+    jti character varying(40) NOT NULL,
+    user_id integer references users(id) NOT NULL,
+    expiration_date timestamp with time zone NOT NULL,
+    created_date timestamp with time zone NOT NULL,
+    CONSTRAINT jwt_denylist_pkey PRIMARY KEY (id),
+    CONSTRAINT jwt_denylist_jti_key UNIQUE (jti)
+);
+CREATE INDEX IF NOT EXISTS jwt_denylist_expiration_date_idx ON public.jwt_denylist (expiration_date);
+
+-- JWTs of a user issued until this date are refused (e.g. after a logout from every session).
+ALTER TABLE public.users ADD COLUMN tokens_revoked_date timestamp with time zone DEFAULT NULL;
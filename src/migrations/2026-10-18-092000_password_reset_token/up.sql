@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS public.password_reset_token (
+    id serial NOT NULL,
+    token_hash character varying(64) NOT NULL,
+    user_id integer references users(id) NOT NULL,
+    expiration_date timestamp with time zone NOT NULL,
+    is_used boolean NOT NULL DEFAULT false,
+    used_date timestamp with time zone,
+    created_date timestamp with time zone NOT NULL,
+    CONSTRAINT password_reset_token_pkey PRIMARY KEY (id),
+    CONSTRAINT password_reset_token_token_hash_key UNIQUE (token_hash)
+);
+CREATE INDEX IF NOT EXISTS password_reset_token_user_id_idx ON public.password_reset_token (user_id);
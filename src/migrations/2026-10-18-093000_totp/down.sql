@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS public.mfa_challenge;
+DROP TABLE IF EXISTS public.recovery_code;
+DROP TABLE IF EXISTS public.user_totp;
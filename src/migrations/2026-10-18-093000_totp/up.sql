@@ -0,0 +1,34 @@
+CREATE TABLE IF NOT EXISTS public.user_totp (
+    id serial NOT NULL,
+    user_id integer references users(id) NOT NULL,
+    secret character varying(128) NOT NULL,
+    is_enabled boolean NOT NULL DEFAULT false,
+    enabled_date timestamp with time zone,
+    last_used_step bigint,
+    created_date timestamp with time zone NOT NULL,
+    CONSTRAINT user_totp_pkey PRIMARY KEY (id),
+    CONSTRAINT user_totp_user_id_key UNIQUE (user_id)
+);
+
+CREATE TABLE IF NOT EXISTS public.recovery_code (
+    id serial NOT NULL,
+    user_id integer references users(id) NOT NULL,
+    code_hash character varying(64) NOT NULL,
+    is_used boolean NOT NULL DEFAULT false,
+    used_date timestamp with time zone,
+    created_date timestamp with time zone NOT NULL,
+    CONSTRAINT recovery_code_pkey PRIMARY KEY (id)
+);
+CREATE INDEX IF NOT EXISTS recovery_code_user_id_idx ON public.recovery_code (user_id);
+
+CREATE TABLE IF NOT EXISTS public.mfa_challenge (
+    id serial NOT NULL,
+    token_hash character varying(64) NOT NULL,
+    user_id integer references users(id) NOT NULL,
+    expiration_date timestamp with time zone NOT NULL,
+    attempts integer NOT NULL DEFAULT 0,
+    is_used boolean NOT NULL DEFAULT false,
+    created_date timestamp with time zone NOT NULL,
+    CONSTRAINT mfa_challenge_pkey PRIMARY KEY (id),
+    CONSTRAINT mfa_challenge_token_hash_key UNIQUE (token_hash)
+);
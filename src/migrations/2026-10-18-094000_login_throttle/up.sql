@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS public.login_throttle (
+    id serial NOT NULL,
+    scope character varying(10) NOT NULL,
+    identifier character varying(255) NOT NULL,
+    failure_count integer NOT NULL DEFAULT 0,
+    last_failure_date timestamp with time zone NOT NULL,
+    locked_until timestamp with time zone,
+    CONSTRAINT login_throttle_pkey PRIMARY KEY (id),
+    CONSTRAINT login_throttle_scope_identifier_key UNIQUE (scope, identifier)
+);
@@ -0,0 +1,16 @@
+CREATE TABLE IF NOT EXISTS public.api_key (
+    id serial NOT NULL,
+    application_id integer references application(id) NOT NULL,
+    name character varying(255) NOT NULL,
+    prefix character varying(16) NOT NULL,
+    key_hash character varying(64) NOT NULL,
+    scopes text[] NOT NULL DEFAULT '{}',
+    expiration_date timestamp with time zone,
+    last_used_date timestamp with time zone,
+    is_revoked boolean NOT NULL DEFAULT false,
+    revoked_date timestamp with time zone,
+    created_date timestamp with time zone NOT NULL,
+    CONSTRAINT api_key_pkey PRIMARY KEY (id),
+    CONSTRAINT api_key_prefix_key UNIQUE (prefix)
+);
+CREATE INDEX IF NOT EXISTS api_key_application_id_idx ON public.api_key (application_id);
@@ -0,0 +1,5 @@
+DELETE FROM public.jwt_denylist WHERE user_id IS NULL;
+ALTER TABLE public.jwt_denylist ALTER COLUMN user_id SET NOT NULL;
+
+ALTER TABLE public."refresh_token" DROP COLUMN IF EXISTS client_id;
+DROP TABLE IF EXISTS public.oauth_client;
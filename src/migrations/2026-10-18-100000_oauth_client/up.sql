@@ -0,0 +1,20 @@
+CREATE TABLE IF NOT EXISTS public.oauth_client (
+    id serial NOT NULL,
+    application_id integer references application(id) NOT NULL,
+    name character varying(255) NOT NULL,
+    client_id character varying(26) NOT NULL,
+    client_secret_hash character varying(64) NOT NULL,
+    scopes text[] NOT NULL DEFAULT '{}',
+    is_revoked boolean NOT NULL DEFAULT false,
+    revoked_date timestamp with time zone,
+    created_date timestamp with time zone NOT NULL,
+    CONSTRAINT oauth_client_pkey PRIMARY KEY (id),
+    CONSTRAINT oauth_client_client_id_key UNIQUE (client_id)
+);
+CREATE INDEX IF NOT EXISTS oauth_client_application_id_idx ON public.oauth_client (application_id);
+
+-- refresh tokens issued to an OAuth client are only accepted from that client, session ones have no client.
+ALTER TABLE public."refresh_token" ADD COLUMN client_id character varying(26) references public.oauth_client(client_id) DEFAULT NULL;
+
+-- tokens issued to OAuth clients through the client_credentials grant have no user.
+ALTER TABLE public.jwt_denylist ALTER COLUMN user_id DROP NOT NULL;
@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS public.role (
+    id serial NOT NULL,
+    application_id integer references application(id) NOT NULL,
+    name character varying(100) NOT NULL,
+    implied_roles text[] NOT NULL DEFAULT '{}',
+    description character varying(255),
+    created_date timestamp with time zone NOT NULL,
+    CONSTRAINT role_pkey PRIMARY KEY (id),
+    CONSTRAINT role_application_id_name_key UNIQUE (application_id, name)
+);
@@ -0,0 +1,16 @@
+CREATE TABLE IF NOT EXISTS public.security_audit_log (
+    id serial NOT NULL,
+    user_id integer,
+    api_key_id integer,
+    application_id integer NOT NULL,
+    subject character varying(100) NOT NULL,
+    access_right character varying(100) NOT NULL,
+    context jsonb NOT NULL DEFAULT '{}',
+    voter character varying(100),
+    outcome character varying(10) NOT NULL,
+    reason text,
+    created_date timestamp with time zone NOT NULL,
+    CONSTRAINT security_audit_log_pkey PRIMARY KEY (id)
+);
+
+CREATE INDEX IF NOT EXISTS security_audit_log_application_id_created_date_idx ON public.security_audit_log (application_id, created_date);
@@ -0,0 +1,13 @@
+CREATE TABLE IF NOT EXISTS public.impersonation_log (
+    id serial NOT NULL,
+    impersonator_id integer references users(id) NOT NULL,
+    impersonated_id integer references users(id) NOT NULL,
+    application_id integer references application(id) NOT NULL,
+    jti character varying(26) NOT NULL,
+    reason character varying(255),
+    expiration_date timestamp with time zone NOT NULL,
+    created_date timestamp with time zone NOT NULL,
+    CONSTRAINT impersonation_log_pkey PRIMARY KEY (id)
+);
+
+CREATE INDEX IF NOT EXISTS impersonation_log_impersonated_id_idx ON public.impersonation_log (impersonated_id);
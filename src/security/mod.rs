@@ -1 +1,3 @@
+#[cfg(test)]
+pub mod testing;
 pub mod voters;
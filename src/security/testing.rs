@@ -0,0 +1,150 @@
+use std::collections::HashMap;
+
+use chrono::Utc;
+
+use crate::{
+    core::{
+        guards::connected_application::ConnectedApplication,
+        role_hierarchy::{RoleHierarchy, DEFAULT_ROLE_HIERARCHY},
+        security::{AccessDecision, SecurityVoter},
+    },
+    domain::model::{api_key::ApiKey, application::Application, user::User},
+};
+
+/// ID of the user voters are tested with, so contexts can refer to it.
+pub const TEST_USER_ID: i32 = 1;
+
+/// application ID of the user voters are tested with, so contexts can refer to it.
+pub const TEST_APPLICATION_ID: i32 = 1;
+
+/// The expected decision of a voter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Expected {
+    /// the voter grants the access.
+    Allow,
+    /// the voter refuses the access.
+    Deny,
+    /// the voter cannot decide (e.g. unknown right, missing context).
+    Error,
+}
+
+/// A row of a voter decision matrix.
+pub struct VoterCase<'c> {
+    /// roles of the user, resolved with the default role hierarchy.
+    pub roles: &'c [&'c str],
+    pub right: &'c str,
+    pub context: Option<HashMap<&'c str, String>>,
+    pub expected: Expected,
+}
+
+impl<'c> VoterCase<'c> {
+    /// constructor.
+    pub fn new(roles: &'c [&'c str], right: &'c str, expected: Expected) -> Self {
+        Self {
+            roles,
+            right,
+            context: None,
+            expected,
+        }
+    }
+
+    /// sets the context given to the voter.
+    pub fn with_context(mut self, context: HashMap<&'c str, String>) -> Self {
+        self.context = Some(context);
+
+        self
+    }
+}
+
+/// asserts a voter takes the expected decision for every case, reporting every mismatch at once.
+///
+/// ```rust
+/// assert_voter_matrix(
+///     &UserSecurityVoter::default(),
+///     vec![
+///         VoterCase::new(&["ROLE_USER"], "list", Expected::Allow),
+///         VoterCase::new(&["ROLE_USER"], "delete", Expected::Deny),
+///     ],
+/// );
+/// ```
+pub fn assert_voter_matrix<'a>(voter: &dyn SecurityVoter<'a>, cases: Vec<VoterCase>) {
+    let hierarchy = RoleHierarchy::parse(DEFAULT_ROLE_HIERARCHY).unwrap();
+
+    let failures = cases
+        .into_iter()
+        .filter_map(|case| {
+            let roles = case
+                .roles
+                .iter()
+                .map(|role| role.to_string())
+                .collect::<Vec<String>>();
+
+            let user = User {
+                id: TEST_USER_ID,
+                application_id: TEST_APPLICATION_ID,
+                roles: hierarchy.resolve(&roles),
+                ..Default::default()
+            };
+
+            let description = format!(
+                "{}:{} for {:?} (context: {:?})",
+                voter.supports(),
+                case.right,
+                case.roles,
+                case.context
+            );
+
+            let actual = match voter.has_access(case.right, &user, case.context) {
+                Ok(AccessDecision::Granted) => Expected::Allow,
+                Ok(AccessDecision::Denied(_)) => Expected::Deny,
+                Err(_) => Expected::Error,
+            };
+
+            if actual == case.expected {
+                return None;
+            }
+
+            Some(format!(
+                "{description} : expected {:?}, got {actual:?}",
+                case.expected
+            ))
+        })
+        .collect::<Vec<String>>();
+
+    assert!(
+        failures.is_empty(),
+        "{} took unexpected decisions :\n{}",
+        voter.get_name(),
+        failures.join("\n")
+    );
+}
+
+/// gets an application of the given ID, authenticated with an API key granted the given scopes.
+pub fn get_test_application(application_id: i32, scopes: &[&str]) -> ConnectedApplication {
+    let scopes = scopes
+        .iter()
+        .map(|scope| scope.to_string())
+        .collect::<Vec<String>>();
+
+    ConnectedApplication {
+        application: Application {
+            id: application_id,
+            ..Default::default()
+        },
+        api_key: Some(ApiKey {
+            id: 1,
+            application_id,
+            name: "test".into(),
+            prefix: "test".into(),
+            key_hash: String::new(),
+            scopes: scopes.clone(),
+            expiration_date: None,
+            last_used_date: None,
+            is_revoked: false,
+            revoked_date: None,
+            created_date: Utc::now(),
+        }),
+        client_id: None,
+        scopes,
+    }
+}
@@ -1,16 +1,25 @@
 use crate::{
-    core::security::{is_user, SecurityVoter},
+    core::{
+        guards::granted::SecuritySubject,
+        security::{is_super_admin, is_user, AccessDecision, AccessError, SecurityVoter},
+    },
     domain::model::user::User,
+    security_subject,
 };
-use anyhow::{bail, Result};
 use std::collections::HashMap;
 
+security_subject!(AccountSubject, "account");
+
 #[derive(Default)]
 pub struct AccountSecurityVoter {}
 
 impl<'a> SecurityVoter<'a> for AccountSecurityVoter {
     fn supports(&self) -> &'a str {
-        "account"
+        AccountSubject::NAME
+    }
+
+    fn get_rights(&self) -> Vec<&'a str> {
+        vec!["list", "details", "create", "update", "delete", "restore"]
     }
 
     fn has_access(
@@ -18,14 +27,34 @@ impl<'a> SecurityVoter<'a> for AccountSecurityVoter {
         right: &str,
         user: &User,
         _context: Option<HashMap<&str, String>>,
-    ) -> Result<bool> {
+    ) -> Result<AccessDecision, AccessError> {
         match right {
-            "list" => Ok(is_user(user)),
-            "details" => Ok(is_user(user)),
-            _ => bail!(
-                "No right \"{right}\" found for subject \"{}\"",
-                self.supports()
-            ),
+            "list" => Ok(is_user(user).into()),
+            "details" => Ok(is_user(user).into()),
+            "create" | "update" | "delete" | "restore" => Ok(is_super_admin(user).into()),
+            _ => Err(self.unknown_right(right)),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::testing::{assert_voter_matrix, Expected, VoterCase};
+
+    #[test]
+    fn test_account_voter_matrix() {
+        assert_voter_matrix(
+            &AccountSecurityVoter::default(),
+            vec![
+                VoterCase::new(&["ROLE_USER"], "list", Expected::Allow),
+                VoterCase::new(&["ROLE_USER"], "create", Expected::Deny),
+                VoterCase::new(&["ROLE_USER_ADMIN"], "update", Expected::Deny),
+                VoterCase::new(&["ROLE_SUPER_ADMIN"], "create", Expected::Allow),
+                VoterCase::new(&["ROLE_SUPER_ADMIN"], "delete", Expected::Allow),
+                VoterCase::new(&["ROLE_SUPER_ADMIN"], "restore", Expected::Allow),
+                VoterCase::new(&["ROLE_SUPER_ADMIN"], "unknown", Expected::Error),
+            ],
+        );
+    }
+}
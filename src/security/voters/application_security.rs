@@ -1,17 +1,25 @@
 use crate::{
-    core::security::{is_user, SecurityVoter},
+    core::{
+        guards::granted::SecuritySubject,
+        security::{is_super_admin, is_user, AccessDecision, AccessError, SecurityVoter},
+    },
     domain::model::user::User,
+    security_subject,
 };
-use anyhow::bail;
-use anyhow::Result;
 use std::collections::HashMap;
 
+security_subject!(ApplicationSubject, "application");
+
 #[derive(Default)]
 pub struct ApplicationSecurityVoter {}
 
 impl<'a> SecurityVoter<'a> for ApplicationSecurityVoter {
     fn supports(&self) -> &'a str {
-        "application"
+        ApplicationSubject::NAME
+    }
+
+    fn get_rights(&self) -> Vec<&'a str> {
+        vec!["list", "details", "create", "update", "delete", "restore"]
     }
 
     fn has_access(
@@ -19,14 +27,38 @@ impl<'a> SecurityVoter<'a> for ApplicationSecurityVoter {
         right: &str,
         user: &User,
         _context: Option<HashMap<&str, String>>,
-    ) -> Result<bool> {
+    ) -> Result<AccessDecision, AccessError> {
         match right {
-            "list" => Ok(is_user(user)),
-            "details" => Ok(is_user(user)),
-            _ => bail!(
-                "No right \"{right}\" found for subject \"{}\"",
-                self.supports()
-            ),
+            "list" => Ok(is_user(user).into()),
+            "details" => Ok(is_user(user).into()),
+            "create" | "update" | "delete" | "restore" => Ok(is_super_admin(user).into()),
+            _ => Err(self.unknown_right(right)),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::testing::{assert_voter_matrix, Expected, VoterCase};
+
+    #[test]
+    fn test_application_voter_matrix() {
+        assert_voter_matrix(
+            &ApplicationSecurityVoter::default(),
+            vec![
+                VoterCase::new(&["ROLE_USER"], "list", Expected::Allow),
+                VoterCase::new(&["ROLE_USER"], "details", Expected::Allow),
+                VoterCase::new(&[], "list", Expected::Deny),
+                VoterCase::new(&["ROLE_USER"], "create", Expected::Deny),
+                VoterCase::new(&["ROLE_USER_ADMIN"], "update", Expected::Deny),
+                VoterCase::new(&["ROLE_USER_ADMIN"], "delete", Expected::Deny),
+                VoterCase::new(&["ROLE_SUPER_ADMIN"], "create", Expected::Allow),
+                VoterCase::new(&["ROLE_SUPER_ADMIN"], "update", Expected::Allow),
+                VoterCase::new(&["ROLE_SUPER_ADMIN"], "delete", Expected::Allow),
+                VoterCase::new(&["ROLE_SUPER_ADMIN"], "restore", Expected::Allow),
+                VoterCase::new(&["ROLE_SUPER_ADMIN"], "unknown", Expected::Error),
+            ],
+        );
+    }
+}
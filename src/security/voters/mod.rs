@@ -1,3 +1,4 @@
 pub mod account_security;
 pub mod application_security;
+pub mod role_security;
 pub mod user_security;
@@ -0,0 +1,38 @@
+use crate::{
+    core::{
+        guards::granted::SecuritySubject,
+        security::{is_admin, is_user, AccessDecision, AccessError, SecurityVoter},
+    },
+    domain::model::user::User,
+    security_subject,
+};
+use std::collections::HashMap;
+
+security_subject!(RoleSubject, "role");
+
+#[derive(Default)]
+pub struct RoleSecurityVoter {}
+
+impl<'a> SecurityVoter<'a> for RoleSecurityVoter {
+    fn supports(&self) -> &'a str {
+        RoleSubject::NAME
+    }
+
+    fn get_rights(&self) -> Vec<&'a str> {
+        vec!["list", "create", "delete"]
+    }
+
+    fn has_access(
+        &self,
+        right: &str,
+        user: &User,
+        _context: Option<HashMap<&str, String>>,
+    ) -> Result<AccessDecision, AccessError> {
+        match right {
+            "list" => Ok(is_user(user).into()),
+            "create" => Ok(is_admin(user).into()),
+            "delete" => Ok(is_admin(user).into()),
+            _ => Err(self.unknown_right(right)),
+        }
+    }
+}
@@ -1,19 +1,36 @@
 use std::collections::HashMap;
 
-use anyhow::bail;
-use anyhow::Result;
-
 use crate::{
-    core::security::{is_admin, is_user, SecurityVoter},
+    core::{
+        guards::{connected_application::ConnectedApplication, granted::SecuritySubject},
+        security::{is_admin, is_user, AccessDecision, AccessError, SecurityVoter},
+    },
     domain::model::user::User,
+    security_right, security_subject,
 };
 
+security_subject!(UserSubject, "user");
+security_right!(RolesRight, "roles");
+security_right!(ImpersonateRight, "impersonate");
+
 #[derive(Default)]
 pub struct UserSecurityVoter {}
 
 impl<'a> SecurityVoter<'a> for UserSecurityVoter {
     fn supports(&self) -> &'a str {
-        "user"
+        UserSubject::NAME
+    }
+
+    fn get_rights(&self) -> Vec<&'a str> {
+        vec![
+            "list",
+            "details",
+            "create",
+            "update",
+            "delete",
+            "roles",
+            "impersonate",
+        ]
     }
 
     fn has_access(
@@ -21,33 +38,129 @@ impl<'a> SecurityVoter<'a> for UserSecurityVoter {
         right: &str,
         user: &User,
         context: Option<HashMap<&str, String>>,
-    ) -> Result<bool> {
+    ) -> Result<AccessDecision, AccessError> {
         match right {
-            "list" => Ok(is_user(user)),
-            "details" => Ok(is_user(user)),
-            "create" => Ok(is_admin(user)),
+            "list" => Ok(is_user(user).into()),
+            "details" => Ok(is_user(user).into()),
+            "create" => Ok(is_admin(user).into()),
             "update" => {
-                let updated_id = self.get_context(context, "updated_id");
-
-                if updated_id.is_none() {
-                    bail!("No \"updated_id\" context provided to security voter.");
-                }
-
-                let updated_id = updated_id.unwrap().parse::<i32>().unwrap();
+                let updated_id = self
+                    .get_required_context(context, "updated_id")?
+                    .parse::<i32>()
+                    .map_err(|error| {
+                        AccessError::InvalidContext("updated_id".into(), error.to_string())
+                    })?;
 
                 let same_user_for_standard_user = user.id == updated_id;
 
                 if !is_admin(user) && !same_user_for_standard_user {
-                    bail!("You cannot update a user that is not you.");
+                    return Ok(AccessDecision::deny(
+                        "You cannot update a user that is not you.",
+                    ));
                 }
 
-                Ok(is_admin(user) || same_user_for_standard_user)
+                Ok(AccessDecision::Granted)
             }
-            "delete" => Ok(is_admin(user)),
-            _ => bail!(
-                "No right \"{right}\" found for subject \"{}\"",
-                self.supports()
+            "delete" => Ok(is_admin(user).into()),
+            "roles" => Ok(is_admin(user).into()),
+            "impersonate" => Ok(is_admin(user).into()),
+            _ => Err(self.unknown_right(right)),
+        }
+    }
+
+    /// applications can only list their own users (e.g. the `user_list_for_application` route is restricted to them).
+    fn has_application_access(
+        &self,
+        right: &str,
+        application: &ConnectedApplication,
+        _context: Option<HashMap<&str, String>>,
+    ) -> Result<AccessDecision, AccessError> {
+        match right {
+            "list" => Ok(application.has_scope(self.supports(), right).into()),
+            "details" | "create" | "update" | "delete" | "roles" | "impersonate" => Ok(
+                AccessDecision::deny("This resource cannot be accessed with an API key."),
             ),
+            _ => Err(self.unknown_right(right)),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use map_macro::hash_map;
+
+    use super::*;
+    use crate::security::{
+        testing::{
+            assert_voter_matrix, get_test_application, Expected, VoterCase, TEST_APPLICATION_ID,
+            TEST_USER_ID,
+        },
+        voters::account_security::AccountSecurityVoter,
+    };
+
+    #[test]
+    fn test_user_voter_matrix() {
+        let other_user_id = (TEST_USER_ID + 1).to_string();
+
+        assert_voter_matrix(
+            &UserSecurityVoter::default(),
+            vec![
+                VoterCase::new(&["ROLE_USER"], "list", Expected::Allow),
+                VoterCase::new(&[], "list", Expected::Deny),
+                VoterCase::new(&["ROLE_USER"], "create", Expected::Deny),
+                VoterCase::new(&["ROLE_USER_ADMIN"], "create", Expected::Allow),
+                VoterCase::new(&["ROLE_SUPER_ADMIN"], "delete", Expected::Allow),
+                VoterCase::new(&["ROLE_USER"], "roles", Expected::Deny),
+                VoterCase::new(&["ROLE_USER"], "impersonate", Expected::Deny),
+                VoterCase::new(&["ROLE_USER_ADMIN"], "impersonate", Expected::Allow),
+                VoterCase::new(&["ROLE_USER"], "update", Expected::Allow)
+                    .with_context(hash_map! { "updated_id" => TEST_USER_ID.to_string() }),
+                VoterCase::new(&["ROLE_USER"], "update", Expected::Deny)
+                    .with_context(hash_map! { "updated_id" => other_user_id.clone() }),
+                VoterCase::new(&["ROLE_USER_ADMIN"], "update", Expected::Allow)
+                    .with_context(hash_map! { "updated_id" => other_user_id }),
+                VoterCase::new(&["ROLE_USER_ADMIN"], "update", Expected::Error),
+                VoterCase::new(&["ROLE_USER_ADMIN"], "update", Expected::Error)
+                    .with_context(hash_map! { "updated_id" => "me".to_string() }),
+                VoterCase::new(&["ROLE_USER_ADMIN"], "unknown", Expected::Error),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_user_voter_application_access() {
+        let voter = UserSecurityVoter::default();
+        let granted = |right: &str, scopes: &[&str]| {
+            voter
+                .has_application_access(
+                    right,
+                    &get_test_application(TEST_APPLICATION_ID, scopes),
+                    None,
+                )
+                .unwrap()
+                .is_granted()
+        };
+
+        assert!(granted("list", &["user:list"]));
+        assert!(granted("list", &["*"]));
+        assert!(!granted("list", &["account:*"]));
+        assert!(!granted("delete", &["*"]));
+        assert!(!granted("roles", &["*"]));
+        assert!(voter
+            .has_application_access(
+                "unknown",
+                &get_test_application(TEST_APPLICATION_ID, &["*"]),
+                None
+            )
+            .is_err());
+        // voters which do not opt in deny API keys, whatever their scopes.
+        assert!(!AccountSecurityVoter::default()
+            .has_application_access(
+                "list",
+                &get_test_application(TEST_APPLICATION_ID, &["*"]),
+                None
+            )
+            .unwrap()
+            .is_granted());
+    }
+}